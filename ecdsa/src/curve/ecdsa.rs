@@ -1,8 +1,11 @@
-use plonky2::field::types::{Field, Sample};
+use num::Integer;
+use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
+use plonky2::field::types::{Field, PrimeField, Sample};
 use serde::{Deserialize, Serialize};
 
 use crate::curve::curve_msm::msm_parallel;
-use crate::curve::curve_types::{base_to_scalar, AffinePoint, Curve, CurveScalar};
+use crate::curve::curve_types::{base_to_scalar, scalar_to_base, AffinePoint, Curve, CurveScalar};
+use crate::curve::secp256k1::{Secp256K1, Secp256K1Affine};
 
 #[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct ECDSASignature<C: Curve> {
@@ -61,14 +64,59 @@ pub fn verify_message<C: Curve>(
     r == x
 }
 
+/// Recovers the public key whose signature of `msg_hash` is `(r, s)`, as Ethereum's `ecrecover`
+/// does. `recovery_id` is the parity of the y coordinate of the nonce point `R`; the ids 2 and 3,
+/// for which `R.x = r + n`, are not supported. Signatures with `s > n / 2` are rejected as
+/// malleable, per EIP-2.
+pub fn ecrecover(
+    msg_hash: &[u8; 32],
+    r: Secp256K1Scalar,
+    s: Secp256K1Scalar,
+    recovery_id: u8,
+) -> Option<Secp256K1Affine> {
+    let half_order = Secp256K1Scalar::order() >> 1;
+    if r.is_zero() || s.is_zero() || s.to_canonical_biguint() > half_order || recovery_id > 1 {
+        return None;
+    }
+
+    // Reconstruct `R` from its x coordinate and the parity of its y coordinate.
+    let x = scalar_to_base::<Secp256K1>(r);
+    let y = (x.cube() + Secp256K1::B).sqrt()?;
+    let y = if y.to_canonical_biguint().is_odd() == (recovery_id == 1) {
+        y
+    } else {
+        -y
+    };
+    let rr = AffinePoint::nonzero(x, y).to_projective();
+
+    // The public key is `r^-1 (s R - h G)`.
+    let h = Secp256K1Scalar::from_bytes_reduce(msg_hash);
+    let r_inv = r.inverse();
+    let point =
+        CurveScalar(s * r_inv) * rr + CurveScalar(-h * r_inv) * Secp256K1::GENERATOR_PROJECTIVE;
+    let point = point.to_affine();
+    (!point.zero).then_some(point)
+}
+
 #[cfg(test)]
 mod tests {
+    use num::BigUint;
     use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
-    use plonky2::field::types::Sample;
+    use plonky2::field::types::{Field, PrimeField, Sample};
 
-    use crate::curve::ecdsa::{sign_message, verify_message, ECDSASecretKey};
+    use crate::curve::ecdsa::{ecrecover, sign_message, verify_message, ECDSASecretKey};
     use crate::curve::secp256k1::Secp256K1;
 
+    fn hex_bytes(hex: &str) -> [u8; 32] {
+        core::array::from_fn(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+    }
+
+    fn decimal_scalar(decimal: &str) -> Secp256K1Scalar {
+        Secp256K1Scalar::from_noncanonical_biguint(
+            BigUint::parse_bytes(decimal.as_bytes(), 10).unwrap(),
+        )
+    }
+
     #[test]
     fn test_ecdsa_native() {
         type C = Secp256K1;
@@ -81,4 +129,52 @@ mod tests {
         let result = verify_message(msg, sig, pk);
         assert!(result);
     }
+
+    #[test]
+    fn test_ecrecover_eip155() {
+        // The example transaction of EIP-155, signed with the private key 0x4646...46.
+        let sk = ECDSASecretKey::<Secp256K1>(Secp256K1Scalar::from_bytes_reduce(&[0x46; 32]));
+        let msg_hash =
+            hex_bytes("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+        let r = decimal_scalar(
+            "18515461264373351373200002665853028612451056578545711640558177340181847433846",
+        );
+        let s = decimal_scalar(
+            "46948507304638947509940763649030358759909902576025900602547168820602576006531",
+        );
+        // v = 37 = 35 + 2 * chain_id + recovery_id, with chain ID 1.
+        assert_eq!(ecrecover(&msg_hash, r, s, 0), Some(sk.to_public().0));
+        assert_ne!(ecrecover(&msg_hash, r, s, 1), Some(sk.to_public().0));
+
+        // The malleable counterpart `(r, n - s)` is rejected.
+        assert_eq!(ecrecover(&msg_hash, r, -s, 1), None);
+        // So are zero components and unsupported recovery ids.
+        assert_eq!(ecrecover(&msg_hash, Secp256K1Scalar::ZERO, s, 0), None);
+        assert_eq!(ecrecover(&msg_hash, r, Secp256K1Scalar::ZERO, 0), None);
+        assert_eq!(ecrecover(&msg_hash, r, s, 2), None);
+    }
+
+    #[test]
+    fn test_ecrecover_random() {
+        type C = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+        let sig = sign_message(msg, sk);
+
+        // `sign_message` doesn't normalize `s`, and negating it flips the parity of `R`.
+        let half_order = Secp256K1Scalar::order() >> 1;
+        let s = if sig.s.to_canonical_biguint() > half_order {
+            -sig.s
+        } else {
+            sig.s
+        };
+        let mut msg_hash = [0; 32];
+        let msg_bytes = msg.to_canonical_biguint().to_bytes_be();
+        msg_hash[32 - msg_bytes.len()..].copy_from_slice(&msg_bytes);
+
+        let recovered = [0, 1].map(|recovery_id| ecrecover(&msg_hash, sig.r, s, recovery_id));
+        assert!(recovered.contains(&Some(pk.0)));
+    }
 }
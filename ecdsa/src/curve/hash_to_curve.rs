@@ -0,0 +1,156 @@
+//! Hashing to the base field of secp256k1 and to its points, with a choice of hash function, e.g.
+//! to derive challenges or generators in a Fiat-Shamir transcript over secp256k1.
+
+use alloc::vec::Vec;
+use core::iter::once;
+
+use num::{BigUint, Integer};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::secp256k1_base::Secp256K1Base;
+use plonky2::field::types::{Field, PrimeField};
+use plonky2::plonk::config::{GenericHashOut, Hasher};
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::curve::secp256k1::{Secp256K1, Secp256K1Affine};
+
+/// The number of hash output bytes reduced into a field element, 128 bits more than the size of
+/// `Secp256K1Base`, so that the result is statistically close to uniform.
+const FIELD_HASH_BYTES: usize = 48;
+
+/// A hash function from bytes to bytes, used by `hash_to_field` and `hash_to_curve`. Hash
+/// functions without a `Hasher`, such as SHA-256, can be plugged in by implementing this trait.
+pub trait FieldHash {
+    fn hash_bytes(input: &[u8]) -> Vec<u8>;
+}
+
+/// Any of the Goldilocks hashers, such as `PoseidonHash` or `KeccakHash<32>`, hashes the length
+/// of the input followed by the input itself, packed into field elements seven bytes at a time.
+impl<H: Hasher<GoldilocksField>> FieldHash for H {
+    fn hash_bytes(input: &[u8]) -> Vec<u8> {
+        let elements = once(GoldilocksField::from_canonical_usize(input.len()))
+            .chain(input.chunks(7).map(|chunk| {
+                let mut bytes = [0; 8];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                GoldilocksField::from_canonical_u64(u64::from_le_bytes(bytes))
+            }))
+            .collect::<Vec<_>>();
+        GenericHashOut::<GoldilocksField>::to_bytes(&H::hash_no_pad(&elements))
+    }
+}
+
+/// Hashes `input` to an element of `Secp256K1Base`. The digests of `input` followed by a block
+/// index are concatenated until there are `FIELD_HASH_BYTES` of them, which are then reduced.
+pub fn hash_to_field<H: FieldHash>(input: &[u8]) -> Secp256K1Base {
+    let mut message = input.to_vec();
+    message.push(0);
+    let mut bytes = Vec::new();
+    for block in 0..=u8::MAX {
+        *message.last_mut().unwrap() = block;
+        bytes.extend(H::hash_bytes(&message));
+        if bytes.len() >= FIELD_HASH_BYTES {
+            return Secp256K1Base::from_noncanonical_biguint(BigUint::from_bytes_le(&bytes));
+        }
+    }
+    panic!("FieldHash output too short");
+}
+
+/// Hashes `input` to a point of secp256k1, by try-and-increment: the x coordinate is the first
+/// of `hash_to_field(input || counter)`, for a little-endian `u32` counter, for which
+/// `x^3 + 7` is a square, and y is its even square root. Each attempt succeeds with probability
+/// about 1/2. The number of attempts depends on the input, so this isn't constant-time.
+pub fn hash_to_curve<H: FieldHash>(input: &[u8]) -> Secp256K1Affine {
+    let mut message = input.to_vec();
+    message.extend([0; 4]);
+    let counter_start = input.len();
+    for counter in 0..=u32::MAX {
+        message[counter_start..].copy_from_slice(&counter.to_le_bytes());
+        let x = hash_to_field::<H>(&message);
+        if let Some(y) = (x.cube() + Secp256K1::A * x + Secp256K1::B).sqrt() {
+            // secp256k1 has no point with y = 0, so exactly one of the roots is even.
+            let y = if y.to_canonical_biguint().is_even() {
+                y
+            } else {
+                -y
+            };
+            return AffinePoint::nonzero(x, y);
+        }
+    }
+    panic!("No point found for any counter");
+}
+
+#[cfg(test)]
+mod tests {
+    use num::Integer;
+    use plonky2::field::types::PrimeField;
+    use plonky2::hash::keccak::KeccakHash;
+    use plonky2::hash::poseidon::PoseidonHash;
+
+    use crate::curve::hash_to_curve::{hash_to_curve, hash_to_field, FieldHash};
+
+    fn inputs() -> Vec<Vec<u8>> {
+        vec![
+            vec![],
+            vec![0],
+            b"secp256k1".to_vec(),
+            (0..=u8::MAX).collect(),
+        ]
+    }
+
+    fn check_on_curve<H: FieldHash>() {
+        for input in inputs() {
+            let point = hash_to_curve::<H>(&input);
+            assert!(!point.zero);
+            assert!(point.is_valid());
+            assert!(point.y.to_canonical_biguint().is_even());
+        }
+    }
+
+    #[test]
+    fn test_hash_to_curve_on_curve() {
+        check_on_curve::<PoseidonHash>();
+        check_on_curve::<KeccakHash<25>>();
+        check_on_curve::<KeccakHash<32>>();
+    }
+
+    #[test]
+    fn test_hash_to_curve_deterministic() {
+        for input in inputs() {
+            assert_eq!(
+                hash_to_curve::<PoseidonHash>(&input),
+                hash_to_curve::<PoseidonHash>(&input)
+            );
+            // The backend determines the point.
+            assert_ne!(
+                hash_to_curve::<PoseidonHash>(&input),
+                hash_to_curve::<KeccakHash<32>>(&input)
+            );
+        }
+
+        // Distinct inputs give distinct points, including inputs differing only in length.
+        let points = inputs()
+            .iter()
+            .map(|input| hash_to_curve::<PoseidonHash>(input))
+            .collect::<Vec<_>>();
+        for (i, p) in points.iter().enumerate() {
+            for q in &points[i + 1..] {
+                assert_ne!(p, q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_to_field_deterministic() {
+        assert_eq!(
+            hash_to_field::<PoseidonHash>(b"abc"),
+            hash_to_field::<PoseidonHash>(b"abc")
+        );
+        assert_ne!(
+            hash_to_field::<PoseidonHash>(b"abc"),
+            hash_to_field::<PoseidonHash>(b"abd")
+        );
+        assert_ne!(
+            hash_to_field::<PoseidonHash>(b""),
+            hash_to_field::<PoseidonHash>(&[0])
+        );
+    }
+}
@@ -5,4 +5,5 @@ pub mod curve_summation;
 pub mod curve_types;
 pub mod ecdsa;
 pub mod glv;
+pub mod hash_to_curve;
 pub mod secp256k1;
@@ -3,7 +3,7 @@ use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
 use plonky2::field::types::Field;
 use serde::{Deserialize, Serialize};
 
-use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::curve::curve_types::{AffinePoint, Curve, ProjectivePoint};
 
 #[derive(Debug, Copy, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Secp256K1;
@@ -24,6 +24,12 @@ impl Curve for Secp256K1 {
     };
 }
 
+/// A point of secp256k1, i.e. on the curve `y^2 = x^3 + 7`, in affine coordinates.
+pub type Secp256K1Affine = AffinePoint<Secp256K1>;
+
+/// A point of secp256k1 in projective coordinates.
+pub type Secp256K1Projective = ProjectivePoint<Secp256K1>;
+
 // 55066263022277343669578718895168534326250603453777594175500187360389116729240
 const SECP256K1_GENERATOR_X: Secp256K1Base = Secp256K1Base([
     0x59F2815B16F81798,
@@ -47,7 +53,7 @@ mod tests {
     use plonky2::field::types::{Field, PrimeField};
 
     use crate::curve::curve_types::{AffinePoint, Curve, ProjectivePoint};
-    use crate::curve::secp256k1::Secp256K1;
+    use crate::curve::secp256k1::{Secp256K1, Secp256K1Affine, Secp256K1Projective};
 
     #[test]
     fn test_generator() {
@@ -62,6 +68,42 @@ mod tests {
         assert!(neg_g.is_valid());
     }
 
+    #[test]
+    fn test_double() {
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        let two = Secp256K1Scalar::TWO;
+        assert_eq!(g + g, g.double());
+        assert_eq!(g + g, Secp256K1::convert(two) * g);
+        assert_eq!((g + g).to_affine(), Secp256K1::GENERATOR_AFFINE.double());
+    }
+
+    #[test]
+    fn test_generator_order() {
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        // `n * G` is the identity, i.e. `(n - 1) * G + G = O`, where `n - 1` is -1 in the scalar
+        // field.
+        let n_minus_one = Secp256K1::convert(Secp256K1Scalar::NEG_ONE) * g;
+        assert_eq!(n_minus_one, -g);
+        assert_eq!(n_minus_one + g, Secp256K1Projective::ZERO);
+    }
+
+    #[test]
+    fn test_projective_affine_round_trip() {
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        for k in [1, 2, 12345, u64::MAX] {
+            let point = Secp256K1::convert(Secp256K1Scalar::from_canonical_u64(k)) * g;
+            let affine: Secp256K1Affine = point.to_affine();
+            assert!(affine.is_valid());
+            assert_eq!(affine.to_projective(), point);
+            assert_eq!(affine.to_projective().to_affine(), affine);
+        }
+        assert_eq!(
+            Secp256K1Affine::ZERO.to_projective(),
+            Secp256K1Projective::ZERO
+        );
+        assert_eq!(Secp256K1Projective::ZERO.to_affine(), Secp256K1Affine::ZERO);
+    }
+
     #[test]
     fn test_naive_multiplication() {
         let g = Secp256K1::GENERATOR_PROJECTIVE;
@@ -171,6 +171,11 @@ fn ctl_logic<F: Field>() -> CrossTableLookup<F> {
     CrossTableLookup::new(all_lookers, logic_looked)
 }
 
+/// Binds every memory operation of the CPU (its code read and the reads and writes of its general
+/// purpose channels) and of the Keccak sponge to a row of the memory table. Each operation is
+/// given as `is_read, context, segment, virtual, value limbs, timestamp`, where the timestamp is
+/// `clock * NUM_CHANNELS + channel`; the memory table is then responsible for read-over-write
+/// consistency of the sorted operations.
 fn ctl_memory<F: Field>() -> CrossTableLookup<F> {
     let cpu_memory_code_read = TableWithColumns::new(
         Table::Cpu,
@@ -202,3 +207,77 @@ fn ctl_memory<F: Field>() -> CrossTableLookup<F> {
     );
     CrossTableLookup::new(all_lookers, memory_looked)
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use ethereum_types::U256;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::all_stark::{ctl_memory, AllStark};
+    use crate::config::StarkConfig;
+    use crate::cpu::kernel::aggregator::KERNEL;
+    use crate::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+    use crate::cross_table_lookup::testutils::check_ctls;
+    use crate::generation::state::GenerationState;
+    use crate::generation::GenerationInputs;
+    use crate::memory::segments::Segment;
+    use crate::witness::memory::MemoryAddress;
+    use crate::witness::state::RegistersState;
+    use crate::witness::transition::transition;
+
+    #[test]
+    fn test_ctl_memory_store_then_load() -> Result<()> {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as GenericConfig<D>>::F;
+
+        // Store 0x2a at (1, MainMemory, 5) and load it back, as kernel code.
+        let push1 = get_push_opcode(1);
+        let segment = Segment::MainMemory as u8;
+        let code = [
+            push1,
+            0x2a,
+            push1,
+            5,
+            push1,
+            segment,
+            push1,
+            1,
+            get_opcode("MSTORE_GENERAL"),
+            push1,
+            5,
+            push1,
+            segment,
+            push1,
+            1,
+            get_opcode("MLOAD_GENERAL"),
+        ];
+        let mut state = GenerationState::<F>::new(GenerationInputs::default(), &KERNEL.code);
+        let code_start = KERNEL.code.len();
+        for (i, &byte) in code.iter().enumerate() {
+            state.memory.set(
+                MemoryAddress::new(0, Segment::Code, code_start + i),
+                byte.into(),
+            );
+        }
+        state.registers = RegistersState {
+            program_counter: code_start,
+            ..Default::default()
+        };
+        assert!(state.registers.is_kernel);
+        while state.registers.program_counter < code_start + code.len() {
+            transition(&mut state)?;
+        }
+        assert_eq!(state.stack(), vec![U256::from(0x2a)]);
+
+        let all_stark = AllStark::<F, D>::default();
+        let config = StarkConfig::standard_fast_config();
+        let tables = state
+            .traces
+            .into_tables(&all_stark, &config, &mut TimingTree::default());
+        check_ctls(&tables, &[ctl_memory()]);
+
+        Ok(())
+    }
+}
@@ -351,4 +351,73 @@ mod tests {
             }
         }
     }
+
+    fn constraints_hold(lv: &[GoldilocksField; NUM_ARITH_COLUMNS]) -> bool {
+        let mut constraint_consumer = ConstraintConsumer::new(
+            vec![GoldilocksField(2), GoldilocksField(3), GoldilocksField(5)],
+            GoldilocksField::ONE,
+            GoldilocksField::ONE,
+            GoldilocksField::ONE,
+        );
+        eval_packed_generic(lv, &mut constraint_consumer);
+        constraint_consumer
+            .constraint_accs
+            .iter()
+            .all(|&acc| acc == GoldilocksField::ZERO)
+    }
+
+    fn limbs(x: U256) -> [GoldilocksField; N_LIMBS] {
+        let mut limbs = [GoldilocksField::ZERO; N_LIMBS];
+        u256_to_array(&mut limbs, x);
+        limbs
+    }
+
+    #[test]
+    fn add_wraps_around() {
+        type F = GoldilocksField;
+
+        let mut lv = [F::ZERO; NUM_ARITH_COLUMNS];
+        lv[IS_ADD] = F::ONE;
+        generate(&mut lv, IS_ADD, U256::MAX, U256::one());
+        assert_eq!(lv[OUTPUT_REGISTER], limbs(U256::zero()));
+        assert_eq!(lv[AUX_INPUT_REGISTER_0], limbs(U256::one()));
+        assert!(constraints_hold(&lv));
+
+        // The carry out can't be dropped, nor can it be anything but a single bit.
+        lv[AUX_INPUT_REGISTER_0.start] = F::ZERO;
+        assert!(!constraints_hold(&lv));
+        lv[AUX_INPUT_REGISTER_0.start] = F::TWO;
+        assert!(!constraints_hold(&lv));
+    }
+
+    #[test]
+    fn sub_wraps_around() {
+        type F = GoldilocksField;
+
+        let mut lv = [F::ZERO; NUM_ARITH_COLUMNS];
+        lv[IS_SUB] = F::ONE;
+        generate(&mut lv, IS_SUB, U256::zero(), U256::one());
+        assert_eq!(lv[OUTPUT_REGISTER], limbs(U256::MAX));
+        assert_eq!(lv[AUX_INPUT_REGISTER_0], limbs(U256::one()));
+        assert!(constraints_hold(&lv));
+
+        // Likewise for the borrow.
+        lv[AUX_INPUT_REGISTER_0.start] = F::ZERO;
+        assert!(!constraints_hold(&lv));
+        lv[AUX_INPUT_REGISTER_0.start] = F::TWO;
+        assert!(!constraints_hold(&lv));
+    }
+
+    #[test]
+    fn carry_fits_in_one_limb() {
+        type F = GoldilocksField;
+
+        // Only the lowest limb of the carry register may be nonzero.
+        let mut lv = [F::ZERO; NUM_ARITH_COLUMNS];
+        lv[IS_ADD] = F::ONE;
+        generate(&mut lv, IS_ADD, U256::one(), U256::one());
+        assert!(constraints_hold(&lv));
+        lv[AUX_INPUT_REGISTER_0.start + 1] = F::ONE;
+        assert!(!constraints_hold(&lv));
+    }
 }
@@ -367,6 +367,51 @@ mod tests {
         }
     }
 
+    /// Whether the lookup argument on a pair of permuted columns holds, i.e. whether each permuted
+    /// input either repeats the previous one or equals the permuted table value, with the first row
+    /// matching the table.
+    fn lookup_holds<F: Field>(permuted_input: &[F], permuted_table: &[F]) -> bool {
+        permuted_input[0] == permuted_table[0]
+            && (1..permuted_input.len()).all(|i| {
+                permuted_input[i] == permuted_input[i - 1] || permuted_input[i] == permuted_table[i]
+            })
+    }
+
+    #[test]
+    fn range_check_rejects_out_of_range_limb() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ArithmeticStark<F, D>;
+
+        let stark = S {
+            f: Default::default(),
+        };
+
+        // byte(31, 0xABCD) = 0xCD
+        let byte = Operation::binary(BinaryOperator::Byte, U256::from(31), U256::from(0xABCD));
+        let mut cols = stark
+            .generate_trace(vec![byte])
+            .into_iter()
+            .map(|p| p.values)
+            .collect::<Vec<_>>();
+
+        // Every shared column, whichever operation writes it, goes through the same range check.
+        let rc_pairs = || columns::SHARED_COLS.zip(columns::RC_COLS.step_by(2));
+        for (_, rc) in rc_pairs() {
+            assert!(lookup_holds(&cols[rc], &cols[rc + 1]));
+        }
+
+        // Replace the output limb with a value one past the range, and redo the range checks.
+        let bad_col = OUTPUT_REGISTER.start;
+        cols[bad_col][0] = F::from_canonical_usize(super::RANGE_MAX);
+        stark.generate_range_checks(&mut cols);
+
+        for (c, rc) in rc_pairs() {
+            assert_eq!(lookup_holds(&cols[rc], &cols[rc + 1]), c != bad_col);
+        }
+    }
+
     #[test]
     fn big_traces() {
         const D: usize = 2;
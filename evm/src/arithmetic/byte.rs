@@ -477,7 +477,39 @@ mod tests {
             for idx in big_indices {
                 generate(&mut lv, idx, val);
                 verify_output(&lv, 0);
+                verify_constraints(&lv);
             }
         }
     }
+
+    fn verify_constraints(lv: &[F; NUM_ARITH_COLUMNS]) {
+        let mut constrant_consumer = ConstraintConsumer::new(
+            vec![GoldilocksField(2), GoldilocksField(3), GoldilocksField(5)],
+            F::ONE,
+            F::ONE,
+            F::ONE,
+        );
+        eval_packed(lv, &mut constrant_consumer);
+        for &acc in &constrant_consumer.constraint_accs {
+            assert_eq!(acc, F::ZERO);
+        }
+    }
+
+    #[test]
+    fn first_last_and_out_of_range_index() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0x6feb51b7ec230f25);
+        let mut lv =
+            [F::default(); NUM_ARITH_COLUMNS].map(|_| F::from_canonical_u16(rng.gen::<u16>()));
+        lv[IS_BYTE] = F::ONE;
+
+        // Bytes 0x01, 0x02, ..., 0x20 from most to least significant.
+        let bytes: [u8; 32] = std::array::from_fn(|i| i as u8 + 1);
+        let val = U256::from_big_endian(&bytes);
+
+        for (idx, expected_byte) in [(0u64, 0x01), (31, 0x20), (32, 0x00)] {
+            generate(&mut lv, idx.into(), val);
+            verify_output(&lv, expected_byte);
+            verify_constraints(&lv);
+        }
+    }
 }
@@ -669,6 +669,7 @@ mod tests {
     use crate::arithmetic::columns::NUM_ARITH_COLUMNS;
     use crate::constraint_consumer::ConstraintConsumer;
     use crate::extension_tower::BN_BASE;
+    use crate::util::{addmod, mulmod, submod};
 
     const N_RND_TESTS: usize = 1000;
     const MODULAR_OPS: [usize; 6] = [
@@ -825,4 +826,172 @@ mod tests {
             }
         }
     }
+
+    /// Generate the two rows of the BN254 operation `op_filter`, starting from random garbage.
+    fn generate_fp254(
+        rng: &mut ChaCha8Rng,
+        op_filter: usize,
+        input0: U256,
+        input1: U256,
+    ) -> (
+        [GoldilocksField; NUM_ARITH_COLUMNS],
+        [GoldilocksField; NUM_ARITH_COLUMNS],
+    ) {
+        type F = GoldilocksField;
+
+        let mut lv = [F::default(); NUM_ARITH_COLUMNS].map(|_| F::from_canonical_u16(rng.gen()));
+        let mut nv = [F::default(); NUM_ARITH_COLUMNS].map(|_| F::from_canonical_u16(rng.gen()));
+        for op in MODULAR_OPS {
+            lv[op] = F::ZERO;
+        }
+        lv[IS_DIV] = F::ZERO;
+        lv[IS_MOD] = F::ZERO;
+        lv[op_filter] = F::ONE;
+
+        generate(&mut lv, &mut nv, op_filter, input0, input1, BN_BASE);
+        (lv, nv)
+    }
+
+    fn constraints_hold(
+        lv: &[GoldilocksField; NUM_ARITH_COLUMNS],
+        nv: &[GoldilocksField; NUM_ARITH_COLUMNS],
+    ) -> bool {
+        let mut constraint_consumer = ConstraintConsumer::new(
+            vec![GoldilocksField(2), GoldilocksField(3), GoldilocksField(5)],
+            GoldilocksField::ONE,
+            GoldilocksField::ZERO,
+            GoldilocksField::ZERO,
+        );
+        eval_packed(lv, nv, &mut constraint_consumer);
+        constraint_consumer
+            .constraint_accs
+            .iter()
+            .all(|&acc| acc == GoldilocksField::ZERO)
+    }
+
+    fn output(lv: &[GoldilocksField; NUM_ARITH_COLUMNS]) -> [GoldilocksField; N_LIMBS] {
+        lv[MODULAR_OUTPUT].try_into().unwrap()
+    }
+
+    fn limbs(x: U256) -> [GoldilocksField; N_LIMBS] {
+        let mut limbs = [GoldilocksField::ZERO; N_LIMBS];
+        u256_to_array(&mut limbs, x);
+        limbs
+    }
+
+    #[test]
+    fn fp254_canonical_inputs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0x6feb51b7ec230f25);
+
+        for op_filter in [IS_ADDFP254, IS_SUBFP254, IS_MULFP254] {
+            for _i in 0..N_RND_TESTS {
+                let input0 = U256::from(rng.gen::<[u8; 32]>()) % BN_BASE;
+                let input1 = U256::from(rng.gen::<[u8; 32]>()) % BN_BASE;
+                let (lv, nv) = generate_fp254(&mut rng, op_filter, input0, input1);
+
+                let expected = match op_filter {
+                    IS_ADDFP254 => addmod(input0, input1, BN_BASE),
+                    IS_SUBFP254 => submod(input0, input1, BN_BASE),
+                    _ => mulmod(input0, input1, BN_BASE),
+                };
+                assert_eq!(output(&lv), limbs(expected));
+                assert!(constraints_hold(&lv, &nv));
+            }
+        }
+    }
+
+    #[test]
+    fn fp254_wraparound() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0x6feb51b7ec230f25);
+        let p_minus_one = BN_BASE - 1;
+
+        // (p - 1) + 2 straddles the prime and wraps around to 1, and 1 - 2 wraps back to p - 1.
+        for (op_filter, input0, input1, expected) in [
+            (IS_ADDFP254, p_minus_one, 2.into(), U256::one()),
+            (IS_ADDFP254, p_minus_one, 1.into(), U256::zero()),
+            (IS_SUBFP254, U256::one(), 2.into(), p_minus_one),
+            (IS_MULFP254, p_minus_one, p_minus_one, U256::one()),
+        ] {
+            let (lv, nv) = generate_fp254(&mut rng, op_filter, input0, input1);
+            assert_eq!(output(&lv), limbs(expected));
+            assert!(constraints_hold(&lv, &nv));
+        }
+    }
+
+    #[test]
+    fn fp254_non_canonical_output() {
+        type F = GoldilocksField;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0x6feb51b7ec230f25);
+
+        // An input equal to p is reduced like any other: p + 0 gives the canonical 0.
+        let (mut lv, mut nv) = generate_fp254(&mut rng, IS_ADDFP254, BN_BASE, U256::zero());
+        assert_eq!(output(&lv), limbs(U256::zero()));
+        assert!(constraints_hold(&lv, &nv));
+
+        // Claiming that p + 0 = p, with quotient zero, satisfies the polynomial identity but not
+        // the reduction check, so the non-canonical p can never be output.
+        lv[MODULAR_OUTPUT].copy_from_slice(&limbs(BN_BASE));
+        lv[MODULAR_QUO_INPUT].fill(F::ZERO);
+        // The auxiliary coefficients are all zero, stored with an offset of 2^20.
+        nv[MODULAR_AUX_INPUT_LO].fill(F::ZERO);
+        nv[MODULAR_AUX_INPUT_HI].fill(F::from_canonical_u64((AUX_COEFF_ABS_MAX >> 16) as u64));
+        nv[MODULAR_OUT_AUX_RED].fill(F::ZERO);
+        assert!(!constraints_hold(&lv, &nv));
+    }
+
+    #[test]
+    fn wide_intermediate() {
+        type F = GoldilocksField;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0x6feb51b7ec230f25);
+
+        // With both inputs at 2^256 - 1 the sum and product overflow
+        // 256 bits, so the reduction must be done on the full
+        // 512-bit intermediate value.
+        let input0 = U256::MAX;
+        let input1 = U256::MAX;
+
+        for op_filter in [IS_ADDMOD, IS_MULMOD] {
+            for _i in 0..N_RND_TESTS {
+                let mut lv = [F::default(); NUM_ARITH_COLUMNS]
+                    .map(|_| F::from_canonical_u16(rng.gen::<u16>()));
+                let mut nv = [F::default(); NUM_ARITH_COLUMNS]
+                    .map(|_| F::from_canonical_u16(rng.gen::<u16>()));
+
+                // Reset operation columns, then select one
+                for op in MODULAR_OPS {
+                    lv[op] = F::ZERO;
+                }
+                lv[IS_DIV] = F::ZERO;
+                lv[IS_MOD] = F::ZERO;
+                lv[op_filter] = F::ONE;
+
+                let modulus = U256::from(rng.gen::<[u8; 32]>()) | U256::one();
+
+                generate(&mut lv, &mut nv, op_filter, input0, input1, modulus);
+
+                let mut expected = [F::ZERO; N_LIMBS];
+                let output = if op_filter == IS_ADDMOD {
+                    addmod(input0, input1, modulus)
+                } else {
+                    mulmod(input0, input1, modulus)
+                };
+                u256_to_array(&mut expected, output);
+                assert_eq!(lv[MODULAR_OUTPUT], expected);
+
+                let mut constraint_consumer = ConstraintConsumer::new(
+                    vec![GoldilocksField(2), GoldilocksField(3), GoldilocksField(5)],
+                    GoldilocksField::ONE,
+                    GoldilocksField::ZERO,
+                    GoldilocksField::ZERO,
+                );
+                eval_packed(&lv, &nv, &mut constraint_consumer);
+                assert!(constraint_consumer
+                    .constraint_accs
+                    .iter()
+                    .all(|&acc| acc == F::ZERO));
+            }
+        }
+    }
 }
@@ -275,4 +275,40 @@ mod tests {
             }
         }
     }
+
+    fn constraints_hold(lv: &[GoldilocksField; NUM_ARITH_COLUMNS]) -> bool {
+        let mut constraint_consumer = ConstraintConsumer::new(
+            vec![GoldilocksField(2), GoldilocksField(3), GoldilocksField(5)],
+            GoldilocksField::ONE,
+            GoldilocksField::ONE,
+            GoldilocksField::ONE,
+        );
+        eval_packed_generic(lv, &mut constraint_consumer);
+        constraint_consumer
+            .constraint_accs
+            .iter()
+            .all(|&acc| acc == GoldilocksField::ZERO)
+    }
+
+    fn limbs(x: U256) -> [GoldilocksField; N_LIMBS] {
+        let mut limbs = [GoldilocksField::ZERO; N_LIMBS];
+        u256_to_array(&mut limbs, x);
+        limbs
+    }
+
+    #[test]
+    fn mul_wraps_around() {
+        type F = GoldilocksField;
+
+        // (2^256 - 1)^2 = 2^512 - 2^257 + 1, so only the 1 remains.
+        let mut lv = [F::ZERO; NUM_ARITH_COLUMNS];
+        lv[IS_MUL] = F::ONE;
+        generate(&mut lv, U256::MAX, U256::MAX);
+        assert_eq!(lv[OUTPUT_REGISTER], limbs(U256::one()));
+        assert!(constraints_hold(&lv));
+
+        // The low 256 bits are fixed, so no other output is accepted with these carries.
+        lv[OUTPUT_REGISTER.start] = F::ZERO;
+        assert!(!constraints_hold(&lv));
+    }
 }
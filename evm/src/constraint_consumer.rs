@@ -26,6 +26,9 @@ pub struct ConstraintConsumer<P: PackedField> {
     /// The evaluation of the Lagrange basis polynomial which is nonzero at the point associated
     /// with the last trace row, and zero at other points in the subgroup.
     lagrange_basis_last: P,
+
+    /// Every constraint emitted so far, if they are being recorded.
+    constraints: Option<Vec<P>>,
 }
 
 impl<P: PackedField> ConstraintConsumer<P> {
@@ -41,6 +44,16 @@ impl<P: PackedField> ConstraintConsumer<P> {
             z_last,
             lagrange_basis_first,
             lagrange_basis_last,
+            constraints: None,
+        }
+    }
+
+    /// Like `new`, but without combining constraints: each one is kept, filter included, and
+    /// returned by `constraints`. This tells which constraint fails, when debugging a trace.
+    pub fn new_recording(z_last: P, lagrange_basis_first: P, lagrange_basis_last: P) -> Self {
+        Self {
+            constraints: Some(vec![]),
+            ..Self::new(vec![], z_last, lagrange_basis_first, lagrange_basis_last)
         }
     }
 
@@ -48,6 +61,12 @@ impl<P: PackedField> ConstraintConsumer<P> {
         self.constraint_accs
     }
 
+    /// The constraints emitted so far, in order. Panics unless created by `new_recording`.
+    pub fn constraints(self) -> Vec<P> {
+        self.constraints
+            .expect("Constraints are only kept by a recording consumer")
+    }
+
     /// Add one constraint valid on all rows except the last.
     pub fn constraint_transition(&mut self, constraint: P) {
         self.constraint(constraint * self.z_last);
@@ -59,6 +78,9 @@ impl<P: PackedField> ConstraintConsumer<P> {
             *acc *= alpha;
             *acc += constraint;
         }
+        if let Some(constraints) = &mut self.constraints {
+            constraints.push(constraint);
+        }
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
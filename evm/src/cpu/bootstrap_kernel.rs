@@ -161,3 +161,68 @@ pub(crate) fn eval_bootstrap_kernel_circuit<F: RichField + Extendable<D>, const
         yield_constr.constraint_transition(builder, constraint);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    use crate::constraint_consumer::ConstraintConsumer;
+    use crate::cpu::bootstrap_kernel::{eval_bootstrap_kernel, generate_bootstrap_kernel};
+    use crate::cpu::columns::{CpuColumnsView, NUM_CPU_COLUMNS};
+    use crate::cpu::kernel::aggregator::KERNEL;
+    use crate::cpu::kernel::assembler::hash_kernel;
+    use crate::cpu::membus::NUM_GP_CHANNELS;
+    use crate::generation::state::GenerationState;
+    use crate::generation::GenerationInputs;
+    use crate::vars::StarkEvaluationVars;
+
+    type F = GoldilocksField;
+
+    /// Evaluates the bootstrap constraints on the transition from `local` to `next`.
+    fn eval_transition(local: CpuColumnsView<F>, next: CpuColumnsView<F>) -> F {
+        let mut consumer = ConstraintConsumer::new(vec![F::rand()], F::ONE, F::ZERO, F::ZERO);
+        let local: [F; NUM_CPU_COLUMNS] = local.into();
+        let next: [F; NUM_CPU_COLUMNS] = next.into();
+        let vars = StarkEvaluationVars {
+            local_values: &local,
+            next_values: &next,
+        };
+        eval_bootstrap_kernel(vars, &mut consumer);
+        consumer.accumulators()[0]
+    }
+
+    #[test]
+    fn test_bootstrap_kernel_hash() {
+        let mut state = GenerationState::<F>::new(GenerationInputs::default(), &KERNEL.code);
+        generate_bootstrap_kernel(&mut state);
+
+        // The kernel code is fed to the Keccak sponge, whose digest the final bootstrap row holds.
+        let sponge_op = state.traces.keccak_sponge_ops.last().unwrap();
+        assert_eq!(sponge_op.input, KERNEL.code);
+        assert_eq!(hash_kernel(&KERNEL.code), KERNEL.code_hash);
+        let final_row = *state.traces.cpu.last().unwrap();
+        assert_eq!(
+            final_row.mem_channels[NUM_GP_CHANNELS - 1].value,
+            KERNEL.code_hash.map(F::from_canonical_u32)
+        );
+        assert_eq!(
+            eval_transition(final_row, CpuColumnsView::default()),
+            F::ZERO
+        );
+
+        // Tampering with a single byte of the kernel changes the digest output by the sponge, which
+        // then no longer matches the hash the constraints expect.
+        let mut tampered_code = KERNEL.code.clone();
+        tampered_code[0] ^= 1;
+        let tampered_hash = hash_kernel(&tampered_code);
+        assert_ne!(tampered_hash, KERNEL.code_hash);
+        let mut tampered_row = final_row;
+        tampered_row.mem_channels[NUM_GP_CHANNELS - 1].value =
+            tampered_hash.map(F::from_canonical_u32);
+        assert_ne!(
+            eval_transition(tampered_row, CpuColumnsView::default()),
+            F::ZERO
+        );
+    }
+}
@@ -6,10 +6,12 @@ use std::fmt::Debug;
 use std::mem::{size_of, transmute};
 use std::ops::{Index, IndexMut};
 
+use once_cell::sync::Lazy;
 use plonky2::field::types::Field;
+use static_assertions::const_assert;
 
-use crate::cpu::columns::general::CpuGeneralColumnsView;
-use crate::cpu::columns::ops::OpsColumnsView;
+use crate::cpu::columns::general::{CpuGeneralColumnsView, NUM_SHARED_COLUMNS};
+use crate::cpu::columns::ops::{OpsColumnsView, NUM_OPS_COLUMNS};
 use crate::cpu::membus::NUM_GP_CHANNELS;
 use crate::memory;
 use crate::util::{indices_arr, transmute_no_compile_time_size_checks};
@@ -84,6 +86,23 @@ pub struct CpuColumnsView<T: Copy> {
 // `u8` is guaranteed to have a `size_of` of 1.
 pub const NUM_CPU_COLUMNS: usize = size_of::<CpuColumnsView<u8>>();
 
+/// The number of columns of a `MemoryChannelView`.
+const NUM_MEM_CHANNEL_COLUMNS: usize = 5 + memory::VALUE_LIMBS;
+
+// Every group of columns of `CpuColumnsView` must be accounted for here, so that adding a column
+// or resizing a group without updating the layout fails to compile.
+const_assert!(size_of::<MemoryChannelView<u8>>() == NUM_MEM_CHANNEL_COLUMNS);
+const_assert!(
+    NUM_CPU_COLUMNS
+        == 9 // is_bootstrap_kernel through gas
+            + NUM_OPS_COLUMNS
+            + 8 // opcode_bits
+            + 1 // is_keccak_sponge
+            + NUM_SHARED_COLUMNS
+            + 1 // clock
+            + NUM_GP_CHANNELS * NUM_MEM_CHANNEL_COLUMNS
+);
+
 impl<F: Field> Default for CpuColumnsView<F> {
     fn default() -> Self {
         Self::from([F::ZERO; NUM_CPU_COLUMNS])
@@ -154,3 +173,191 @@ const fn make_col_map() -> CpuColumnsView<usize> {
 }
 
 pub const COL_MAP: CpuColumnsView<usize> = make_col_map();
+
+/// The name of each column, indexed by its position in `CpuColumnsView`. Array entries are
+/// suffixed with their index, e.g. `opcode_bits[3]` or `mem_channels[2].value[5]`; since
+/// `general` is a union of overlapping views, its columns are only named `general[i]`.
+static COLUMN_NAMES: Lazy<Vec<String>> = Lazy::new(make_column_names);
+
+fn make_column_names() -> Vec<String> {
+    let mut names = vec![String::new(); NUM_CPU_COLUMNS];
+
+    macro_rules! name_columns {
+        ($prefix:literal, $view:expr, [$($field:ident),*]) => {
+            $(names[$view.$field] = format!("{}{}", $prefix, stringify!($field));)*
+        };
+    }
+    macro_rules! name_array_columns {
+        ($name:expr, $indices:expr) => {
+            for (i, &index) in $indices.iter().enumerate() {
+                names[index] = format!("{}[{}]", $name, i);
+            }
+        };
+    }
+
+    name_columns!(
+        "",
+        COL_MAP,
+        [
+            is_bootstrap_kernel,
+            is_cpu_cycle,
+            context,
+            code_context,
+            program_counter,
+            stack_len,
+            stack_len_bounds_aux,
+            is_kernel_mode,
+            gas,
+            is_keccak_sponge,
+            clock
+        ]
+    );
+    name_columns!(
+        "op.",
+        COL_MAP.op,
+        [
+            add,
+            mul,
+            sub,
+            div,
+            mod_,
+            addmod,
+            mulmod,
+            addfp254,
+            mulfp254,
+            subfp254,
+            submod,
+            lt,
+            gt,
+            eq,
+            iszero,
+            and,
+            or,
+            xor,
+            not,
+            byte,
+            shl,
+            shr,
+            keccak_general,
+            prover_input,
+            pop,
+            jump,
+            jumpi,
+            pc,
+            jumpdest,
+            push0,
+            push,
+            dup,
+            swap,
+            get_context,
+            set_context,
+            exit_kernel,
+            mload_general,
+            mstore_general,
+            syscall,
+            exception
+        ]
+    );
+    name_array_columns!("opcode_bits", COL_MAP.opcode_bits);
+    let general: [usize; NUM_SHARED_COLUMNS] = *COL_MAP.general.borrow();
+    name_array_columns!("general", general);
+    for (i, channel) in COL_MAP.mem_channels.iter().enumerate() {
+        let prefix = format!("mem_channels[{i}].");
+        for (field, index) in [
+            ("used", channel.used),
+            ("is_read", channel.is_read),
+            ("addr_context", channel.addr_context),
+            ("addr_segment", channel.addr_segment),
+            ("addr_virtual", channel.addr_virtual),
+        ] {
+            names[index] = format!("{prefix}{field}");
+        }
+        name_array_columns!(format!("{prefix}value"), channel.value);
+    }
+
+    names
+}
+
+/// Returns the name of the column at `index`. Panics if `index >= NUM_CPU_COLUMNS`.
+pub fn column_name(index: usize) -> &'static str {
+    &COLUMN_NAMES[index]
+}
+
+/// Returns the index of the column called `name`, as given by `column_name`.
+pub fn column_index(name: &str) -> Option<usize> {
+    COLUMN_NAMES.iter().position(|n| n == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Borrow;
+    use std::collections::HashSet;
+
+    use crate::cpu::columns::general::NUM_SHARED_COLUMNS;
+    use crate::cpu::columns::{column_index, column_name, COL_MAP, NUM_CPU_COLUMNS};
+
+    #[test]
+    fn test_column_indices() {
+        let mut indices = vec![
+            COL_MAP.is_bootstrap_kernel,
+            COL_MAP.is_cpu_cycle,
+            COL_MAP.context,
+            COL_MAP.code_context,
+            COL_MAP.program_counter,
+            COL_MAP.stack_len,
+            COL_MAP.stack_len_bounds_aux,
+            COL_MAP.is_kernel_mode,
+            COL_MAP.gas,
+            COL_MAP.is_keccak_sponge,
+            COL_MAP.clock,
+        ];
+        indices.extend(*COL_MAP.op);
+        indices.extend(COL_MAP.opcode_bits);
+        let general: [usize; NUM_SHARED_COLUMNS] = *COL_MAP.general.borrow();
+        indices.extend(general);
+        for channel in COL_MAP.mem_channels {
+            indices.extend([
+                channel.used,
+                channel.is_read,
+                channel.addr_context,
+                channel.addr_segment,
+                channel.addr_virtual,
+            ]);
+            indices.extend(channel.value);
+        }
+
+        // Every column is declared exactly once.
+        assert_eq!(indices.len(), NUM_CPU_COLUMNS);
+        let mut declared = [false; NUM_CPU_COLUMNS];
+        for index in indices {
+            assert!(
+                index < NUM_CPU_COLUMNS,
+                "Column index {index} is out of range"
+            );
+            assert!(
+                !declared[index],
+                "Column {} is declared twice",
+                column_name(index)
+            );
+            declared[index] = true;
+        }
+    }
+
+    #[test]
+    fn test_column_names() {
+        let mut names = HashSet::new();
+        for index in 0..NUM_CPU_COLUMNS {
+            let name = column_name(index);
+            assert!(!name.is_empty(), "Column {index} has no name");
+            assert!(names.insert(name), "Column name {name} is not unique");
+            assert_eq!(column_index(name), Some(index));
+        }
+
+        assert_eq!(column_index("op.add"), Some(COL_MAP.op.add));
+        assert_eq!(
+            column_index("mem_channels[1].value[2]"),
+            Some(COL_MAP.mem_channels[1].value[2])
+        );
+        assert_eq!(column_index("op.nonexistent"), None);
+    }
+}
@@ -2,25 +2,36 @@ use std::borrow::{Borrow, BorrowMut};
 use std::iter::repeat;
 use std::marker::PhantomData;
 
+use ethereum_types::{Address, H256, U256};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
-use plonky2::field::types::Field;
+use plonky2::field::types::{Field, PrimeField64};
 use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use serde::{Deserialize, Serialize};
 
 use crate::all_stark::Table;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
-use crate::cpu::columns::{CpuColumnsView, COL_MAP, NUM_CPU_COLUMNS};
+use crate::cpu::columns::{column_name, CpuColumnsView, COL_MAP, NUM_CPU_COLUMNS};
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
 use crate::cpu::membus::NUM_GP_CHANNELS;
 use crate::cpu::{
     bootstrap_kernel, contextops, control_flow, decode, dup_swap, exceptions, gas, jumps, membus,
     memio, modfp254, pc, push0, shift, simple_logic, stack, stack_bounds, syscalls,
 };
 use crate::cross_table_lookup::{Column, TableWithColumns};
+use crate::generation::state::GenerationState;
+use crate::generation::GenerationInputs;
 use crate::memory::segments::Segment;
 use crate::memory::{NUM_CHANNELS, VALUE_LIMBS};
+use crate::proof::AllProof;
 use crate::stark::Stark;
 use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use crate::witness::memory::MemoryAddress;
+use crate::witness::state::RegistersState;
+use crate::witness::transition::transition;
 
 pub fn ctl_data_keccak_sponge<F: Field>() -> Vec<Column<F>> {
     // When executing KECCAK_GENERAL, the GP memory channels are used as follows:
@@ -158,6 +169,182 @@ pub fn ctl_filter_gp_memory<F: Field>(channel: usize) -> Column<F> {
     Column::single(COL_MAP.mem_channels[channel].used)
 }
 
+/// The modules contributing constraints to the CPU STARK, in the order in which they are applied.
+pub const CONSTRAINT_MODULES: [&str; 18] = [
+    "bootstrap_kernel",
+    "contextops",
+    "control_flow",
+    "decode",
+    "dup_swap",
+    "exceptions",
+    "gas",
+    "jumps",
+    "membus",
+    "memio",
+    "modfp254",
+    "pc",
+    "push0",
+    "shift",
+    "simple_logic",
+    "stack",
+    "stack_bounds",
+    "syscalls",
+];
+
+/// Applies the constraints of `CONSTRAINT_MODULES[module]`.
+fn eval_module_packed<P: PackedField>(
+    module: usize,
+    vars: StarkEvaluationVars<P::Scalar, P, NUM_CPU_COLUMNS>,
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let local_values = vars.local_values.borrow();
+    let next_values = vars.next_values.borrow();
+    match module {
+        0 => bootstrap_kernel::eval_bootstrap_kernel(vars, yield_constr),
+        1 => contextops::eval_packed(local_values, next_values, yield_constr),
+        2 => control_flow::eval_packed_generic(local_values, next_values, yield_constr),
+        3 => decode::eval_packed_generic(local_values, yield_constr),
+        4 => dup_swap::eval_packed(local_values, yield_constr),
+        5 => exceptions::eval_packed(local_values, next_values, yield_constr),
+        6 => gas::eval_packed(local_values, next_values, yield_constr),
+        7 => jumps::eval_packed(local_values, next_values, yield_constr),
+        8 => membus::eval_packed(local_values, yield_constr),
+        9 => memio::eval_packed(local_values, yield_constr),
+        10 => modfp254::eval_packed(local_values, yield_constr),
+        11 => pc::eval_packed(local_values, yield_constr),
+        12 => push0::eval_packed(local_values, yield_constr),
+        13 => shift::eval_packed(local_values, yield_constr),
+        14 => simple_logic::eval_packed(local_values, yield_constr),
+        15 => stack::eval_packed(local_values, yield_constr),
+        16 => stack_bounds::eval_packed(local_values, yield_constr),
+        17 => syscalls::eval_packed(local_values, next_values, yield_constr),
+        _ => panic!("Unknown CPU constraint module {module}"),
+    }
+}
+
+/// The index of the first constraint of `CONSTRAINT_MODULES[module]`, in the order in which they
+/// are emitted, which doesn't vanish on row `i` of `trace`, paired with the row after it.
+/// Transition constraints are not applied to the last row, and the constraints on the first and
+/// last rows are only applied if `boundaries`.
+fn failing_constraint<F: RichField>(
+    trace: &[[F; NUM_CPU_COLUMNS]],
+    module: usize,
+    i: usize,
+    boundaries: bool,
+) -> Option<usize> {
+    let n = trace.len();
+    let vars = StarkEvaluationVars {
+        local_values: &trace[i],
+        next_values: &trace[(i + 1) % n],
+    };
+    let mut consumer = ConstraintConsumer::new_recording(
+        F::from_bool(i != n - 1),
+        F::from_bool(boundaries && i == 0),
+        F::from_bool(boundaries && i == n - 1),
+    );
+    eval_module_packed(module, vars, &mut consumer);
+    consumer.constraints().iter().position(|c| !c.is_zero())
+}
+
+/// A CPU constraint which doesn't hold on some row of a trace.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ConstraintError {
+    /// The index of the row, paired with the row after it, on which the constraint fails.
+    pub row: usize,
+    /// The entry of `CONSTRAINT_MODULES` imposing the constraint.
+    pub module: &'static str,
+    /// The index of the constraint among those of `module`, in the order in which they are
+    /// emitted.
+    pub constraint: usize,
+}
+
+/// Checks every CPU constraint on each row of `trace`, paired with the row after it, without
+/// proving anything, and returns the first violation by row, then by constraint. Transition
+/// constraints are not applied to the last row. The constraints on the first and last rows of a
+/// whole execution are left out, so `trace` may be any part of one, such as that of
+/// `generate_trace`.
+pub fn check_constraints<F: RichField>(
+    trace: &[[F; NUM_CPU_COLUMNS]],
+) -> Result<(), ConstraintError> {
+    for row in 0..trace.len() {
+        for (module, &name) in CONSTRAINT_MODULES.iter().enumerate() {
+            if let Some(constraint) = failing_constraint(trace, module, row, false) {
+                return Err(ConstraintError {
+                    row,
+                    module: name,
+                    constraint,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates the constraints of the named `CONSTRAINT_MODULES` on each row of `trace`, paired
+/// with the row after it, and reports every row where some constraint doesn't vanish, in order.
+/// Each report names the failing modules and lists the nonzero columns of the row. Transition
+/// constraints are not applied to the last row. Panics if a module name is unknown.
+pub fn dump_failing_rows<F: RichField>(
+    trace: &[[F; NUM_CPU_COLUMNS]],
+    modules: &[&str],
+) -> Vec<(usize, String)> {
+    let modules = modules
+        .iter()
+        .map(|&name| {
+            CONSTRAINT_MODULES
+                .iter()
+                .position(|&module| module == name)
+                .unwrap_or_else(|| panic!("Unknown CPU constraint module {name}"))
+        })
+        .collect_vec();
+
+    (0..trace.len())
+        .filter_map(|i| {
+            let failing = modules
+                .iter()
+                .filter(|&&module| failing_constraint(trace, module, i, true).is_some())
+                .map(|&module| CONSTRAINT_MODULES[module])
+                .collect_vec();
+            if failing.is_empty() {
+                return None;
+            }
+
+            let columns = trace[i]
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| !x.is_zero())
+                .map(|(col, x)| format!("{} = {}", column_name(col), x.to_canonical_u64()))
+                .join(", ");
+            Some((
+                i,
+                format!("{} failed on row with {columns}", failing.join(", ")),
+            ))
+        })
+        .collect()
+}
+
+/// Reports the degree of the constraints of each module of `CONSTRAINT_MODULES`, as evaluated
+/// by `stark_testing::constraint_eval_degree`, or `None` for a module imposing no constraints.
+/// A module is within `constraint_degree()` iff its degree is at most
+/// `WITNESS_SIZE * constraint_degree() - 1`.
+#[cfg(test)]
+pub(crate) fn constraint_degrees<F: RichField + Extendable<D>, const D: usize>(
+    stark: &CpuStark<F, D>,
+) -> Vec<(&'static str, Option<usize>)> {
+    let rate_bits = plonky2_util::log2_ceil(stark.constraint_degree() + 1);
+    CONSTRAINT_MODULES
+        .iter()
+        .enumerate()
+        .map(|(module, &name)| {
+            let degree = crate::stark_testing::constraint_eval_degree::<F, NUM_CPU_COLUMNS>(
+                rate_bits,
+                |vars, consumer| eval_module_packed(module, vars, consumer),
+            );
+            (name, degree)
+        })
+        .collect()
+}
+
 #[derive(Copy, Clone, Default)]
 pub struct CpuStark<F, const D: usize> {
     pub f: PhantomData<F>,
@@ -183,26 +370,9 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for CpuStark<F, D
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>,
     {
-        let local_values = vars.local_values.borrow();
-        let next_values = vars.next_values.borrow();
-        bootstrap_kernel::eval_bootstrap_kernel(vars, yield_constr);
-        contextops::eval_packed(local_values, next_values, yield_constr);
-        control_flow::eval_packed_generic(local_values, next_values, yield_constr);
-        decode::eval_packed_generic(local_values, yield_constr);
-        dup_swap::eval_packed(local_values, yield_constr);
-        exceptions::eval_packed(local_values, next_values, yield_constr);
-        gas::eval_packed(local_values, next_values, yield_constr);
-        jumps::eval_packed(local_values, next_values, yield_constr);
-        membus::eval_packed(local_values, yield_constr);
-        memio::eval_packed(local_values, yield_constr);
-        modfp254::eval_packed(local_values, yield_constr);
-        pc::eval_packed(local_values, yield_constr);
-        push0::eval_packed(local_values, yield_constr);
-        shift::eval_packed(local_values, yield_constr);
-        simple_logic::eval_packed(local_values, yield_constr);
-        stack::eval_packed(local_values, yield_constr);
-        stack_bounds::eval_packed(local_values, yield_constr);
-        syscalls::eval_packed(local_values, next_values, yield_constr);
+        for module in 0..CONSTRAINT_MODULES.len() {
+            eval_module_packed(module, vars, yield_constr);
+        }
     }
 
     fn eval_ext_circuit(
@@ -238,13 +408,147 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for CpuStark<F, D
     }
 }
 
+/// What the prover claims about the last transaction it executed.
+///
+/// These values are unverified: nothing constrains them to match the traces yet. The prover reads
+/// them from kernel memory, but they are not observed by the challenger, and the memory
+/// cross-table lookup that would tie them to the CPU trace is disabled. Don't rely on them until
+/// they are bound.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CpuPublicValues {
+    /// The hash of the signed transaction.
+    pub txn_hash: H256,
+    pub sender: Address,
+    /// The gas charged to the sender, net of refunds.
+    pub gas_used: U256,
+    /// Whether the transaction succeeded, i.e. didn't revert or halt exceptionally.
+    pub status: bool,
+}
+
+/// The CPU public values claimed by `proof`, which are unverified, like all of `CpuPublicValues`.
+pub fn public_values<F, C, const D: usize>(proof: &AllProof<F, C, D>) -> CpuPublicValues
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    proof.public_values.cpu.clone()
+}
+
+/// The context in which `generate_trace` runs user code.
+pub(crate) const USER_CONTEXT: usize = 1;
+
+/// Inputs for `generate_trace`.
+#[derive(Clone, Debug, Default)]
+pub struct CpuInputs {
+    /// The initial stack, from bottom to top.
+    pub initial_stack: Vec<U256>,
+    /// The gas limit of the context running the code, or `None` for no limit.
+    pub gas_limit: Option<u64>,
+}
+
+/// Generates the CPU trace of running `bytecode` as user code, outside of any transaction. The
+/// rows are laid out as described by `CpuColumnsView`, one per instruction.
+///
+/// Execution stops as soon as control passes to the kernel, i.e. at the first syscall (including
+/// `STOP`) or exception, such as running out of gas; the row of that instruction is the last one
+/// returned. The initial code,
+/// jumpdest bits and stack are written directly to memory, so the trace is meant for inspection
+/// rather than proving.
+pub fn generate_trace<F: RichField>(
+    bytecode: &[u8],
+    inputs: CpuInputs,
+) -> anyhow::Result<Vec<[F; NUM_CPU_COLUMNS]>> {
+    let state = run_user_code(bytecode, inputs)?;
+    Ok(state.traces.cpu.into_iter().map(|row| row.into()).collect())
+}
+
+/// Runs `bytecode` as `generate_trace` does, and returns the state in which control passed to the
+/// kernel. The user stack is left in the memory of `USER_CONTEXT`.
+pub(crate) fn run_user_code<F: RichField>(
+    bytecode: &[u8],
+    inputs: CpuInputs,
+) -> anyhow::Result<GenerationState<F>> {
+    let mut state = GenerationState::<F>::new(GenerationInputs::default(), &KERNEL.code);
+
+    let mut offset = 0;
+    while offset < bytecode.len() {
+        let opcode = bytecode[offset];
+        state.memory.set(
+            MemoryAddress::new(USER_CONTEXT, Segment::Code, offset),
+            opcode.into(),
+        );
+        match opcode {
+            0x5b => state.memory.set(
+                MemoryAddress::new(USER_CONTEXT, Segment::JumpdestBits, offset),
+                U256::one(),
+            ),
+            // Copy the data of PUSH instructions, which can't hold a JUMPDEST.
+            0x60..=0x7f => {
+                for _ in 0..opcode - 0x5f {
+                    offset += 1;
+                    if let Some(&byte) = bytecode.get(offset) {
+                        state.memory.set(
+                            MemoryAddress::new(USER_CONTEXT, Segment::Code, offset),
+                            byte.into(),
+                        );
+                    }
+                }
+            }
+            _ => (),
+        }
+        offset += 1;
+    }
+    let gas_limit = inputs.gas_limit.map_or(U256::MAX, U256::from);
+    state.memory.set(
+        MemoryAddress::new(
+            USER_CONTEXT,
+            Segment::ContextMetadata,
+            ContextMetadata::GasLimit as usize,
+        ),
+        gas_limit,
+    );
+    for (i, &x) in inputs.initial_stack.iter().enumerate() {
+        state
+            .memory
+            .set(MemoryAddress::new(USER_CONTEXT, Segment::Stack, i), x);
+    }
+
+    state.registers = RegistersState {
+        program_counter: 0,
+        is_kernel: false,
+        stack_len: inputs.initial_stack.len(),
+        context: USER_CONTEXT,
+        gas_used: 0,
+    };
+    while !state.registers.is_kernel {
+        transition(&mut state)?;
+    }
+
+    Ok(state)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::borrow::{Borrow, BorrowMut};
+
     use anyhow::Result;
+    use ethereum_types::U256;
+    use plonky2::field::types::Field;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 
-    use crate::cpu::cpu_stark::CpuStark;
-    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::cpu::columns::CpuColumnsView;
+    use crate::cpu::cpu_stark::{
+        check_constraints, constraint_degrees, dump_failing_rows, generate_trace, run_user_code,
+        ConstraintError, CpuInputs, CpuStark, CONSTRAINT_MODULES, USER_CONTEXT,
+    };
+    use crate::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+    use crate::cpu::membus::{memory_ops, MemoryOpKind, NUM_GP_CHANNELS};
+    use crate::memory::segments::Segment;
+    use crate::stark::Stark;
+    use crate::stark_testing::{
+        test_stark_circuit_constraints, test_stark_low_degree, WITNESS_SIZE,
+    };
+    use crate::witness::memory::MemoryAddress;
 
     #[test]
     fn test_stark_degree() -> Result<()> {
@@ -259,6 +563,26 @@ mod tests {
         test_stark_low_degree(stark)
     }
 
+    #[test]
+    fn test_constraint_degrees() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = CpuStark<F, D>;
+
+        let stark = S {
+            f: Default::default(),
+        };
+        let maximum_degree = WITNESS_SIZE * stark.constraint_degree() - 1;
+        for (module, degree) in constraint_degrees(&stark) {
+            let degree = degree.unwrap_or_else(|| panic!("{module} imposes no constraints"));
+            assert!(
+                degree <= maximum_degree,
+                "Constraints of {module} have degree {degree}, expected at most {maximum_degree}"
+            );
+        }
+    }
+
     #[test]
     fn test_stark_circuit() -> Result<()> {
         const D: usize = 2;
@@ -271,4 +595,337 @@ mod tests {
         };
         test_stark_circuit_constraints::<F, C, S, D>(stark)
     }
+
+    #[test]
+    fn test_generate_trace() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push1 = get_push_opcode(1);
+        let code = [push1, 1, push1, 2, get_opcode("ADD"), get_opcode("STOP")];
+        let trace = generate_trace::<F>(&code, CpuInputs::default())?;
+        let rows: Vec<&CpuColumnsView<F>> = trace.iter().map(|row| row.borrow()).collect();
+
+        // PUSH1, PUSH1, ADD, and STOP, which traps into the kernel.
+        assert_eq!(rows.len(), 4);
+        let pcs = rows
+            .iter()
+            .map(|row| row.program_counter)
+            .collect::<Vec<_>>();
+        assert_eq!(pcs, [0, 2, 4, 5].map(F::from_canonical_usize));
+        assert!(rows.iter().all(|row| row.is_kernel_mode == F::ZERO));
+
+        // ADD pops both operands and pushes their sum through the last memory channel.
+        let add = rows[2];
+        assert_eq!(add.stack_len, F::TWO);
+        assert_eq!(add.mem_channels[0].value[0], F::TWO);
+        assert_eq!(add.mem_channels[1].value[0], F::ONE);
+        let output = add.mem_channels[NUM_GP_CHANNELS - 1];
+        assert_eq!(output.used, F::ONE);
+        assert_eq!(output.is_read, F::ZERO);
+        assert_eq!(output.value[0], F::from_canonical_u64(3));
+        assert_eq!(rows[3].stack_len, F::ONE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_trace_stop() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push1 = get_push_opcode(1);
+        // Code past the end reads as zero, so running off the end executes a STOP.
+        for code in [vec![push1, 1, get_opcode("STOP")], vec![push1, 1]] {
+            let state = run_user_code::<F>(&code, CpuInputs::default())?;
+            let stop = state.traces.cpu.last().unwrap();
+            assert_eq!(stop.program_counter, F::TWO);
+            assert_eq!(stop.opcode_bits, [F::ZERO; 8]);
+            assert_eq!(stop.op.syscall, F::ONE);
+
+            // The kernel is entered with the kexit_info on top of the stack. STOP charges no
+            // gas, so only PUSH1 has been paid for.
+            let kexit_info = state.memory.get(MemoryAddress::new(
+                USER_CONTEXT,
+                Segment::Stack,
+                state.registers.stack_len - 1,
+            ));
+            assert_eq!(kexit_info.low_u32(), 3);
+            assert_eq!(kexit_info >> 192, U256::from(3));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_trace_out_of_gas() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        // Two PUSH1s and an ADD cost 3 + 3 + 3 = 9 gas.
+        let push1 = get_push_opcode(1);
+        let code = [push1, 1, push1, 2, get_opcode("ADD"), get_opcode("STOP")];
+        let run = |gas_limit| {
+            let inputs = CpuInputs {
+                gas_limit: Some(gas_limit),
+                ..Default::default()
+            };
+            generate_trace::<F>(&code, inputs)
+        };
+
+        // With exactly enough gas, the ADD succeeds and we reach the STOP syscall.
+        let trace = run(9)?;
+        let last: &CpuColumnsView<F> = trace.last().unwrap().borrow();
+        assert_eq!(trace.len(), 4);
+        assert_eq!(last.op.syscall, F::ONE);
+        assert_eq!(last.gas, F::from_canonical_u64(9));
+
+        // One gas short, the ADD raises an out-of-gas exception (code 0) instead.
+        let trace = run(8)?;
+        let last: &CpuColumnsView<F> = trace.last().unwrap().borrow();
+        assert_eq!(trace.len(), 3);
+        assert_eq!(last.op.exception, F::ONE);
+        assert_eq!(last.program_counter, F::from_canonical_usize(4));
+        assert_eq!(last.general.exception().exc_code_bits, [F::ZERO; 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_trace_pc() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        // PC sits after 2 + 3 + 33 bytes of PUSH instructions, whose immediates look like PC bytes.
+        let mut code = vec![get_push_opcode(1), 0x58, get_push_opcode(2), 0x58, 0x58];
+        code.push(get_push_opcode(32));
+        code.extend([0x58; 32]);
+        code.extend([get_opcode("GETPC"), get_opcode("GETPC"), get_opcode("STOP")]);
+        let trace = generate_trace::<F>(&code, CpuInputs::default())?;
+        let rows: Vec<&CpuColumnsView<F>> = trace.iter().map(|row| row.borrow()).collect();
+        assert_eq!(rows.len(), 6);
+
+        // Each PC pushes its own offset, not that of the next instruction, and costs 2 gas.
+        for (row, offset, gas) in [(rows[3], 38, 9), (rows[4], 39, 11)] {
+            assert_eq!(row.op.pc, F::ONE);
+            assert_eq!(row.program_counter, F::from_canonical_usize(offset));
+            assert_eq!(row.gas, F::from_canonical_u64(gas));
+            let mut expected = [F::ZERO; 8];
+            expected[0] = F::from_canonical_usize(offset);
+            assert_eq!(row.mem_channels[NUM_GP_CHANNELS - 1].value, expected);
+        }
+        assert_eq!(rows[5].gas, F::from_canonical_u64(13));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_trace_comparisons() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push1 = get_push_opcode(1);
+        let mut code = vec![push1, 5, push1, 5, get_opcode("EQ")];
+        code.extend([get_push_opcode(0), get_opcode("ISZERO")]);
+        // LT(-1, 1) and GT(-1, 1) compare unsigned values, i.e. 2^256 - 1 against 1.
+        for op in ["LT", "GT"] {
+            code.extend([push1, 1, get_push_opcode(32)]);
+            code.extend([0xff; 32]);
+            code.push(get_opcode(op));
+        }
+        code.push(get_opcode("STOP"));
+        let trace = generate_trace::<F>(&code, CpuInputs::default())?;
+        let rows: Vec<&CpuColumnsView<F>> = trace.iter().map(|row| row.borrow()).collect();
+
+        let output = |flag: fn(&CpuColumnsView<F>) -> F| {
+            let row = rows.iter().find(|row| flag(row) == F::ONE).unwrap();
+            row.mem_channels[NUM_GP_CHANNELS - 1].value
+        };
+        let [zero, one] = [F::ZERO, F::ONE].map(|x| {
+            let mut limbs = [F::ZERO; 8];
+            limbs[0] = x;
+            limbs
+        });
+        assert_eq!(output(|row| row.op.eq), one);
+        assert_eq!(output(|row| row.op.iszero), one);
+        assert_eq!(output(|row| row.op.lt), zero);
+        assert_eq!(output(|row| row.op.gt), one);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_trace_large_shifts() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push32 = get_push_opcode(32);
+        let shifts = [U256::from(256), U256::one() << 255, U256::MAX];
+        let mut code = vec![];
+        for op in ["SHL", "SHR"] {
+            for shift in shifts {
+                let mut shift_bytes = [0; 32];
+                shift.to_big_endian(&mut shift_bytes);
+                code.push(push32);
+                code.extend([0xff; 32]);
+                code.push(push32);
+                code.extend(shift_bytes);
+                code.push(get_opcode(op));
+            }
+        }
+        code.push(get_opcode("STOP"));
+        let mut trace = generate_trace::<F>(&code, CpuInputs::default())?;
+
+        let shift_rows = (0..trace.len())
+            .filter(|&i| {
+                let row: &CpuColumnsView<F> = trace[i].borrow();
+                row.op.shl + row.op.shr == F::ONE
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(shift_rows.len(), 6);
+        for (&i, shift) in shift_rows.iter().zip(shifts.iter().cycle()) {
+            let row: &CpuColumnsView<F> = trace[i].borrow();
+            // Shifting by 256 or more gives zero, whatever the low limb of the displacement.
+            assert_eq!(row.mem_channels[NUM_GP_CHANNELS - 1].value, [F::ZERO; 8]);
+            // The shifting factor is only looked up for displacements below 2^32.
+            let two_exp = row.mem_channels[2];
+            assert_eq!(two_exp.used, F::from_bool(shift.bits() <= 32));
+            assert_eq!(two_exp.value, [F::ZERO; 8]);
+        }
+        assert!(dump_failing_rows(&trace, &["shift"]).is_empty());
+
+        // A nonzero shifting factor can't be claimed for a displacement of 2^255.
+        let row: &mut CpuColumnsView<F> = trace[shift_rows[1]].borrow_mut();
+        row.mem_channels[2].value[0] = F::ONE;
+        let reports = dump_failing_rows(&trace, &["shift"]);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].0, shift_rows[1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_trace_dup_swap_underflow() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        // Runs `opcode` alone on a stack of `stack_len` elements, and returns the final row.
+        let run = |opcode: &str, stack_len: usize| {
+            let code = [get_opcode(opcode), get_opcode("STOP")];
+            let inputs = CpuInputs {
+                initial_stack: vec![U256::one(); stack_len],
+                ..Default::default()
+            };
+            let trace = generate_trace::<F>(&code, inputs)?;
+            let last: CpuColumnsView<F> = *trace.last().unwrap().borrow();
+            Ok::<_, anyhow::Error>(last)
+        };
+        // Exception 2 is a stack underflow.
+        let stack_underflow = [F::ZERO, F::ONE, F::ZERO];
+
+        // DUPn needs n elements, and SWAPn needs n + 1.
+        for (opcode, min_stack_len) in [("DUP3", 3), ("SWAP16", 17)] {
+            let last = run(opcode, min_stack_len - 1)?;
+            assert_eq!(last.op.exception, F::ONE);
+            assert_eq!(last.program_counter, F::ZERO);
+            assert_eq!(last.general.exception().exc_code_bits, stack_underflow);
+
+            let last = run(opcode, min_stack_len)?;
+            assert_eq!(last.op.syscall, F::ONE);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_ops() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push1 = get_push_opcode(1);
+        let code = [push1, 1, push1, 2, get_opcode("ADD"), get_opcode("STOP")];
+        let trace = generate_trace::<F>(&code, CpuInputs::default())?;
+        let ops = memory_ops(&trace);
+
+        assert!(ops
+            .windows(2)
+            .all(|pair| pair[0].timestamp < pair[1].timestamp));
+
+        // Each instruction starts by reading its opcode from the code segment.
+        let code_reads: Vec<_> = ops
+            .iter()
+            .filter(|op| op.address == (USER_CONTEXT, Segment::Code as usize, op.address.2))
+            .map(|op| (op.address.2, op.value.as_u32() as u8))
+            .collect();
+        assert_eq!(
+            code_reads,
+            [(0, push1), (2, push1), (4, code[4]), (5, code[5])]
+        );
+
+        // ADD pops both operands and pushes their sum to the stack.
+        let stack_ops: Vec<_> = ops
+            .iter()
+            .filter(|op| op.address.1 == Segment::Stack as usize)
+            .map(|op| (op.kind, op.address.2, op.value.as_u32()))
+            .collect();
+        assert_eq!(
+            stack_ops[..5],
+            [
+                (MemoryOpKind::Write, 0, 1),
+                (MemoryOpKind::Write, 1, 2),
+                (MemoryOpKind::Read, 1, 2),
+                (MemoryOpKind::Read, 0, 1),
+                (MemoryOpKind::Write, 0, 3),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump_failing_rows() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push1 = get_push_opcode(1);
+        let code = [push1, 1, push1, 2, get_opcode("ADD"), get_opcode("STOP")];
+        let mut trace = generate_trace::<F>(&code, CpuInputs::default())?;
+        // The trace starts mid-execution, so leave out the bootstrapping constraints on the first
+        // row.
+        let modules = &CONSTRAINT_MODULES[1..];
+        assert!(dump_failing_rows(&trace, modules)
+            .iter()
+            .all(|&(row, _)| row != 2));
+
+        // Make the ADD row's opcode bits disagree with its operation flag.
+        let row: &mut CpuColumnsView<F> = trace[2].borrow_mut();
+        row.opcode_bits[1] = F::ONE;
+        let reports = dump_failing_rows(&trace, modules);
+        let (_, report) = reports
+            .iter()
+            .find(|(row, _)| *row == 2)
+            .expect("corrupted row not reported");
+        assert!(report.starts_with("decode"), "{report}");
+        assert!(report.contains("opcode_bits[1] = 1"), "{report}");
+        assert!(report.contains("op.add = 1"), "{report}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_constraints() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let push1 = get_push_opcode(1);
+        let code = [push1, 1, push1, 2, get_opcode("ADD"), get_opcode("STOP")];
+        let mut trace = generate_trace::<F>(&code, CpuInputs::default())?;
+        assert_eq!(check_constraints(&trace), Ok(()));
+
+        // Make the ADD row's opcode bits disagree with its operation flag. The first constraint
+        // to fail is the one matching ADD's flag to its opcode, which `decode` emits after those
+        // on the kernel flag, the 8 opcode bits, the 38 operation flags and their sum.
+        let row: &mut CpuColumnsView<F> = trace[2].borrow_mut();
+        row.opcode_bits[1] = F::ONE;
+        assert_eq!(
+            check_constraints(&trace),
+            Err(ConstraintError {
+                row: 2,
+                module: "decode",
+                constraint: 1 + 8 + 38 + 1,
+            })
+        );
+
+        Ok(())
+    }
 }
@@ -5,6 +5,9 @@ use plonky2::iop::ext_target::ExtensionTarget;
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use crate::cpu::columns::{CpuColumnsView, COL_MAP};
+use crate::cpu::kernel::constants::exc_bitfields::INVALID_OPCODES_USER;
+use crate::cpu::kernel::opcodes::get_mnemonic;
+use crate::fork::Fork;
 
 /// List of opcode blocks
 ///  Each block corresponds to exactly one flag, and each flag corresponds to exactly one block.
@@ -22,7 +25,7 @@ use crate::cpu::columns::{CpuColumnsView, COL_MAP};
 /// behavior.
 /// Note: invalid opcodes are not represented here. _Any_ opcode is permitted to decode to
 /// `is_invalid`. The kernel then verifies that the opcode was _actually_ invalid.
-const OPCODES: [(u8, usize, bool, usize); 37] = [
+const OPCODES: [(u8, usize, bool, usize); 38] = [
     // (start index of block, number of top bits to check (log2), kernel-only, flag column)
     (0x01, 0, false, COL_MAP.op.add),
     (0x02, 0, false, COL_MAP.op.mul),
@@ -34,6 +37,7 @@ const OPCODES: [(u8, usize, bool, usize); 37] = [
     (0x0c, 0, true, COL_MAP.op.addfp254),
     (0x0d, 0, true, COL_MAP.op.mulfp254),
     (0x0e, 0, true, COL_MAP.op.subfp254),
+    (0x0f, 0, true, COL_MAP.op.submod),
     (0x10, 0, false, COL_MAP.op.lt),
     (0x11, 0, false, COL_MAP.op.gt),
     (0x14, 0, false, COL_MAP.op.eq),
@@ -109,6 +113,34 @@ pub fn generate<F: RichField>(lv: &mut CpuColumnsView<F>) {
     }
 }
 
+/// Disassemble user `code` into `(offset, instruction)` pairs. `PUSH` immediates are printed in
+/// hex, and padded with zeros if the code ends early, just as they are read during execution.
+/// Bytes which would decode to an invalid opcode in user mode under `fork` are listed as
+/// `INVALID`, followed by the byte itself.
+pub fn disassemble(code: &[u8], fork: Fork) -> Vec<(usize, String)> {
+    let mut listing = vec![];
+    let mut offset = 0;
+    while offset < code.len() {
+        let opcode = code[offset];
+        let is_push = (0x60..=0x7f).contains(&opcode);
+        let num_bytes = if is_push { (opcode - 0x5f) as usize } else { 0 };
+
+        let instruction = if INVALID_OPCODES_USER.bit(opcode as usize) || !fork.has_opcode(opcode) {
+            format!("INVALID 0x{opcode:02x}")
+        } else if is_push {
+            let immediate: String = (offset + 1..offset + 1 + num_bytes)
+                .map(|i| format!("{:02x}", code.get(i).copied().unwrap_or(0)))
+                .collect();
+            format!("{} 0x{immediate}", get_mnemonic(opcode))
+        } else {
+            get_mnemonic(opcode).to_string()
+        };
+        listing.push((offset, instruction));
+        offset += 1 + num_bytes;
+    }
+    listing
+}
+
 /// Break up an opcode (which is 8 bits long) into its eight bits.
 const fn bits_from_opcode(opcode: u8) -> [bool; 8] {
     [
@@ -255,3 +287,72 @@ pub fn eval_ext_circuit<F: RichField + Extendable<D>, const D: usize>(
         yield_constr.constraint(builder, constr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use crate::cpu::decode::disassemble;
+    use crate::fork::Fork;
+
+    fn instructions(code: &[u8], fork: Fork) -> Vec<String> {
+        disassemble(code, fork)
+            .into_iter()
+            .map(|(_, instruction)| instruction)
+            .collect()
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let listing = disassemble(&hex!("60016002015B00"), Fork::default());
+        assert_eq!(
+            listing,
+            vec![
+                (0, "PUSH1 0x01".to_string()),
+                (2, "PUSH1 0x02".to_string()),
+                (4, "ADD".to_string()),
+                (5, "JUMPDEST".to_string()),
+                (6, "STOP".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disassemble_invalid() {
+        // Kernel-only opcodes are invalid in user code, as is PUSH0 before Shanghai.
+        let code = hex!("0c5ff6fe5f");
+        assert_eq!(
+            instructions(&code, Fork::Shanghai),
+            [
+                "INVALID 0x0c",
+                "PUSH0",
+                "INVALID 0xf6",
+                "INVALID 0xfe",
+                "PUSH0"
+            ]
+        );
+        assert_eq!(
+            instructions(&code, Fork::Paris),
+            [
+                "INVALID 0x0c",
+                "INVALID 0x5f",
+                "INVALID 0xf6",
+                "INVALID 0xfe",
+                "INVALID 0x5f"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disassemble_truncated_push() {
+        // The immediate is padded with zeros, and bytes inside it aren't decoded.
+        assert_eq!(
+            instructions(&hex!("615b"), Fork::default()),
+            ["PUSH2 0x5b00"]
+        );
+        assert_eq!(
+            instructions(&hex!("625b5b5b5b"), Fork::default()),
+            ["PUSH3 0x5b5b5b", "JUMPDEST"]
+        );
+    }
+}
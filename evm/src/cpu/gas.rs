@@ -1,13 +1,15 @@
-use itertools::izip;
+use std::borrow::Borrow;
+
+use itertools::{izip, Itertools};
 use plonky2::field::extension::Extendable;
 use plonky2::field::packed::PackedField;
-use plonky2::field::types::Field;
+use plonky2::field::types::{Field, PrimeField64};
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use crate::cpu::columns::ops::OpsColumnsView;
-use crate::cpu::columns::CpuColumnsView;
+use crate::cpu::columns::{CpuColumnsView, NUM_CPU_COLUMNS};
 
 const KERNEL_ONLY_INSTR: Option<u32> = Some(0);
 const G_JUMPDEST: Option<u32> = Some(1);
@@ -195,3 +197,106 @@ pub fn eval_ext_circuit<F: RichField + Extendable<D>, const D: usize>(
     eval_ext_circuit_accumulate(builder, lv, nv, yield_constr);
     eval_ext_circuit_init(builder, lv, nv, yield_constr);
 }
+
+/// Exports the gas accounting of a CPU trace, such as one from `generate_trace`, for comparison
+/// against a reference client's trace. Each user instruction gives a `(step, opcode,
+/// gas_remaining_after)` entry, where `step` counts user instructions from 0 and `gas_limit` is
+/// the gas limit of the context running them. Kernel rows are skipped, so the gas charged by a
+/// syscall handler shows up in the entry of the syscall itself.
+///
+/// The remaining gas after an instruction is read from the `gas` column of the next user
+/// instruction, so only instructions followed by one in the same context get an entry. In
+/// particular, the last one, e.g. the final `STOP` at which `generate_trace` ends, has none. Fails
+/// if the trace uses more gas than `gas_limit`.
+pub fn gas_trace<F: PrimeField64>(
+    trace: &[[F; NUM_CPU_COLUMNS]],
+    gas_limit: u64,
+) -> anyhow::Result<Vec<(usize, u8, u64)>> {
+    trace
+        .iter()
+        .map(Borrow::<CpuColumnsView<F>>::borrow)
+        .filter(|row| row.is_cpu_cycle == F::ONE && row.is_kernel_mode == F::ZERO)
+        .tuple_windows()
+        .enumerate()
+        .filter(|(_, (lv, nv))| lv.context == nv.context)
+        .map(|(step, (lv, nv))| {
+            let opcode = lv.opcode_bits.iter().enumerate().fold(0, |acc, (i, bit)| {
+                acc | ((bit.to_canonical_u64() as u8) << i)
+            });
+            let gas_used = nv.gas.to_canonical_u64();
+            let gas_remaining = gas_limit.checked_sub(gas_used).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Step {step} leaves {gas_used} gas used, over the limit {gas_limit}"
+                )
+            })?;
+            Ok((step, opcode, gas_remaining))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::BorrowMut;
+
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::cpu::columns::CpuColumnsView;
+    use crate::cpu::cpu_stark::{generate_trace, CpuInputs};
+    use crate::cpu::gas::gas_trace;
+    use crate::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+
+    #[test]
+    fn test_gas_trace() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let (push1, add, pop, pc) = (
+            get_push_opcode(1),
+            get_opcode("ADD"),
+            get_opcode("POP"),
+            get_opcode("GETPC"),
+        );
+        let code = [push1, 1, push1, 2, add, pop, pc, get_opcode("STOP")];
+        let inputs = CpuInputs {
+            gas_limit: Some(100),
+            ..Default::default()
+        };
+        let trace = generate_trace::<F>(&code, inputs)?;
+
+        // PUSH1 and ADD cost 3, and POP and PC cost 2.
+        let expected = vec![
+            (0, push1, 97),
+            (1, push1, 94),
+            (2, add, 91),
+            (3, pop, 89),
+            (4, pc, 87),
+        ];
+        assert_eq!(gas_trace(&trace, 100)?, expected);
+
+        // The second PUSH1 takes the gas used past a limit of 5.
+        assert!(gas_trace(&trace, 5).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gas_trace_context_switch() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let (push1, pop, pc) = (get_push_opcode(1), get_opcode("POP"), get_opcode("GETPC"));
+        let code = [push1, 1, pop, pc, get_opcode("STOP")];
+        let mut trace = generate_trace::<F>(&code, CpuInputs::default())?;
+
+        // Pretend that the POP switched to another context, which ran the rest. The gas column
+        // there is unrelated to the POP, so it gets no entry.
+        for row in &mut trace[2..] {
+            let row: &mut CpuColumnsView<F> = row.borrow_mut();
+            row.context += F::ONE;
+        }
+        let expected = vec![(0, push1, 97), (2, pc, 93)];
+        assert_eq!(gas_trace(&trace, 100)?, expected);
+
+        Ok(())
+    }
+}
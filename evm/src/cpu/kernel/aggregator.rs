@@ -28,6 +28,7 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/core/create.asm"),
         include_str!("asm/core/create_addresses.asm"),
         include_str!("asm/core/create_contract_account.asm"),
+        include_str!("asm/core/created_contracts.asm"),
         include_str!("asm/core/exception.asm"),
         include_str!("asm/core/gas.asm"),
         include_str!("asm/core/intrinsic_gas.asm"),
@@ -43,6 +44,7 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/core/log.asm"),
         include_str!("asm/core/selfdestruct_list.asm"),
         include_str!("asm/core/touched_addresses.asm"),
+        include_str!("asm/core/transient_storage.asm"),
         include_str!("asm/core/precompiles/main.asm"),
         include_str!("asm/core/precompiles/ecrec.asm"),
         include_str!("asm/core/precompiles/sha256.asm"),
@@ -146,6 +148,9 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/journal/code_change.asm"),
         include_str!("asm/journal/refund.asm"),
         include_str!("asm/journal/account_created.asm"),
+        include_str!("asm/journal/log.asm"),
+        include_str!("asm/journal/transient_storage_change.asm"),
+        include_str!("asm/journal/contract_created.asm"),
         include_str!("asm/journal/revert.asm"),
         include_str!("asm/transactions/common_decoding.asm"),
         include_str!("asm/transactions/router.asm"),
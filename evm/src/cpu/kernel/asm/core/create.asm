@@ -254,14 +254,23 @@ global set_codehash:
     // stack: retdest
     JUMP
 
-// Check and charge gas cost for initcode size. See EIP-3860.
+// Check and charge gas cost for initcode size. See EIP-3860, which only applies from Shanghai
+// onwards.
 // Pre stack: code_size, kexit_info
 // Post stack: kexit_info
 %macro check_initcode_size
+    %before_fork(@FORK_SHANGHAI) %jumpi(%%skip)
+    // stack: code_size, kexit_info
     DUP1 %gt_const(@MAX_INITCODE_SIZE) %jumpi(fault_exception)
     // stack: code_size, kexit_info
     %num_bytes_to_num_words %mul_const(@INITCODE_WORD_COST)
     %charge_gas
+    %jump(%%done)
+%%skip:
+    // stack: code_size, kexit_info
+    POP
+%%done:
+    // stack: kexit_info
 %endmacro
 
 
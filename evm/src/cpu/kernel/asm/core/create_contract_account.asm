@@ -29,6 +29,8 @@
     // stack: existing_balance, address
     DUP2 %journal_add_account_created
 %%do_insert:
+    // stack: new_acct_value, address
+    DUP2 %insert_created_contracts
     // stack: new_acct_value, address
     // Write the new account's data to MPT data, and get a pointer to it.
     %get_trie_data_size
@@ -0,0 +1,52 @@
+/// List of the contracts created in the current transaction, which EIP-6780 allows to
+/// self-destruct.
+/// Implemented as an array, with the length stored in the global metadata.
+/// Note: This array allows duplicates.
+
+%macro insert_created_contracts
+    // stack: addr
+    DUP1 %journal_add_contract_created // Add a journal entry for the created contract.
+    %mload_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+    %stack (len, addr) -> (len, addr, len)
+    %mstore_kernel(@SEGMENT_CREATED_CONTRACTS) // Store new address at the end of the array.
+    // stack: len
+    %increment
+    %mstore_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN) // Store new length.
+%endmacro
+
+/// Returns 1 if the contract at `addr` was created in the current transaction, 0 otherwise.
+global contract_just_created:
+    // stack: addr, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+    // stack: len, addr, retdest
+    PUSH 0
+contract_just_created_loop:
+    // stack: i, len, addr, retdest
+    DUP2 DUP2 EQ %jumpi(contract_just_created_false)
+    // stack: i, len, addr, retdest
+    DUP1 %mload_kernel(@SEGMENT_CREATED_CONTRACTS)
+    // stack: loaded_addr, i, len, addr, retdest
+    DUP4 EQ %jumpi(contract_just_created_true)
+    // stack: i, len, addr, retdest
+    %increment
+    %jump(contract_just_created_loop)
+contract_just_created_true:
+    %stack (i, len, addr, retdest) -> (retdest, 1)
+    JUMP
+contract_just_created_false:
+    %stack (i, len, addr, retdest) -> (retdest, 0)
+    JUMP
+
+%macro contract_just_created
+    // stack: addr
+    %stack (addr) -> (addr, %%after)
+    %jump(contract_just_created)
+%%after:
+    // stack: contract_just_created
+%endmacro
+
+%macro clear_created_contracts
+    // stack: (empty)
+    PUSH 0
+    %mstore_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+%endmacro
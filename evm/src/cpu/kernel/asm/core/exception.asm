@@ -24,15 +24,35 @@ global exception_jumptable:
 
 
 global exc_out_of_gas:
-    // TODO
-    %jump(fault_exception)
+    // stack: trap_info
+    // check that the static cost of the triggering opcode does exceed the gas left; the dynamic
+    // costs of syscalls are charged by their handlers, which fault on their own
+    DUP1 %shr_const(192)
+    // stack: gas_used, trap_info
+    SWAP1
+    %opcode_from_exp_trap_info
+    %add_const(gas_cost_for_opcode)
+    %mload_kernel_code
+    // stack: gas_cost, gas_used
+    ADD
+    // stack: gas_used', where gas_used' = gas_used + gas_cost
+    %ctx_gas_limit
+    // stack: gas_limit, gas_used'
+    LT
+    // stack: gas_limit < gas_used'
+    %jumpi(fault_exception)
+    // otherwise, panic because this trap should not have been entered
+    PANIC
 
 
 global exc_invalid_opcode:
     // stack: trap_info
-    // check if the opcode that triggered this trap is _actually_ invalid
+    // check if the opcode that triggered this trap is _actually_ invalid, either in general or
+    // because the configured fork predates it
     %opcode_from_exp_trap_info
+    %inactive_opcodes
     PUSH @INVALID_OPCODES_USER
+    OR
     // stack: invalid_opcodes_user, opcode
     SWAP1
     // stack: opcode, invalid_opcodes_user
@@ -227,9 +247,9 @@ min_stack_len_for_opcode:
     BYTES 0  // 0x59, MSIZE
     BYTES 0  // 0x5a, GAS
     BYTES 0  // 0x5b, JUMPDEST
-    %rep 3  // 0x5c-0x5e, invalid
-        BYTES 0
-    %endrep
+    BYTES 1  // 0x5c, TLOAD
+    BYTES 2  // 0x5d, TSTORE
+    BYTES 3  // 0x5e, MCOPY
 
     %rep 33 // 0x5f-0x7f, PUSH0-PUSH32
         BYTES 0
@@ -297,3 +317,53 @@ min_stack_len_for_opcode:
     BYTES 2  // 0xfd, REVERT
     BYTES 0  // 0xfe, invalid
     BYTES 1  // 0xff, SELFDESTRUCT
+
+
+// The static cost of the opcodes that are charged natively, i.e. without a syscall. It is 0 for
+// all other opcodes, which therefore never trigger exc_out_of_gas.
+gas_cost_for_opcode:
+    BYTES 0  // 0x00, STOP
+    BYTES 3  // 0x01, ADD
+    BYTES 5  // 0x02, MUL
+    BYTES 3  // 0x03, SUB
+    BYTES 5  // 0x04, DIV
+    BYTES 0  // 0x05, SDIV
+    BYTES 5  // 0x06, MOD
+    BYTES 0  // 0x07, SMOD
+    BYTES 8  // 0x08, ADDMOD
+    BYTES 8  // 0x09, MULMOD
+    %rep 6  // 0x0a-0x0f, EXP, SIGNEXTEND and invalid
+        BYTES 0
+    %endrep
+
+    BYTES 3  // 0x10, LT
+    BYTES 3  // 0x11, GT
+    BYTES 0  // 0x12, SLT
+    BYTES 0  // 0x13, SGT
+    %rep 9  // 0x14-0x1c, EQ, ISZERO, AND, OR, XOR, NOT, BYTE, SHL and SHR
+        BYTES 3
+    %endrep
+    %rep 51  // 0x1d-0x4f, SAR, KECCAK256, environment and block information, and invalid
+        BYTES 0
+    %endrep
+
+    BYTES 2  // 0x50, POP
+    %rep 5  // 0x51-0x55, MLOAD, MSTORE, MSTORE8, SLOAD and SSTORE
+        BYTES 0
+    %endrep
+    BYTES 8  // 0x56, JUMP
+    BYTES 10 // 0x57, JUMPI
+    BYTES 2  // 0x58, PC
+    BYTES 0  // 0x59, MSIZE
+    BYTES 0  // 0x5a, GAS
+    BYTES 1  // 0x5b, JUMPDEST
+    %rep 3  // 0x5c-0x5e, TLOAD, TSTORE and MCOPY
+        BYTES 0
+    %endrep
+    BYTES 2  // 0x5f, PUSH0
+    %rep 64  // 0x60-0x9f, PUSH1-PUSH32, DUP1-DUP16 and SWAP1-SWAP16
+        BYTES 3
+    %endrep
+    %rep 96  // 0xa0-0xff, logging, system operations and invalid
+        BYTES 0
+    %endrep
@@ -34,6 +34,28 @@ global refund_gas_hook:
     %mstore_global_metadata(@GLOBAL_METADATA_REFUND_COUNTER)
 %endmacro
 
+// Returns the refund for clearing a storage slot, which London lowered (EIP-3529).
+%macro refund_sclear
+    // stack: (empty)
+    PUSH @REFUND_SCLEAR
+    PUSH @REFUND_SCLEAR_BEFORE_LONDON
+    %before_fork(@FORK_LONDON)
+    // stack: before_london, refund_sclear_before_london, refund_sclear
+    %select
+    // stack: refund_sclear
+%endmacro
+
+// Returns `q` such that the refund is capped at `used_gas / q`, which London raised (EIP-3529).
+%macro max_refund_quotient
+    // stack: (empty)
+    PUSH @MAX_REFUND_QUOTIENT
+    PUSH @MAX_REFUND_QUOTIENT_BEFORE_LONDON
+    %before_fork(@FORK_LONDON)
+    // stack: before_london, max_refund_quotient_before_london, max_refund_quotient
+    %select
+    // stack: max_refund_quotient
+%endmacro
+
 // TODO: `%charge_gas` and `charge_gas_hook` are hooks used for debugging. They should be removed at some point and `charge_gas_original` renamed to `charge_gas`.
 %macro charge_gas
     PUSH %%after %jump(charge_gas_hook)
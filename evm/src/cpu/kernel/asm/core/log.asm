@@ -1,3 +1,6 @@
+// Each log is appended to SEGMENT_LOGS_DATA as
+//     address, num_topics, topic_1, ..., topic_num_topics, data_len, data_0, ..., data_{data_len - 1}
+// and a pointer to it is appended to SEGMENT_LOGS.
 // TODO: Implement receipts
 
 global sys_log0:
@@ -14,8 +17,8 @@ log0_after_mem_gas:
     DUP3 %mul_const(@GAS_LOGDATA) %add_const(@GAS_LOG)
     // stack: gas, kexit_info, offset, size
     %charge_gas
-    %stack (kexit_info, offset, size) -> (kexit_info)
-    EXIT_KERNEL
+    %stack (kexit_info, offset, size) -> (0, offset, size, finish_sys_log, kexit_info)
+    %jump(log_n_entry)
 
 global sys_log1:
     %check_static
@@ -31,8 +34,8 @@ log1_after_mem_gas:
     DUP3 %mul_const(@GAS_LOGDATA) %add_const(@GAS_LOG) %add_const(@GAS_LOGTOPIC)
     // stack: gas, kexit_info, offset, size, topic
     %charge_gas
-    %stack (kexit_info, offset, size, topic) -> (kexit_info)
-    EXIT_KERNEL
+    %stack (kexit_info, offset, size, topic) -> (1, offset, size, topic, finish_sys_log, kexit_info)
+    %jump(log_n_entry)
 
 global sys_log2:
     %check_static
@@ -48,8 +51,8 @@ log2_after_mem_gas:
     DUP3 %mul_const(@GAS_LOGDATA) %add_const(@GAS_LOG) %add_const(@GAS_LOGTOPIC) %add_const(@GAS_LOGTOPIC)
     // stack: gas, kexit_info, offset, size, topic1, topic2
     %charge_gas
-    %stack (kexit_info, offset, size, topic1, topic2) -> (kexit_info)
-    EXIT_KERNEL
+    %stack (kexit_info, offset, size, topic1, topic2) -> (2, offset, size, topic1, topic2, finish_sys_log, kexit_info)
+    %jump(log_n_entry)
 
 global sys_log3:
     %check_static
@@ -65,8 +68,8 @@ log3_after_mem_gas:
     DUP3 %mul_const(@GAS_LOGDATA) %add_const(@GAS_LOG) %add_const(@GAS_LOGTOPIC) %add_const(@GAS_LOGTOPIC) %add_const(@GAS_LOGTOPIC)
     // stack: gas, kexit_info, offset, size, topic1, topic2, topic3
     %charge_gas
-    %stack (kexit_info, offset, size, topic1, topic2, topic3) -> (kexit_info)
-    EXIT_KERNEL
+    %stack (kexit_info, offset, size, topic1, topic2, topic3) -> (3, offset, size, topic1, topic2, topic3, finish_sys_log, kexit_info)
+    %jump(log_n_entry)
 
 global sys_log4:
     %check_static
@@ -82,5 +85,60 @@ log4_after_mem_gas:
     DUP3 %mul_const(@GAS_LOGDATA) %add_const(@GAS_LOG) %add_const(@GAS_LOGTOPIC) %add_const(@GAS_LOGTOPIC) %add_const(@GAS_LOGTOPIC) %add_const(@GAS_LOGTOPIC)
     // stack: gas, kexit_info, offset, size, topic1, topic2, topic3, topic4
     %charge_gas
-    %stack (kexit_info, offset, size, topic1, topic2, topic3, topic4) -> (kexit_info)
+    %stack (kexit_info, offset, size, topic1, topic2, topic3, topic4) -> (4, offset, size, topic1, topic2, topic3, topic4, finish_sys_log, kexit_info)
+    %jump(log_n_entry)
+
+finish_sys_log:
+    // stack: kexit_info
     EXIT_KERNEL
+
+// Records a log emitted by the current context's address, with data taken from its main memory.
+// Pre stack: num_topics, offset, size, topic_1, ..., topic_num_topics, retdest
+// Post stack: (empty)
+global log_n_entry:
+    // stack: num_topics, offset, size, topics, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_LOGS_DATA_LEN)
+    // stack: log_ptr, num_topics, offset, size, topics, retdest
+    DUP1 %journal_add_log
+    %mload_global_metadata(@GLOBAL_METADATA_LOGS_LEN)
+    // stack: logs_len, log_ptr, num_topics, offset, size, topics, retdest
+    DUP1 %increment %mstore_global_metadata(@GLOBAL_METADATA_LOGS_LEN)
+    DUP2 SWAP1
+    // stack: logs_len, log_ptr, log_ptr, num_topics, offset, size, topics, retdest
+    %mstore_kernel(@SEGMENT_LOGS)
+    // stack: log_ptr, num_topics, offset, size, topics, retdest
+    %address DUP2
+    // stack: log_ptr, address, log_ptr, num_topics, offset, size, topics, retdest
+    %mstore_kernel(@SEGMENT_LOGS_DATA)
+    %increment
+    // stack: ptr, num_topics, offset, size, topics, retdest
+    DUP2 DUP2
+    // stack: ptr, num_topics, ptr, num_topics, offset, size, topics, retdest
+    %mstore_kernel(@SEGMENT_LOGS_DATA)
+    %increment
+log_n_entry_topics_loop:
+    // stack: ptr, num_topics, offset, size, topics, retdest
+    DUP2 ISZERO %jumpi(log_n_entry_topics_done)
+    // stack: ptr, num_topics, offset, size, topic, topics', retdest
+    DUP5 DUP2
+    // stack: ptr, topic, ptr, num_topics, offset, size, topic, topics', retdest
+    %mstore_kernel(@SEGMENT_LOGS_DATA)
+    %stack (ptr, num_topics, offset, size, topic) -> (ptr, num_topics, offset, size)
+    %increment SWAP1 %decrement SWAP1
+    %jump(log_n_entry_topics_loop)
+log_n_entry_topics_done:
+    // stack: ptr, 0, offset, size, retdest
+    SWAP1 POP
+    // stack: ptr, offset, size, retdest
+    DUP3 DUP2
+    // stack: ptr, size, ptr, offset, size, retdest
+    %mstore_kernel(@SEGMENT_LOGS_DATA)
+    %increment
+    // stack: data_ptr, offset, size, retdest
+    DUP3 DUP2 ADD
+    // stack: data_ptr + size, data_ptr, offset, size, retdest
+    %mstore_global_metadata(@GLOBAL_METADATA_LOGS_DATA_LEN)
+    GET_CONTEXT
+    %stack (context, data_ptr, offset, size, retdest) ->
+        (0, @SEGMENT_LOGS_DATA, data_ptr, context, @SEGMENT_MAIN_MEMORY, offset, size, retdest)
+    %jump(memcpy)
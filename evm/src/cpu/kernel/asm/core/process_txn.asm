@@ -6,6 +6,13 @@
 // Pre stack: retdest
 // Post stack: (empty)
 global process_normalized_txn:
+    // stack: retdest
+    %clear_transient_storage
+    // stack: retdest
+    %clear_created_contracts
+    // stack: retdest
+    // The status is only set once the transaction succeeds.
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_TXN_STATUS)
     // stack: retdest
     %compute_fees
     // stack: retdest
@@ -212,6 +219,7 @@ global process_contract_creation_txn_after_constructor:
     %set_codehash
 
     // stack: leftover_gas, new_ctx, address, retdest
+    PUSH 1 %mstore_global_metadata(@GLOBAL_METADATA_TXN_STATUS)
     %pay_coinbase_and_refund_sender
     %delete_all_touched_addresses
     %delete_all_selfdestructed_addresses
@@ -259,6 +267,7 @@ global process_message_txn_return:
     // Since no code was executed, the leftover gas is the non-intrinsic gas.
     %non_intrinisic_gas
     // stack: leftover_gas, retdest
+    PUSH 1 %mstore_global_metadata(@GLOBAL_METADATA_TXN_STATUS)
     %pay_coinbase_and_refund_sender
     %delete_all_touched_addresses
     // stack: retdest
@@ -291,7 +300,7 @@ process_message_txn_code_loaded_finish:
 
 global process_message_txn_after_call:
     // stack: success, leftover_gas, new_ctx, retdest
-    DUP1 POP // TODO: Success will go into the receipt when we support that.
+    DUP1 %mstore_global_metadata(@GLOBAL_METADATA_TXN_STATUS)
     ISZERO %jumpi(process_message_txn_fail)
 process_message_txn_after_call_contd:
     // stack: leftover_gas, new_ctx, retdest
@@ -321,7 +330,7 @@ process_message_txn_fail:
     // stack: used_gas, leftover_gas
     %mload_global_metadata(@GLOBAL_METADATA_REFUND_COUNTER)
     // stack: refund, used_gas, leftover_gas
-    DUP2 %div_const(@MAX_REFUND_QUOTIENT) // max_refund = used_gas/5
+    %max_refund_quotient DUP3 DIV // max_refund = used_gas/5, or used_gas/2 before London
     // stack: max_refund, refund, used_gas, leftover_gas
     %min
     %stack (refund, used_gas, leftover_gas) -> (leftover_gas, refund, refund, used_gas)
@@ -331,6 +340,7 @@ process_message_txn_fail:
     // stack: used_gas, refund, leftover_gas'
     SUB
     // stack: used_gas', leftover_gas'
+    DUP1 %mstore_global_metadata(@GLOBAL_METADATA_TXN_GAS_USED)
 
     // Pay the coinbase.
     %mload_txn_field(@TXN_FIELD_COMPUTED_PRIORITY_FEE_PER_GAS)
@@ -12,6 +12,37 @@
     %mstore_global_metadata(@GLOBAL_METADATA_SELFDESTRUCT_LIST_LEN) // Store new length.
 %endmacro
 
+/// Returns 1 if the address is in the list, 0 otherwise.
+global selfdestruct_list_contains:
+    // stack: addr, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_SELFDESTRUCT_LIST_LEN)
+    // stack: len, addr, retdest
+    PUSH 0
+selfdestruct_list_contains_loop:
+    // stack: i, len, addr, retdest
+    DUP2 DUP2 EQ %jumpi(selfdestruct_list_contains_false)
+    // stack: i, len, addr, retdest
+    DUP1 %mload_kernel(@SEGMENT_SELFDESTRUCT_LIST)
+    // stack: loaded_addr, i, len, addr, retdest
+    DUP4 EQ %jumpi(selfdestruct_list_contains_true)
+    // stack: i, len, addr, retdest
+    %increment
+    %jump(selfdestruct_list_contains_loop)
+selfdestruct_list_contains_true:
+    %stack (i, len, addr, retdest) -> (retdest, 1)
+    JUMP
+selfdestruct_list_contains_false:
+    %stack (i, len, addr, retdest) -> (retdest, 0)
+    JUMP
+
+%macro selfdestruct_list_contains
+    // stack: addr
+    %stack (addr) -> (addr, %%after)
+    %jump(selfdestruct_list_contains)
+%%after:
+    // stack: contains
+%endmacro
+
 /// Remove one occurrence of the address from the list.
 /// Panics if the address is not in the list.
 global remove_selfdestruct_list:
@@ -86,9 +86,9 @@ global syscall_jumptable:
     JUMPTABLE sys_msize
     JUMPTABLE sys_gas
     JUMPTABLE panic // jumpdest is implemented natively
-    JUMPTABLE panic // 0x5c is an invalid opcode
-    JUMPTABLE panic // 0x5d is an invalid opcode
-    JUMPTABLE panic // 0x5e is an invalid opcode
+    JUMPTABLE sys_tload
+    JUMPTABLE sys_tstore
+    JUMPTABLE sys_mcopy
     JUMPTABLE panic // 0x5f is an invalid opcode
 
     // 0x60-0x6f
@@ -77,6 +77,41 @@ global sys_selfdestruct:
     %charge_gas
     %stack (kexit_info, balance, address, recipient) -> (balance, address, recipient, kexit_info)
 
+    // Since Cancun (EIP-6780), an account is only destroyed if it was created in the same
+    // transaction. Otherwise, its balance is sent to the recipient and nothing else changes.
+    // stack: balance, address, recipient, kexit_info
+    %before_fork(@FORK_CANCUN) %jumpi(sys_selfdestruct_destroy)
+    DUP2 %contract_just_created %jumpi(sys_selfdestruct_destroy)
+    // stack: balance, address, recipient, kexit_info
+    DUP3 DUP3 EQ %jumpi(sys_selfdestruct_keep_balance)
+    %stack (balance, address, recipient) ->
+        (address, recipient, balance, address, recipient, balance)
+    %transfer_eth %jumpi(panic) // This should never happen, as we send exactly the balance.
+    // stack: address, recipient, balance, kexit_info
+    %journal_add_balance_transfer
+    // stack: kexit_info
+    %leftover_gas
+    // stack: leftover_gas
+    PUSH 1 // success
+    %jump(terminate_common)
+
+sys_selfdestruct_keep_balance:
+    // stack: balance, address, recipient, kexit_info
+    %pop3
+    %leftover_gas
+    // stack: leftover_gas
+    PUSH 1 // success
+    %jump(terminate_common)
+
+sys_selfdestruct_destroy:
+    // Before London (EIP-3529), destroying an account for the first time in a transaction earns
+    // a refund.
+    // stack: balance, address, recipient, kexit_info
+    %before_fork(@FORK_LONDON) ISZERO %jumpi(sys_selfdestruct_insert)
+    DUP2 %selfdestruct_list_contains %jumpi(sys_selfdestruct_insert)
+    PUSH @REFUND_SELFDESTRUCT %refund_gas
+
+sys_selfdestruct_insert:
     // Insert address into the selfdestruct set.
     // stack: balance, address, recipient, kexit_info
     DUP2 %insert_selfdestruct_list
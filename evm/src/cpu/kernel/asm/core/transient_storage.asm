@@ -0,0 +1,144 @@
+/// Transient storage (EIP-1153), used by TLOAD and TSTORE.
+/// Transient storage is stored in an array of `address, key, value` triples in the
+/// SEGMENT_TRANSIENT_STORAGE segment, in kernel memory (context=0). The length of the array is
+/// stored in the global metadata.
+/// Searching is done by doing a linear search through the array. New keys are inserted at the end.
+/// Unlike storage, none of this is ever written to the state trie; the array is simply cleared at
+/// the start of each transaction.
+
+global sys_tload:
+    // stack: kexit_info, key
+    PUSH @GAS_WARMACCESS
+    %charge_gas
+    // stack: kexit_info, key
+    SWAP1 %address
+    // stack: addr, key, kexit_info
+    %tload
+    // stack: value, kexit_info
+    SWAP1
+    EXIT_KERNEL
+
+global sys_tstore:
+    // stack: kexit_info, key, value
+    %check_static
+    PUSH @GAS_WARMACCESS
+    %charge_gas
+    %stack (kexit_info, key, value) -> (key, value, after_tstore, kexit_info)
+    %address
+    // stack: addr, key, value, after_tstore, kexit_info
+    %jump(tstore)
+after_tstore:
+    // stack: kexit_info
+    EXIT_KERNEL
+
+%macro tload
+    %stack (addr, key) -> (addr, key, %%after)
+    %jump(tload)
+%%after:
+    // stack: value
+%endmacro
+
+/// Returns the transient storage value at `(addr, key)`, or 0 if it was never set.
+global tload:
+    // stack: addr, key, retdest
+    %search_transient_storage
+    // stack: i, retdest
+    DUP1 %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN) EQ
+    // stack: i == len, i, retdest
+    %jumpi(tload_not_found)
+    // stack: i, retdest
+    %add_const(2)
+    %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: value, retdest
+    SWAP1 JUMP
+tload_not_found:
+    // stack: i, retdest
+    %stack (i, retdest) -> (retdest, 0)
+    JUMP
+
+/// Sets the transient storage value at `(addr, key)`, adding a journal entry so that the
+/// previous value is restored if the current context reverts.
+global tstore:
+    // stack: addr, key, value, retdest
+    DUP2 DUP2 %tload
+    // stack: prev_value, addr, key, value, retdest
+    DUP3 DUP3
+    // stack: addr, key, prev_value, addr, key, value, retdest
+    %journal_add_transient_storage_change
+    // stack: addr, key, value, retdest
+    %jump(set_transient_storage)
+
+/// Sets the transient storage value at `(addr, key)` without adding a journal entry.
+global set_transient_storage:
+    // stack: addr, key, value, retdest
+    DUP2 DUP2 %search_transient_storage
+    // stack: i, addr, key, value, retdest
+    DUP1 %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN) EQ
+    // stack: i == len, i, addr, key, value, retdest
+    %jumpi(insert_transient_storage_key)
+    // stack: i, addr, key, value, retdest
+    %stack (i, addr, key, value) -> (i, value)
+    %add_const(2)
+    %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Overwrite the existing value.
+    // stack: retdest
+    JUMP
+insert_transient_storage_key:
+    // stack: i, addr, key, value, retdest
+    DUP1 %add_const(3)
+    %mstore_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN) // Store new length.
+    DUP1 %increment
+    DUP1 %increment
+    %stack (i_plus_2, i_plus_1, i, addr, key, value) -> (i, addr, i_plus_1, key, i_plus_2, value)
+    %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Store new address at the end of the array.
+    %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Store new key after that
+    %mstore_kernel(@SEGMENT_TRANSIENT_STORAGE) // Store new value after that
+    // stack: retdest
+    JUMP
+
+%macro search_transient_storage
+    %stack (addr, key) -> (addr, key, %%after)
+    %jump(search_transient_storage)
+%%after:
+    // stack: i
+%endmacro
+
+/// Returns the index of the triple for `(addr, key)`, or the length of the array if not present.
+search_transient_storage:
+    // stack: addr, key, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
+    // stack: len, addr, key, retdest
+    PUSH 0
+search_transient_storage_loop:
+    // stack: i, len, addr, key, retdest
+    DUP2 DUP2 EQ %jumpi(search_transient_storage_done)
+    // stack: i, len, addr, key, retdest
+    DUP1 %increment %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: loaded_key, i, len, addr, key, retdest
+    DUP2 %mload_kernel(@SEGMENT_TRANSIENT_STORAGE)
+    // stack: loaded_addr, loaded_key, i, len, addr, key, retdest
+    DUP5 EQ
+    // stack: loaded_addr==addr, loaded_key, i, len, addr, key, retdest
+    SWAP1 DUP6 EQ
+    // stack: loaded_key==key, loaded_addr==addr, i, len, addr, key, retdest
+    MUL // AND
+    %jumpi(search_transient_storage_done)
+    // stack: i, len, addr, key, retdest
+    %add_const(3)
+    %jump(search_transient_storage_loop)
+search_transient_storage_done:
+    %stack (i, len, addr, key, retdest) -> (retdest, i)
+    JUMP
+
+%macro clear_transient_storage
+    PUSH %%after
+    %jump(clear_transient_storage)
+%%after:
+    // stack: (empty)
+%endmacro
+
+/// Discards all transient storage. Called at the start of each transaction.
+global clear_transient_storage:
+    // stack: retdest
+    PUSH 0
+    %mstore_global_metadata(@GLOBAL_METADATA_TRANSIENT_STORAGE_LEN)
+    JUMP
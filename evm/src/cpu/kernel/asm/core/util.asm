@@ -87,3 +87,26 @@
     %mload_current(@SEGMENT_CONTEXT_METADATA)
     // stack: stack_length
 %endmacro
+
+// Returns whether the configured fork predates `fork`, one of the `FORK_*` constants.
+%macro before_fork(fork)
+    // stack: (empty)
+    %mload_global_metadata(@GLOBAL_METADATA_FORK)
+    %lt_const($fork)
+    // stack: before_fork
+%endmacro
+
+// Returns the bitmask of opcodes that are not yet active in the configured fork, in the same
+// layout as `INVALID_OPCODES_USER`. Berlin and Paris introduced no opcodes.
+%macro inactive_opcodes
+    // stack: (empty)
+    %before_fork(@FORK_LONDON)
+    %mul_const(@FORK_LONDON_OPCODES)
+    %before_fork(@FORK_SHANGHAI)
+    %mul_const(@FORK_SHANGHAI_OPCODES)
+    ADD
+    %before_fork(@FORK_CANCUN)
+    %mul_const(@FORK_CANCUN_OPCODES)
+    ADD
+    // stack: inactive_opcodes
+%endmacro
@@ -0,0 +1,22 @@
+// struct ContractCreated { address }
+
+%macro journal_add_contract_created
+    %journal_add_1(@JOURNAL_ENTRY_CONTRACT_CREATED)
+%endmacro
+
+global revert_contract_created:
+    // stack: entry_type, ptr, retdest
+    POP
+    %journal_load_1
+    // stack: address, retdest
+    // Entries are reverted in reverse order, so `address` is the last one in the list.
+    %mload_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+    %decrement
+    // stack: len-1, address, retdest
+    DUP1 %mload_kernel(@SEGMENT_CREATED_CONTRACTS)
+    // stack: last_address, len-1, address, retdest
+    DUP3 EQ ISZERO %jumpi(panic) // Sanity check. This should never happen.
+    // stack: len-1, address, retdest
+    %mstore_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+    // stack: address, retdest
+    POP JUMP
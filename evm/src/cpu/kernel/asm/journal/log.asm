@@ -0,0 +1,18 @@
+// struct Log { log_ptr }
+
+%macro journal_add_log
+    %journal_add_1(@JOURNAL_ENTRY_LOG)
+%endmacro
+
+global revert_log:
+    // stack: entry_type, ptr, retdest
+    POP
+    %journal_load_1
+    // stack: log_ptr, retdest
+    // The log was the last entry appended to SEGMENT_LOGS_DATA, so truncate the data there.
+    %mstore_global_metadata(@GLOBAL_METADATA_LOGS_DATA_LEN)
+    // stack: retdest
+    %mload_global_metadata(@GLOBAL_METADATA_LOGS_LEN)
+    %decrement
+    %mstore_global_metadata(@GLOBAL_METADATA_LOGS_LEN)
+    JUMP
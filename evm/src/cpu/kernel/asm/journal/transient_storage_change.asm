@@ -0,0 +1,12 @@
+// struct TransientStorageChange { address, key, prev_value }
+
+%macro journal_add_transient_storage_change
+    %journal_add_3(@JOURNAL_ENTRY_TRANSIENT_STORAGE_CHANGE)
+%endmacro
+
+global revert_transient_storage_change:
+    // stack: entry_type, ptr, retdest
+    POP
+    %journal_load_3
+    // stack: address, key, prev_value, retdest
+    %jump(set_transient_storage)
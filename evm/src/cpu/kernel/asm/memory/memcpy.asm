@@ -42,6 +42,43 @@ global memcpy:
     // Continue the loop.
     %jump(memcpy)
 
+// Same as memcpy, but copies the values in reverse order, starting with the last one. This makes
+// it safe to use when DST and SRC overlap with DST after SRC.
+global memcpy_backwards:
+    // stack: DST, SRC, count, retdest
+    DUP7
+    // stack: count, DST, SRC, count, retdest
+    ISZERO
+    // stack: count == 0, DST, SRC, count, retdest
+    %jumpi(memcpy_finish)
+    // stack: DST, SRC, count, retdest
+
+    // Decrement count.
+    SWAP6
+    %decrement
+    SWAP6
+
+    // Copy the value at index count.
+    DUP7
+    DUP7
+    ADD
+    DUP6
+    DUP6
+    // stack: src_ctx, src_segment, src_addr + count, DST, SRC, count, retdest
+    MLOAD_GENERAL
+    // stack: value, DST, SRC, count, retdest
+    DUP8
+    DUP5
+    ADD
+    DUP4
+    DUP4
+    // stack: dst_ctx, dst_segment, dst_addr + count, value, DST, SRC, count, retdest
+    MSTORE_GENERAL
+    // stack: DST, SRC, count, retdest
+
+    // Continue the loop.
+    %jump(memcpy_backwards)
+
 memcpy_finish:
     // stack: DST, SRC, count, retdest
     %pop7
@@ -235,6 +235,46 @@ global sys_basefee:
     SWAP1
     EXIT_KERNEL
 
+global sys_blockhash:
+    // stack: kexit_info, block_number
+    %charge_gas_const(@GAS_BLOCKHASH)
+    // stack: kexit_info, block_number
+    SWAP1
+    %stack (block_number) -> (block_number, sys_blockhash_after)
+    %jump(blockhash)
+sys_blockhash_after:
+    // stack: blockhash, kexit_info
+    SWAP1
+    EXIT_KERNEL
+
+// Returns the hash of block `block_number` if it is one of the 256 most recent complete blocks,
+// and 0 otherwise. SEGMENT_BLOCK_HASHES holds the hashes of those blocks, oldest first.
+global blockhash:
+    // stack: block_number, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_BLOCK_NUMBER)
+    // stack: cur_block_number, block_number, retdest
+    DUP2 DUP2 GT ISZERO
+    // stack: block_number >= cur_block_number, cur_block_number, block_number, retdest
+    %jumpi(blockhash_not_complete)
+    // stack: cur_block_number, block_number, retdest
+    SUB
+    // stack: depth, retdest
+    DUP1 %gt_const(256)
+    // stack: depth > 256, depth, retdest
+    %jumpi(blockhash_too_old)
+    // stack: depth, retdest
+    PUSH 256 SUB
+    // stack: 256 - depth, retdest
+    %mload_kernel(@SEGMENT_BLOCK_HASHES)
+    // stack: blockhash, retdest
+    SWAP1 JUMP
+blockhash_not_complete:
+    %stack (cur_block_number, block_number, retdest) -> (retdest, 0)
+    JUMP
+blockhash_too_old:
+    %stack (depth, retdest) -> (retdest, 0)
+    JUMP
+
 %macro update_mem_words
     // stack: num_words, kexit_info
     %mem_words
@@ -185,12 +185,8 @@ global sys_returndatacopy:
     // stack: Gverylow, kexit_info, dest_offset, offset, size
     DUP5 %num_bytes_to_num_words %mul_const(@GAS_COPY) ADD %charge_gas
 
-    %stack (kexit_info, dest_offset, offset, size) -> (dest_offset, size, kexit_info, dest_offset, offset, size)
-    %add_or_fault
-    // stack: expanded_num_bytes, kexit_info, dest_offset, offset, size, kexit_info
-    DUP1 %ensure_reasonable_offset
-    %update_mem_bytes
-    // stack: kexit_info, dest_offset, offset, size, kexit_info
+    // The bounds of the return data are checked even when nothing is copied.
+    // stack: kexit_info, dest_offset, offset, size
     DUP4 DUP4 %add_or_fault // Overflow check
     %mload_context_metadata(@CTX_METADATA_RETURNDATA_SIZE) LT %jumpi(fault_exception) // Data len check
 
@@ -199,6 +195,13 @@ global sys_returndatacopy:
     // stack:  size, kexit_info, dest_offset, offset, size
     ISZERO %jumpi(returndatacopy_empty)
 
+    // Memory is only expanded for a nonempty copy, whatever dest_offset is.
+    %stack (kexit_info, dest_offset, offset, size) -> (dest_offset, size, kexit_info, dest_offset, offset, size)
+    %add_or_fault
+    // stack: expanded_num_bytes, kexit_info, dest_offset, offset, size
+    DUP1 %ensure_reasonable_offset
+    %update_mem_bytes
+
     %mload_context_metadata(@CTX_METADATA_RETURNDATA_SIZE)
     // stack: total_size, kexit_info, dest_offset, offset, size
     DUP4
@@ -213,3 +216,42 @@ global sys_returndatacopy:
 returndatacopy_empty:
     %stack (kexit_info, dest_offset, offset, size) -> (kexit_info)
     EXIT_KERNEL
+
+// Copies memory to memory, with the semantics of memmove when the two ranges overlap.
+global sys_mcopy:
+    // stack: kexit_info, dest_offset, offset, size
+    PUSH @GAS_VERYLOW
+    // stack: Gverylow, kexit_info, dest_offset, offset, size
+    DUP5 %num_bytes_to_num_words %mul_const(@GAS_COPY) ADD %charge_gas
+
+    // stack: kexit_info, dest_offset, offset, size
+    DUP4
+    // stack: size, kexit_info, dest_offset, offset, size
+    ISZERO %jumpi(mcopy_empty)
+
+    // Expand memory to cover both the source and the destination.
+    %stack (kexit_info, dest_offset, offset, size) -> (dest_offset, size, kexit_info, dest_offset, offset, size)
+    %add_or_fault
+    // stack: expanded_num_bytes, kexit_info, dest_offset, offset, size
+    DUP1 %ensure_reasonable_offset
+    %update_mem_bytes
+    %stack (kexit_info, dest_offset, offset, size) -> (offset, size, kexit_info, dest_offset, offset, size)
+    %add_or_fault
+    // stack: expanded_num_bytes, kexit_info, dest_offset, offset, size
+    DUP1 %ensure_reasonable_offset
+    %update_mem_bytes
+
+    // If the destination is after the source, copy backwards so that overlapping bytes of the
+    // source are read before they are overwritten.
+    // stack: kexit_info, dest_offset, offset, size
+    DUP3 DUP3 GT
+    // stack: dest_offset > offset, kexit_info, dest_offset, offset, size
+    GET_CONTEXT
+    %stack (context, backwards, kexit_info, dest_offset, offset, size) ->
+        (backwards, context, @SEGMENT_MAIN_MEMORY, dest_offset, context, @SEGMENT_MAIN_MEMORY, offset, size, wcopy_after, kexit_info)
+    %jumpi(memcpy_backwards)
+    %jump(memcpy)
+
+mcopy_empty:
+    %stack (kexit_info, dest_offset, offset, size) -> (kexit_info)
+    EXIT_KERNEL
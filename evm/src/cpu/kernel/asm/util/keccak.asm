@@ -30,6 +30,28 @@ sys_keccak256_empty:
     %stack (kexit_info, offset, len) -> (kexit_info, @EMPTY_STRING_HASH)
     EXIT_KERNEL
 
+// Computes the Keccak256 hash of `len` bytes of the current context's main memory, like the KECCAK256
+// opcode does, but without charging gas or expanding memory. This is meant for kernel code, such as
+// precompiles or storage key derivation, which has already validated the region it hashes.
+//
+// Pre stack: offset, len, retdest
+// Post stack: hash
+global keccak256_main_memory:
+    // stack: offset, len, retdest
+    PUSH @SEGMENT_MAIN_MEMORY
+    GET_CONTEXT
+    // stack: ADDR: 3, len, retdest
+    KECCAK_GENERAL
+    // stack: hash, retdest
+    SWAP1
+    JUMP
+
+%macro keccak256_main_memory
+    %stack (offset, len) -> (offset, len, %%after)
+    %jump(keccak256_main_memory)
+%%after:
+%endmacro
+
 // Computes Keccak256(input_word). Clobbers @SEGMENT_KERNEL_GENERAL.
 //
 // Pre stack: input_word
@@ -43,10 +43,7 @@ impl Kernel {
         global_labels: HashMap<String, usize>,
         prover_inputs: HashMap<usize, ProverInputFn>,
     ) -> Self {
-        let code_hash_bytes = keccak(&code).0;
-        let code_hash = core::array::from_fn(|i| {
-            u32::from_le_bytes(core::array::from_fn(|j| code_hash_bytes[i * 4 + j]))
-        });
+        let code_hash = hash_kernel(&code);
         let ordered_labels = global_labels
             .keys()
             .cloned()
@@ -72,6 +69,18 @@ impl Kernel {
         serde_json::from_slice(&bytes).unwrap()
     }
 
+    /// Returns the offset of the global label `name` within the kernel code, if it exists.
+    pub fn global_label(&self, name: &str) -> Option<usize> {
+        self.global_labels.get(name).copied()
+    }
+
+    /// Iterates over all global labels and their offsets, by increasing offset.
+    pub fn labels(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.ordered_labels
+            .iter()
+            .map(|label| (label.as_str(), self.global_labels[label]))
+    }
+
     /// Get a string representation of the current offset for debugging purposes.
     pub(crate) fn offset_name(&self, offset: usize) -> String {
         match self
@@ -91,6 +100,15 @@ impl Kernel {
     }
 }
 
+/// Hashes the kernel code with Keccak, as the bootstrapping rows do through the Keccak sponge
+/// table, and returns the digest as little-endian `u32` limbs.
+pub(crate) fn hash_kernel(code: &[u8]) -> [u32; 8] {
+    let code_hash_bytes = keccak(code).0;
+    core::array::from_fn(|i| {
+        u32::from_le_bytes(core::array::from_fn(|j| code_hash_bytes[i * 4 + j]))
+    })
+}
+
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
 struct MacroSignature {
     name: String,
@@ -414,6 +432,7 @@ mod tests {
 
     use itertools::Itertools;
 
+    use crate::cpu::kernel::aggregator::KERNEL;
     use crate::cpu::kernel::assembler::*;
     use crate::cpu::kernel::ast::*;
     use crate::cpu::kernel::parser::parse;
@@ -698,6 +717,29 @@ mod tests {
         assert_eq!(kernel.code, vec![pop, push_label, 0, 0, 0]);
     }
 
+    #[test]
+    fn global_label_lookup() {
+        let kernel = parse_and_assemble(&["global foo: ADD", "global bar: MUL global baz: SUB"]);
+        assert_eq!(kernel.global_label("foo"), Some(0));
+        assert_eq!(kernel.global_label("bar"), Some(1));
+        assert_eq!(kernel.global_label("baz"), Some(2));
+        assert_eq!(kernel.global_label("qux"), None);
+        assert_eq!(
+            kernel.labels().collect_vec(),
+            vec![("foo", 0), ("bar", 1), ("baz", 2)]
+        );
+
+        // The real kernel's entry point resolves to the same offset as the one used internally.
+        let main = KERNEL.global_label("main").unwrap();
+        assert_eq!(main, KERNEL.global_labels["main"]);
+        assert!(KERNEL.labels().any(|label| label == ("main", main)));
+        assert!(KERNEL
+            .labels()
+            .tuple_windows()
+            .all(|((_, a), (_, b))| a <= b));
+        assert_eq!(KERNEL.global_label("no_such_label"), None);
+    }
+
     fn parse_and_assemble(files: &[&str]) -> Kernel {
         parse_and_assemble_ext(files, HashMap::new(), true)
     }
@@ -45,7 +45,6 @@ pub const INVALID_OPCODES_USER: U256 = u256_from_set_index_ranges([
     0x1e..=0x1f,
     0x21..=0x2f,
     0x49..=0x4f,
-    0x5c..=0x5e,
     0xa5..=0xef,
     0xf6..=0xf9,
     0xfb..=0xfc,
@@ -69,10 +69,26 @@ pub(crate) enum GlobalMetadata {
     ContractCreation = 33,
     IsPrecompileFromEoa = 34,
     CallStackDepth = 35,
+    /// Number of logs emitted so far, i.e. the length of the `Logs` segment.
+    LogsLen = 36,
+    /// Length of the `LogsData` segment.
+    LogsDataLen = 37,
+    /// Length of the `TransientStorage` segment.
+    TransientStorageLen = 38,
+    /// The configured fork, as one of the `FORK_*` constants. User code executing an opcode
+    /// introduced by a later fork faults, like an invalid opcode. The kernel also uses it to tell
+    /// forks apart where their semantics differ.
+    Fork = 39,
+    /// Length of the `CreatedContracts` segment.
+    CreatedContractsLen = 40,
+    /// The gas used by the current transaction, net of refunds. Set once it has been processed.
+    TxnGasUsed = 41,
+    /// Whether the current transaction succeeded. Set once it has been processed.
+    TxnStatus = 42,
 }
 
 impl GlobalMetadata {
-    pub(crate) const COUNT: usize = 35;
+    pub(crate) const COUNT: usize = 42;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -111,6 +127,13 @@ impl GlobalMetadata {
             Self::ContractCreation,
             Self::IsPrecompileFromEoa,
             Self::CallStackDepth,
+            Self::LogsLen,
+            Self::LogsDataLen,
+            Self::TransientStorageLen,
+            Self::Fork,
+            Self::CreatedContractsLen,
+            Self::TxnGasUsed,
+            Self::TxnStatus,
         ]
     }
 
@@ -152,6 +175,13 @@ impl GlobalMetadata {
             Self::ContractCreation => "GLOBAL_METADATA_CONTRACT_CREATION",
             Self::IsPrecompileFromEoa => "GLOBAL_METADATA_IS_PRECOMPILE_FROM_EOA",
             Self::CallStackDepth => "GLOBAL_METADATA_CALL_STACK_DEPTH",
+            Self::LogsLen => "GLOBAL_METADATA_LOGS_LEN",
+            Self::LogsDataLen => "GLOBAL_METADATA_LOGS_DATA_LEN",
+            Self::TransientStorageLen => "GLOBAL_METADATA_TRANSIENT_STORAGE_LEN",
+            Self::Fork => "GLOBAL_METADATA_FORK",
+            Self::CreatedContractsLen => "GLOBAL_METADATA_CREATED_CONTRACTS_LEN",
+            Self::TxnGasUsed => "GLOBAL_METADATA_TXN_GAS_USED",
+            Self::TxnStatus => "GLOBAL_METADATA_TXN_STATUS",
         }
     }
 }
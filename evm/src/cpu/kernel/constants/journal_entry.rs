@@ -11,10 +11,13 @@ pub(crate) enum JournalEntry {
     CodeChange = 7,
     Refund = 8,
     AccountCreated = 9,
+    Log = 10,
+    TransientStorageChange = 11,
+    ContractCreated = 12,
 }
 
 impl JournalEntry {
-    pub(crate) const COUNT: usize = 10;
+    pub(crate) const COUNT: usize = 13;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -28,6 +31,9 @@ impl JournalEntry {
             Self::CodeChange,
             Self::Refund,
             Self::AccountCreated,
+            Self::Log,
+            Self::TransientStorageChange,
+            Self::ContractCreated,
         ]
     }
 
@@ -44,6 +50,9 @@ impl JournalEntry {
             Self::CodeChange => "JOURNAL_ENTRY_CODE_CHANGE",
             Self::Refund => "JOURNAL_ENTRY_REFUND",
             Self::AccountCreated => "JOURNAL_ENTRY_ACCOUNT_CREATED",
+            Self::Log => "JOURNAL_ENTRY_LOG",
+            Self::TransientStorageChange => "JOURNAL_ENTRY_TRANSIENT_STORAGE_CHANGE",
+            Self::ContractCreated => "JOURNAL_ENTRY_CONTRACT_CREATED",
         }
     }
 }
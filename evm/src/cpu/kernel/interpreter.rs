@@ -13,6 +13,8 @@ use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::cpu::kernel::opcodes::get_mnemonic;
+use crate::cpu::signed::{byte_decompose, sign_bit, twos_complement_neg, INT_MIN, MINUS_ONE};
 use crate::extension_tower::BN_BASE;
 use crate::generation::prover_input::ProverInputFn;
 use crate::generation::state::GenerationState;
@@ -38,7 +40,6 @@ impl MemoryState {
 
 pub struct Interpreter<'a> {
     kernel_mode: bool,
-    jumpdests: Vec<usize>,
     pub(crate) context: usize,
     pub(crate) generation_state: GenerationState<F>,
     prover_inputs_map: &'a HashMap<usize, ProverInputFn>,
@@ -116,7 +117,6 @@ impl<'a> Interpreter<'a> {
     ) -> Self {
         let mut result = Self {
             kernel_mode: true,
-            jumpdests: find_jumpdests(code),
             generation_state: GenerationState::new(GenerationInputs::default(), code),
             prover_inputs_map: prover_inputs,
             context: 0,
@@ -128,6 +128,9 @@ impl<'a> Interpreter<'a> {
         result.generation_state.registers.program_counter = initial_offset;
         result.generation_state.registers.stack_len = initial_stack.len();
         *result.stack_mut() = initial_stack;
+        // As in `apply_metadata_memops`, so that the kernel runs with the default fork.
+        let fork = result.generation_state.inputs.fork;
+        result.set_global_metadata_field(GlobalMetadata::Fork, (fork as u32).into());
         result
     }
 
@@ -147,7 +150,9 @@ impl<'a> Interpreter<'a> {
     }
 
     fn code(&self) -> &MemorySegmentState {
-        &self.generation_state.memory.contexts[self.context].segments[Segment::Code as usize]
+        // The kernel code lives in context 0, even when running in another context.
+        &self.generation_state.memory.contexts[self.generation_state.registers.code_context()]
+            .segments[Segment::Code as usize]
     }
 
     fn code_slice(&self, n: usize) -> Vec<u8> {
@@ -182,6 +187,21 @@ impl<'a> Interpreter<'a> {
             .set(field as usize, value)
     }
 
+    pub(crate) fn get_context_metadata_field(&self, ctx: usize, field: ContextMetadata) -> U256 {
+        self.generation_state.memory.contexts[ctx].segments[Segment::ContextMetadata as usize]
+            .get(field as usize)
+    }
+
+    pub(crate) fn set_context_metadata_field(
+        &mut self,
+        ctx: usize,
+        field: ContextMetadata,
+        value: U256,
+    ) {
+        self.generation_state.memory.contexts[ctx].segments[Segment::ContextMetadata as usize]
+            .set(field as usize, value)
+    }
+
     pub(crate) fn get_trie_data(&self) -> &[U256] {
         &self.generation_state.memory.contexts[0].segments[Segment::TrieData as usize].content
     }
@@ -225,6 +245,10 @@ impl<'a> Interpreter<'a> {
             .content = memory;
     }
 
+    pub(crate) fn set_memory_segment(&mut self, segment: Segment, memory: Vec<U256>) {
+        self.generation_state.memory.contexts[0].segments[segment as usize].content = memory;
+    }
+
     pub(crate) fn set_memory_segment_bytes(&mut self, segment: Segment, memory: Vec<u8>) {
         self.generation_state.memory.contexts[0].segments[segment as usize].content =
             memory.into_iter().map(U256::from).collect();
@@ -254,6 +278,13 @@ impl<'a> Interpreter<'a> {
             .collect()
     }
 
+    /// Switch between kernel and user mode, e.g. to run a syscall handler after the interpreter
+    /// has exited the kernel.
+    pub(crate) fn set_is_kernel(&mut self, is_kernel: bool) {
+        self.kernel_mode = is_kernel;
+        self.generation_state.registers.is_kernel = is_kernel;
+    }
+
     fn incr(&mut self, n: usize) {
         self.generation_state.registers.program_counter += n;
     }
@@ -368,8 +399,8 @@ impl<'a> Interpreter<'a> {
             0x53 => self.run_mstore8(),                                 // "MSTORE8",
             0x54 => todo!(),                                            // "SLOAD",
             0x55 => todo!(),                                            // "SSTORE",
-            0x56 => self.run_jump(),                                    // "JUMP",
-            0x57 => self.run_jumpi(),                                   // "JUMPI",
+            0x56 => self.run_jump()?,                                   // "JUMP",
+            0x57 => self.run_jumpi()?,                                  // "JUMPI",
             0x58 => self.run_pc(),                                      // "PC",
             0x59 => self.run_msize(),                                   // "MSIZE",
             0x5a => todo!(),                                            // "GAS",
@@ -395,7 +426,7 @@ impl<'a> Interpreter<'a> {
             0xf5 => todo!(),                                            // "CREATE2",
             0xf6 => self.run_get_context(),                             // "GET_CONTEXT",
             0xf7 => self.run_set_context(),                             // "SET_CONTEXT",
-            0xf9 => todo!(),                                            // "EXIT_KERNEL",
+            0xf9 => self.run_exit_kernel(),                             // "EXIT_KERNEL",
             0xfa => todo!(),                                            // "STATICCALL",
             0xfb => self.run_mload_general(),                           // "MLOAD_GENERAL",
             0xfc => self.run_mstore_general(),                          // "MSTORE_GENERAL",
@@ -481,29 +512,25 @@ impl<'a> Interpreter<'a> {
 
         if y_is_zero {
             self.push(U256::zero());
-        } else if y.eq(&MINUS_ONE) && x.eq(&MIN_VALUE) {
-            self.push(MIN_VALUE);
+        } else if y.eq(&MINUS_ONE) && x.eq(&INT_MIN) {
+            self.push(INT_MIN);
         } else {
-            let x_is_pos = x.eq(&(x & SIGN_MASK));
-            let y_is_pos = y.eq(&(y & SIGN_MASK));
+            let x_is_pos = !sign_bit(x);
+            let y_is_pos = !sign_bit(y);
 
             // We compute the absolute quotient first,
             // then adapt its sign based on the operands.
             if !x_is_pos {
-                x = two_complement(x);
+                x = twos_complement_neg(x);
             }
             if !y_is_pos {
-                y = two_complement(y);
+                y = twos_complement_neg(y);
             }
             let div = x / y;
-            if div.eq(&U256::zero()) {
-                self.push(U256::zero());
-            }
-
             self.push(if x_is_pos == y_is_pos {
                 div
             } else {
-                two_complement(div)
+                twos_complement_neg(div)
             });
         }
     }
@@ -521,24 +548,24 @@ impl<'a> Interpreter<'a> {
         if y.is_zero() {
             self.push(U256::zero());
         } else {
-            let x_is_pos = x.eq(&(x & SIGN_MASK));
-            let y_is_pos = y.eq(&(y & SIGN_MASK));
+            let x_is_pos = !sign_bit(x);
+            let y_is_pos = !sign_bit(y);
 
             // We compute the absolute remainder first,
             // then adapt its sign based on the operands.
             if !x_is_pos {
-                x = two_complement(x);
+                x = twos_complement_neg(x);
             }
             if !y_is_pos {
-                y = two_complement(y);
+                y = twos_complement_neg(y);
             }
             let rem = x % y;
-            if rem.eq(&U256::zero()) {
-                self.push(U256::zero());
-            }
-
             // Remainder always has the same sign as the dividend.
-            self.push(if x_is_pos { rem } else { two_complement(rem) });
+            self.push(if x_is_pos {
+                rem
+            } else {
+                twos_complement_neg(rem)
+            });
         }
     }
 
@@ -603,12 +630,10 @@ impl<'a> Interpreter<'a> {
             let n = n.low_u64() as usize;
             let num_bytes_prepend = 31 - n;
 
-            let mut x_bytes = [0u8; 32];
-            x.to_big_endian(&mut x_bytes);
-            let x_bytes = x_bytes[num_bytes_prepend..].to_vec();
-            let sign_bit = x_bytes[0] >> 7;
+            let x_bytes = byte_decompose(x)[num_bytes_prepend..].to_vec();
+            let sign = x_bytes[0] >> 7;
 
-            let mut bytes = if sign_bit == 0 {
+            let mut bytes = if sign == 0 {
                 vec![0; num_bytes_prepend]
             } else {
                 vec![0xff; num_bytes_prepend]
@@ -683,7 +708,7 @@ impl<'a> Interpreter<'a> {
     fn run_sar(&mut self) {
         let shift = self.pop();
         let value = self.pop();
-        let value_is_neg = !value.eq(&(value & SIGN_MASK));
+        let value_is_neg = sign_bit(value);
 
         if shift < U256::from(256usize) {
             let shift = shift.low_u64() as usize;
@@ -926,8 +951,7 @@ impl<'a> Interpreter<'a> {
     fn run_mstore(&mut self) {
         let offset = self.pop().as_usize();
         let value = self.pop();
-        let mut bytes = [0; 32];
-        value.to_big_endian(&mut bytes);
+        let bytes = byte_decompose(value);
         for (i, byte) in (0..32).zip(bytes) {
             self.generation_state.memory.mstore_general(
                 self.context,
@@ -949,17 +973,18 @@ impl<'a> Interpreter<'a> {
         );
     }
 
-    fn run_jump(&mut self) {
-        let x = self.pop().as_usize();
-        self.jump_to(x);
+    fn run_jump(&mut self) -> anyhow::Result<()> {
+        let x = self.pop();
+        self.jump_to(x)
     }
 
-    fn run_jumpi(&mut self) {
-        let x = self.pop().as_usize();
+    fn run_jumpi(&mut self) -> anyhow::Result<()> {
+        let x = self.pop();
         let b = self.pop();
         if !b.is_zero() {
-            self.jump_to(x);
+            self.jump_to(x)?;
         }
+        Ok(())
     }
 
     fn run_pc(&mut self) {
@@ -978,10 +1003,24 @@ impl<'a> Interpreter<'a> {
         assert!(!self.kernel_mode, "JUMPDEST is not needed in kernel code");
     }
 
-    fn jump_to(&mut self, offset: usize) {
-        // The JUMPDEST rule is not enforced in kernel mode.
-        if !self.kernel_mode && self.jumpdests.binary_search(&offset).is_err() {
-            panic!("Destination is not a JUMPDEST.");
+    fn jump_to(&mut self, offset: U256) -> anyhow::Result<()> {
+        let offset: u32 = offset
+            .try_into()
+            .map_err(|_| anyhow!("Invalid jump destination {offset}"))?;
+        let offset = offset as usize;
+
+        // The JUMPDEST rule is not enforced in kernel mode. In user mode, like the CPU, we consult
+        // the bits written by the kernel's JUMPDEST analysis of the current context's code.
+        if !self.kernel_mode {
+            let jumpdest_bit = self.generation_state.memory.mload_general(
+                self.context,
+                Segment::JumpdestBits,
+                offset,
+            );
+            ensure!(
+                jumpdest_bit == U256::one(),
+                "Destination {offset} is not a JUMPDEST."
+            );
         }
 
         self.generation_state.registers.program_counter = offset;
@@ -989,6 +1028,7 @@ impl<'a> Interpreter<'a> {
         if self.halt_offsets.contains(&offset) {
             self.running = false;
         }
+        Ok(())
     }
 
     fn run_push(&mut self, num_bytes: u8) {
@@ -1008,13 +1048,52 @@ impl<'a> Interpreter<'a> {
         Ok(())
     }
 
+    fn run_exit_kernel(&mut self) {
+        let kexit_info = self.pop();
+        let kexit_info_u64 = kexit_info.0[0];
+        let program_counter = kexit_info_u64 as u32 as usize;
+        let is_kernel_mode_val = (kexit_info_u64 >> 32) as u32;
+        assert!(is_kernel_mode_val == 0 || is_kernel_mode_val == 1);
+        let is_kernel_mode = is_kernel_mode_val != 0;
+        let gas_used_val = kexit_info.0[3];
+
+        self.kernel_mode = is_kernel_mode;
+        self.generation_state.registers.is_kernel = is_kernel_mode;
+        self.generation_state.registers.gas_used = gas_used_val;
+        // Syscalls return to the instruction after the one that trapped,
+        // which need not be a JUMPDEST, so don't go through `jump_to`.
+        self.generation_state.registers.program_counter = program_counter;
+        if self.halt_offsets.contains(&program_counter) {
+            self.running = false;
+        }
+    }
+
     fn run_get_context(&mut self) {
         self.push(self.context.into());
     }
 
     fn run_set_context(&mut self) {
         let x = self.pop();
-        self.context = x.as_usize();
+        self.set_context(x.as_usize());
+    }
+
+    /// Switch to `new_ctx`, as SET_CONTEXT does.
+    pub(crate) fn set_context(&mut self, new_ctx: usize) {
+        // Like the CPU, save the stack length of the context we are leaving.
+        let stack_len = self.stack_len().into();
+        self.set_context_metadata_field(self.context, ContextMetadata::StackSize, stack_len);
+        let new_stack_len = self
+            .generation_state
+            .memory
+            .mload_general(
+                new_ctx,
+                Segment::ContextMetadata,
+                ContextMetadata::StackSize as usize,
+            )
+            .as_usize();
+        self.context = new_ctx;
+        self.generation_state.registers.context = new_ctx;
+        self.generation_state.registers.stack_len = new_stack_len;
     }
 
     fn run_mload_general(&mut self) {
@@ -1044,12 +1123,6 @@ impl<'a> Interpreter<'a> {
     }
 }
 
-// Computes the two's complement of the given integer.
-fn two_complement(x: U256) -> U256 {
-    let flipped_bits = x ^ MINUS_ONE;
-    flipped_bits.overflowing_add(U256::one()).0
-}
-
 fn signed_cmp(x: U256, y: U256) -> Ordering {
     let x_is_zero = x.is_zero();
     let y_is_zero = y.is_zero();
@@ -1058,8 +1131,8 @@ fn signed_cmp(x: U256, y: U256) -> Ordering {
         return Ordering::Equal;
     }
 
-    let x_is_pos = x.eq(&(x & SIGN_MASK));
-    let y_is_pos = y.eq(&(y & SIGN_MASK));
+    let x_is_pos = !sign_bit(x);
+    let y_is_pos = !sign_bit(y);
 
     if x_is_zero {
         if y_is_pos {
@@ -1085,205 +1158,6 @@ fn signed_cmp(x: U256, y: U256) -> Ordering {
     }
 }
 
-/// -1 in two's complement representation consists in all bits set to 1.
-const MINUS_ONE: U256 = U256([
-    0xffffffffffffffff,
-    0xffffffffffffffff,
-    0xffffffffffffffff,
-    0xffffffffffffffff,
-]);
-
-/// -2^255 in two's complement representation consists in the MSB set to 1.
-const MIN_VALUE: U256 = U256([
-    0x0000000000000000,
-    0x0000000000000000,
-    0x0000000000000000,
-    0x8000000000000000,
-]);
-
-const SIGN_MASK: U256 = U256([
-    0xffffffffffffffff,
-    0xffffffffffffffff,
-    0xffffffffffffffff,
-    0x7fffffffffffffff,
-]);
-
-/// Return the (ordered) JUMPDEST offsets in the code.
-fn find_jumpdests(code: &[u8]) -> Vec<usize> {
-    let mut offset = 0;
-    let mut res = Vec::new();
-    while offset < code.len() {
-        let opcode = code[offset];
-        match opcode {
-            0x5b => res.push(offset),
-            x if (0x60..0x80).contains(&x) => offset += x as usize - 0x5f, // PUSH instruction, disregard data.
-            _ => (),
-        }
-        offset += 1;
-    }
-    res
-}
-
-fn get_mnemonic(opcode: u8) -> &'static str {
-    match opcode {
-        0x00 => "STOP",
-        0x01 => "ADD",
-        0x02 => "MUL",
-        0x03 => "SUB",
-        0x04 => "DIV",
-        0x05 => "SDIV",
-        0x06 => "MOD",
-        0x07 => "SMOD",
-        0x08 => "ADDMOD",
-        0x09 => "MULMOD",
-        0x0a => "EXP",
-        0x0b => "SIGNEXTEND",
-        0x0c => "ADDFP254",
-        0x0d => "MULFP254",
-        0x0e => "SUBFP254",
-        0x10 => "LT",
-        0x11 => "GT",
-        0x12 => "SLT",
-        0x13 => "SGT",
-        0x14 => "EQ",
-        0x15 => "ISZERO",
-        0x16 => "AND",
-        0x17 => "OR",
-        0x18 => "XOR",
-        0x19 => "NOT",
-        0x1a => "BYTE",
-        0x1b => "SHL",
-        0x1c => "SHR",
-        0x1d => "SAR",
-        0x20 => "KECCAK256",
-        0x21 => "KECCAK_GENERAL",
-        0x30 => "ADDRESS",
-        0x31 => "BALANCE",
-        0x32 => "ORIGIN",
-        0x33 => "CALLER",
-        0x34 => "CALLVALUE",
-        0x35 => "CALLDATALOAD",
-        0x36 => "CALLDATASIZE",
-        0x37 => "CALLDATACOPY",
-        0x38 => "CODESIZE",
-        0x39 => "CODECOPY",
-        0x3a => "GASPRICE",
-        0x3b => "EXTCODESIZE",
-        0x3c => "EXTCODECOPY",
-        0x3d => "RETURNDATASIZE",
-        0x3e => "RETURNDATACOPY",
-        0x3f => "EXTCODEHASH",
-        0x40 => "BLOCKHASH",
-        0x41 => "COINBASE",
-        0x42 => "TIMESTAMP",
-        0x43 => "NUMBER",
-        0x44 => "DIFFICULTY",
-        0x45 => "GASLIMIT",
-        0x46 => "CHAINID",
-        0x48 => "BASEFEE",
-        0x49 => "PROVER_INPUT",
-        0x50 => "POP",
-        0x51 => "MLOAD",
-        0x52 => "MSTORE",
-        0x53 => "MSTORE8",
-        0x54 => "SLOAD",
-        0x55 => "SSTORE",
-        0x56 => "JUMP",
-        0x57 => "JUMPI",
-        0x58 => "GETPC",
-        0x59 => "MSIZE",
-        0x5a => "GAS",
-        0x5b => "JUMPDEST",
-        0x5f => "PUSH0",
-        0x60 => "PUSH1",
-        0x61 => "PUSH2",
-        0x62 => "PUSH3",
-        0x63 => "PUSH4",
-        0x64 => "PUSH5",
-        0x65 => "PUSH6",
-        0x66 => "PUSH7",
-        0x67 => "PUSH8",
-        0x68 => "PUSH9",
-        0x69 => "PUSH10",
-        0x6a => "PUSH11",
-        0x6b => "PUSH12",
-        0x6c => "PUSH13",
-        0x6d => "PUSH14",
-        0x6e => "PUSH15",
-        0x6f => "PUSH16",
-        0x70 => "PUSH17",
-        0x71 => "PUSH18",
-        0x72 => "PUSH19",
-        0x73 => "PUSH20",
-        0x74 => "PUSH21",
-        0x75 => "PUSH22",
-        0x76 => "PUSH23",
-        0x77 => "PUSH24",
-        0x78 => "PUSH25",
-        0x79 => "PUSH26",
-        0x7a => "PUSH27",
-        0x7b => "PUSH28",
-        0x7c => "PUSH29",
-        0x7d => "PUSH30",
-        0x7e => "PUSH31",
-        0x7f => "PUSH32",
-        0x80 => "DUP1",
-        0x81 => "DUP2",
-        0x82 => "DUP3",
-        0x83 => "DUP4",
-        0x84 => "DUP5",
-        0x85 => "DUP6",
-        0x86 => "DUP7",
-        0x87 => "DUP8",
-        0x88 => "DUP9",
-        0x89 => "DUP10",
-        0x8a => "DUP11",
-        0x8b => "DUP12",
-        0x8c => "DUP13",
-        0x8d => "DUP14",
-        0x8e => "DUP15",
-        0x8f => "DUP16",
-        0x90 => "SWAP1",
-        0x91 => "SWAP2",
-        0x92 => "SWAP3",
-        0x93 => "SWAP4",
-        0x94 => "SWAP5",
-        0x95 => "SWAP6",
-        0x96 => "SWAP7",
-        0x97 => "SWAP8",
-        0x98 => "SWAP9",
-        0x99 => "SWAP10",
-        0x9a => "SWAP11",
-        0x9b => "SWAP12",
-        0x9c => "SWAP13",
-        0x9d => "SWAP14",
-        0x9e => "SWAP15",
-        0x9f => "SWAP16",
-        0xa0 => "LOG0",
-        0xa1 => "LOG1",
-        0xa2 => "LOG2",
-        0xa3 => "LOG3",
-        0xa4 => "LOG4",
-        0xa5 => "PANIC",
-        0xf0 => "CREATE",
-        0xf1 => "CALL",
-        0xf2 => "CALLCODE",
-        0xf3 => "RETURN",
-        0xf4 => "DELEGATECALL",
-        0xf5 => "CREATE2",
-        0xf6 => "GET_CONTEXT",
-        0xf7 => "SET_CONTEXT",
-        0xf9 => "EXIT_KERNEL",
-        0xfa => "STATICCALL",
-        0xfb => "MLOAD_GENERAL",
-        0xfc => "MSTORE_GENERAL",
-        0xfd => "REVERT",
-        0xfe => "INVALID",
-        0xff => "SELFDESTRUCT",
-        _ => panic!("Unrecognized opcode {opcode}"),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
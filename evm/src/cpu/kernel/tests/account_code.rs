@@ -7,8 +7,10 @@ use keccak_hash::keccak;
 use rand::{thread_rng, Rng};
 
 use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::kernel_kexit_info;
 use crate::cpu::kernel::tests::mpt::nibbles_64;
 use crate::generation::mpt::{all_mpt_prover_inputs_reversed, AccountRlp};
 use crate::memory::segments::Segment;
@@ -185,3 +187,60 @@ fn test_extcodecopy() -> Result<()> {
 
     Ok(())
 }
+
+/// Run `sys_extcodehash` on `address`, returning the hash and the total gas used so far.
+fn sys_extcodehash(interpreter: &mut Interpreter, address: Address) -> Result<(H256, u64)> {
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["sys_extcodehash"];
+    interpreter.push(U256::from_big_endian(address.as_bytes()));
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    let hash = H256::from_uint(&interpreter.pop());
+    assert!(interpreter.stack().is_empty());
+    Ok((hash, interpreter.generation_state.registers.gas_used))
+}
+
+#[test]
+fn test_extcodehash() -> Result<()> {
+    let mut rng = thread_rng();
+
+    for code in [random_code(), vec![]] {
+        let account = test_account(&code);
+        let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+        let address: Address = rng.gen();
+        prepare_interpreter(&mut interpreter, address, &account)?;
+        interpreter.pop();
+        interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+
+        // An existing account with empty code hashes to `keccak("")`, not to 0.
+        assert_eq!(
+            sys_extcodehash(&mut interpreter, address)?,
+            (keccak(&code), 2600)
+        );
+        // The address is now warm.
+        assert_eq!(
+            sys_extcodehash(&mut interpreter, address)?,
+            (keccak(&code), 2600 + 100)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_extcodehash_nonexistent_account() -> Result<()> {
+    let mut rng = thread_rng();
+    let account = test_account(&random_code());
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_interpreter(&mut interpreter, rng.gen(), &account)?;
+    interpreter.pop();
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+
+    let other_address: Address = rng.gen();
+    assert_eq!(
+        sys_extcodehash(&mut interpreter, other_address)?,
+        (H256::zero(), 2600)
+    );
+
+    Ok(())
+}
@@ -5,14 +5,16 @@ use keccak_hash::keccak;
 use rand::{thread_rng, Rng};
 
 use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::kernel_kexit_info;
 use crate::cpu::kernel::tests::mpt::nibbles_64;
 use crate::generation::mpt::{all_mpt_prover_inputs_reversed, AccountRlp};
 use crate::Node;
 
 // Test account with a given code hash.
-fn test_account(balance: U256) -> AccountRlp {
+pub(crate) fn test_account(balance: U256) -> AccountRlp {
     AccountRlp {
         nonce: U256::from(1111),
         balance,
@@ -23,7 +25,7 @@ fn test_account(balance: U256) -> AccountRlp {
 
 // Stolen from `tests/mpt/insert.rs`
 // Prepare the interpreter by inserting the account in the state trie.
-fn prepare_interpreter(
+pub(crate) fn prepare_interpreter(
     interpreter: &mut Interpreter,
     address: Address,
     account: &AccountRlp,
@@ -117,3 +119,69 @@ fn test_balance() -> Result<()> {
 
     Ok(())
 }
+
+/// Run the syscall handler `label` on `args` (top of the stack first), returning its single
+/// output and the gas it charged.
+fn run_balance_syscall(
+    interpreter: &mut Interpreter,
+    label: &str,
+    args: &[U256],
+) -> Result<(U256, u64)> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels[label];
+    for &arg in args.iter().rev() {
+        interpreter.push(arg);
+    }
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert_eq!(interpreter.stack().len(), 1);
+    Ok((
+        interpreter.pop(),
+        interpreter.generation_state.registers.gas_used,
+    ))
+}
+
+#[test]
+fn test_selfbalance() -> Result<()> {
+    let mut rng = thread_rng();
+    let balance = U256(rng.gen());
+    let account = test_account(balance);
+
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address: Address = rng.gen();
+    prepare_interpreter(&mut interpreter, address, &account)?;
+    interpreter.pop();
+    let address = U256::from_big_endian(address.as_bytes());
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, address);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+
+    assert_eq!(
+        run_balance_syscall(&mut interpreter, "sys_selfbalance", &[])?,
+        (balance, 5)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_balance_nonexistent_account() -> Result<()> {
+    let mut rng = thread_rng();
+    let account = test_account(U256(rng.gen()));
+
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address: Address = rng.gen();
+    prepare_interpreter(&mut interpreter, address, &account)?;
+    interpreter.pop();
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+
+    // The first access to the address is cold, the second one is warm.
+    let other_address: Address = rng.gen();
+    let other_address = U256::from_big_endian(other_address.as_bytes());
+    for expected_gas in [2600, 100] {
+        assert_eq!(
+            run_balance_syscall(&mut interpreter, "sys_balance", &[other_address])?,
+            (U256::zero(), expected_gas)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+const CALLDATA_LEN: usize = 40;
+
+/// Run the syscall handler `label` on `args` (top of the stack first) with
+/// calldata `[1, 2, ..., CALLDATA_LEN]`.
+fn run_calldata_syscall(label: &str, args: &[U256]) -> Result<Interpreter<'static>> {
+    let mut interpreter = syscall_interpreter(label, args);
+    interpreter.set_memory_segment_bytes(Segment::Calldata, calldata());
+    interpreter.set_context_metadata_field(0, ContextMetadata::CalldataSize, CALLDATA_LEN.into());
+    interpreter.run()?;
+    Ok(interpreter)
+}
+
+fn calldata() -> Vec<u8> {
+    (1..=CALLDATA_LEN as u8).collect()
+}
+
+/// The 32 bytes of calldata starting at `offset`, zero-padded past the end.
+fn calldata_word(offset: usize) -> [u8; 32] {
+    let calldata = calldata();
+    std::array::from_fn(|i| calldata.get(offset + i).copied().unwrap_or(0))
+}
+
+#[test]
+fn test_calldataload() -> Result<()> {
+    for offset in [0, 8, 20, 39, 40, 1000] {
+        let interpreter = run_calldata_syscall("sys_calldataload", &[offset.into()])?;
+        let expected = U256::from_big_endian(&calldata_word(offset));
+        assert_eq!(interpreter.stack(), &[expected], "offset {offset}");
+        assert_eq!(interpreter.generation_state.registers.gas_used, 3);
+    }
+
+    // An offset that doesn't fit in a usize still loads zero.
+    let interpreter = run_calldata_syscall("sys_calldataload", &[U256::MAX])?;
+    assert_eq!(interpreter.stack(), &[U256::zero()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_calldatasize() -> Result<()> {
+    let interpreter = run_calldata_syscall("sys_calldatasize", &[])?;
+    assert_eq!(interpreter.stack(), &[CALLDATA_LEN.into()]);
+    assert_eq!(interpreter.generation_state.registers.gas_used, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_calldatacopy_straddling_end() -> Result<()> {
+    // Copy 32 bytes starting 8 bytes before the end of calldata to memory offset 4.
+    let (dest_offset, offset, size) = (4, CALLDATA_LEN - 8, 32);
+    let interpreter = run_calldata_syscall(
+        "sys_calldatacopy",
+        &[dest_offset.into(), offset.into(), size.into()],
+    )?;
+    assert!(interpreter.stack().is_empty());
+
+    let memory = interpreter.get_memory_segment_bytes(Segment::MainMemory);
+    let mut expected = vec![0; dest_offset];
+    expected.extend_from_slice(&calldata_word(offset));
+    assert_eq!(memory, expected);
+
+    // 3 static + 3 per copied word + 6 to expand memory to 2 words.
+    assert_eq!(interpreter.generation_state.registers.gas_used, 3 + 3 + 6);
+
+    Ok(())
+}
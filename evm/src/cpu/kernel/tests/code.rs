@@ -0,0 +1,52 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+/// Run the syscall handler `label` on `args` (top of the stack first). The handler runs in
+/// context 0, whose code is the kernel itself, so that is the code `CODESIZE` and `CODECOPY` see.
+fn run_code_syscall(label: &str, args: &[U256]) -> Result<Interpreter<'static>> {
+    let mut interpreter = syscall_interpreter(label, args);
+    interpreter.set_context_metadata_field(0, ContextMetadata::CodeSize, KERNEL.code.len().into());
+    interpreter.run()?;
+    Ok(interpreter)
+}
+
+/// The `size` bytes of code starting at `offset`, zero-padded past the end.
+fn code_slice(offset: usize, size: usize) -> Vec<u8> {
+    (offset..offset + size)
+        .map(|i| KERNEL.code.get(i).copied().unwrap_or(0))
+        .collect()
+}
+
+#[test]
+fn test_codesize() -> Result<()> {
+    let interpreter = run_code_syscall("sys_codesize", &[])?;
+    assert_eq!(interpreter.stack(), &[KERNEL.code.len().into()]);
+    assert_eq!(interpreter.generation_state.registers.gas_used, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_codecopy() -> Result<()> {
+    let code_len = KERNEL.code.len();
+
+    // The first word of code, a word straddling the end of the code, and a word past the end.
+    for offset in [0, code_len - 8, code_len + 100] {
+        let interpreter = run_code_syscall("sys_codecopy", &[0.into(), offset.into(), 32.into()])?;
+        assert!(interpreter.stack().is_empty());
+
+        let memory = interpreter.get_memory_segment_bytes(Segment::MainMemory);
+        assert_eq!(memory, code_slice(offset, 32), "offset {offset}");
+
+        // 3 static + 3 per copied word + 3 to expand memory to 1 word.
+        assert_eq!(interpreter.generation_state.registers.gas_used, 3 + 3 + 3);
+    }
+
+    Ok(())
+}
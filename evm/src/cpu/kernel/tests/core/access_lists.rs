@@ -5,10 +5,13 @@ use ethereum_types::{Address, U256};
 use rand::{thread_rng, Rng};
 
 use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata::{
     AccessedAddressesLen, AccessedStorageKeysLen,
 };
 use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::balance::{prepare_interpreter, test_account};
+use crate::cpu::kernel::tests::kernel_kexit_info;
 use crate::memory::segments::Segment::{AccessedAddresses, AccessedStorageKeys, GlobalMetadata};
 use crate::witness::memory::MemoryAddress;
 
@@ -214,3 +217,35 @@ fn test_insert_accessed_storage_keys() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_sload_cold_then_warm() -> Result<()> {
+    let sys_sload = KERNEL.global_labels["sys_sload"];
+
+    let mut rng = thread_rng();
+    let address: Address = rng.gen();
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_interpreter(&mut interpreter, address, &test_account(U256::from(1000)))?;
+    // Discard the state trie hash left by `prepare_interpreter`.
+    interpreter.pop();
+    let address = U256::from_big_endian(address.as_bytes());
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, address);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+
+    let slot = U256::from(7);
+    // The first access to the slot is cold (2100 gas), the second one is warm (100 gas).
+    for expected_gas in [2100, 100] {
+        interpreter.generation_state.registers.program_counter = sys_sload;
+        interpreter.push(slot);
+        interpreter.push(kernel_kexit_info());
+        interpreter.run()?;
+        assert_eq!(interpreter.stack(), &[U256::zero()]);
+        assert_eq!(
+            interpreter.generation_state.registers.gas_used,
+            expected_gas
+        );
+        interpreter.pop();
+    }
+
+    Ok(())
+}
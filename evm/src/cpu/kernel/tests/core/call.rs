@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, U256};
+use keccak_hash::keccak;
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::balance::prepare_interpreter;
+use crate::cpu::kernel::tests::kernel_kexit_info;
+use crate::generation::mpt::AccountRlp;
+use crate::memory::segments::Segment;
+use crate::Node;
+
+const GAS_LIMIT: u64 = 100_000;
+
+/// Cost of a first access to an address.
+const GAS_COLDACCOUNTACCESS: u64 = 2_600;
+
+/// Prepare the interpreter to run in the context of a freshly inserted account holding `code`.
+/// The calls below all target this same account, so that the state trie contains the callee.
+fn prepare_context(interpreter: &mut Interpreter, code: &[u8]) -> Result<U256> {
+    let address: Address = thread_rng().gen();
+    let account = AccountRlp {
+        nonce: U256::from(1111),
+        balance: U256::from(2222),
+        storage_root: HashedPartialTrie::from(Node::Empty).hash(),
+        code_hash: keccak(code),
+    };
+    prepare_interpreter(interpreter, address, &account)?;
+    // Discard the state trie hash left by `prepare_interpreter`.
+    interpreter.pop();
+    assert!(interpreter.stack().is_empty());
+
+    interpreter.generation_state.inputs.contract_code =
+        HashMap::from([(keccak(code), code.to_vec())]);
+    let address = U256::from_big_endian(address.as_bytes());
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, address);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, GAS_LIMIT.into());
+    // Stop once the kernel hands control over to the callee's code.
+    interpreter.halt_offsets.push(0);
+    Ok(address)
+}
+
+/// Run the syscall handler `label` on `args` (top of the stack first).
+fn run_syscall(
+    interpreter: &mut Interpreter,
+    label: &str,
+    kexit_info: U256,
+    args: &[U256],
+) -> Result<()> {
+    interpreter.set_is_kernel(true);
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels[label];
+    for &arg in args.iter().rev() {
+        interpreter.push(arg);
+    }
+    interpreter.push(kexit_info);
+    interpreter.run()
+}
+
+/// Enter a sub-context through the syscall `label`, and return the new context.
+fn enter_sub_context(interpreter: &mut Interpreter, label: &str, args: &[U256]) -> Result<usize> {
+    run_syscall(interpreter, label, kernel_kexit_info(), args)?;
+    assert_eq!(interpreter.generation_state.registers.program_counter, 0);
+    assert!(!interpreter.generation_state.registers.is_kernel);
+    assert!(interpreter.stack().is_empty());
+    assert_ne!(interpreter.context, 0);
+    Ok(interpreter.context)
+}
+
+/// Run ADDRESS, CALLER and CALLVALUE in the current context.
+fn call_context(interpreter: &mut Interpreter) -> Result<[U256; 3]> {
+    let mut values = [U256::zero(); 3];
+    for (label, value) in ["sys_address", "sys_caller", "sys_callvalue"]
+        .into_iter()
+        .zip(&mut values)
+    {
+        run_syscall(interpreter, label, U256::zero(), &[])?;
+        *value = interpreter.pop();
+    }
+    Ok(values)
+}
+
+#[test]
+fn test_call_with_return_data() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter, &[0x00])?;
+
+    // CALL(gas, address, value, args_offset, args_size, ret_offset, ret_size)
+    let ret_size = 32;
+    let args = [
+        U256::MAX,
+        address,
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+        ret_size.into(),
+    ];
+    let new_ctx = enter_sub_context(&mut interpreter, "sys_call", &args)?;
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Address),
+        address
+    );
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Caller),
+        address
+    );
+
+    // The callee does RETURN(0, 32), with some data in its memory.
+    let return_data: Vec<u8> = (0..ret_size).map(|_| thread_rng().gen()).collect();
+    interpreter.set_current_general_memory(return_data.iter().map(|&b| b.into()).collect());
+    let callee_kexit_info = U256::zero();
+    run_syscall(
+        &mut interpreter,
+        "sys_return",
+        callee_kexit_info,
+        &[U256::zero(), ret_size.into()],
+    )?;
+
+    // Back in the parent, with `1` for success.
+    assert_eq!(interpreter.context, 0);
+    assert_eq!(interpreter.stack(), &[U256::one()]);
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        ret_size.into()
+    );
+    assert_eq!(
+        interpreter.get_memory_segment_bytes(Segment::MainMemory),
+        return_data
+    );
+
+    // The parent pays for its own memory expansion, the cold access, and the gas the callee
+    // consumed (its memory expansion); the rest of the forwarded gas is refunded.
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        3 + GAS_COLDACCOUNTACCESS + 3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_staticcall_forbids_sstore() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter, &[0x00])?;
+
+    // STATICCALL(gas, address, args_offset, args_size, ret_offset, ret_size)
+    let args = [
+        U256::MAX,
+        address,
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+    ];
+    let new_ctx = enter_sub_context(&mut interpreter, "sys_staticcall", &args)?;
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Static),
+        U256::one()
+    );
+
+    // The callee attempts SSTORE(1, 2).
+    let fault_exception = KERNEL.global_labels["fault_exception"];
+    interpreter.halt_offsets.push(fault_exception);
+    run_syscall(
+        &mut interpreter,
+        "sys_sstore",
+        U256::zero(),
+        &[U256::one(), 2.into()],
+    )?;
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        fault_exception
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_delegatecall_keeps_caller_and_value() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter, &[0x00])?;
+    let caller: Address = thread_rng().gen();
+    let caller = U256::from_big_endian(caller.as_bytes());
+    let value = U256::from(1234);
+    interpreter.set_context_metadata_field(0, ContextMetadata::Caller, caller);
+    interpreter.set_context_metadata_field(0, ContextMetadata::CallValue, value);
+
+    // DELEGATECALL(gas, address, args_offset, args_size, ret_offset, ret_size)
+    let args = [
+        U256::MAX,
+        address,
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+    ];
+    let new_ctx = enter_sub_context(&mut interpreter, "sys_delegatecall", &args)?;
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Address),
+        address
+    );
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Caller),
+        caller
+    );
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::CallValue),
+        value
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_call_gas_capped_at_all_but_one_64th() -> Result<()> {
+    // Gas that was charged before computing the gas to forward: the cold access only, since no
+    // memory is expanded.
+    let leftover_gas = GAS_LIMIT - GAS_COLDACCOUNTACCESS;
+    let cap = leftover_gas - leftover_gas / 64;
+
+    for (requested_gas, forwarded_gas) in [(U256::MAX, cap), (1000.into(), 1000)] {
+        let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+        let address = prepare_context(&mut interpreter, &[0x00])?;
+
+        let args = [
+            requested_gas,
+            address,
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+        ];
+        let new_ctx = enter_sub_context(&mut interpreter, "sys_call", &args)?;
+        assert_eq!(
+            interpreter.get_context_metadata_field(new_ctx, ContextMetadata::GasLimit),
+            forwarded_gas.into()
+        );
+        // The forwarded gas is charged to the caller upfront.
+        let parent_stack = &interpreter.generation_state.memory.contexts[0].segments
+            [Segment::Stack as usize]
+            .content;
+        let parent_kexit_info = parent_stack[parent_stack.len() - 2];
+        assert_eq!(
+            parent_kexit_info.0[3],
+            GAS_COLDACCOUNTACCESS + forwarded_gas
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_call_depth_limit() -> Result<()> {
+    let call_args = |address| {
+        [
+            U256::MAX,
+            address,
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+        ]
+    };
+
+    // The transaction's own context is at depth 1, so a context at depth 1024 can still call.
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter, &[0x00])?;
+    interpreter.set_global_metadata_field(GlobalMetadata::CallStackDepth, 1024.into());
+    enter_sub_context(&mut interpreter, "sys_call", &call_args(address))?;
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::CallStackDepth),
+        1025.into()
+    );
+
+    // The 1025th nested call fails without entering a new context.
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter, &[0x00])?;
+    interpreter.set_global_metadata_field(GlobalMetadata::CallStackDepth, 1025.into());
+    run_syscall(
+        &mut interpreter,
+        "sys_call",
+        kernel_kexit_info(),
+        &call_args(address),
+    )?;
+    assert_eq!(interpreter.context, 0);
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        0xdeadbeef
+    );
+    assert_eq!(interpreter.stack(), &[U256::zero()]);
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::CallStackDepth),
+        1025.into()
+    );
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        U256::zero()
+    );
+    // The gas that would have been forwarded is given back to the caller.
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        GAS_COLDACCOUNTACCESS
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_origin_unchanged_by_call() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter, &[0x00])?;
+    let origin: Address = thread_rng().gen();
+    let origin = U256::from_big_endian(origin.as_bytes());
+    interpreter.set_txn_field(NormalizedTxnField::Origin, origin);
+
+    run_syscall(&mut interpreter, "sys_origin", U256::zero(), &[])?;
+    assert_eq!(interpreter.pop(), origin);
+
+    let args = [
+        U256::MAX,
+        address,
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+        U256::zero(),
+    ];
+    enter_sub_context(&mut interpreter, "sys_call", &args)?;
+
+    // In the callee, CALLER is the calling account, but ORIGIN is still the transaction's sender.
+    run_syscall(&mut interpreter, "sys_caller", U256::zero(), &[])?;
+    assert_eq!(interpreter.pop(), address);
+    run_syscall(&mut interpreter, "sys_origin", U256::zero(), &[])?;
+    assert_eq!(interpreter.pop(), origin);
+
+    Ok(())
+}
+
+#[test]
+fn test_call_context_in_call_and_delegatecall() -> Result<()> {
+    let caller: Address = thread_rng().gen();
+    let caller = U256::from_big_endian(caller.as_bytes());
+    let (parent_value, call_value) = (U256::from(1234), U256::from(5));
+
+    // CALL(gas, address, value, args_offset, args_size, ret_offset, ret_size) and
+    // DELEGATECALL(gas, address, args_offset, args_size, ret_offset, ret_size), both from a
+    // context which was itself called by `caller` with `parent_value`.
+    for delegate in [false, true] {
+        let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+        let address = prepare_context(&mut interpreter, &[0x00])?;
+        interpreter.set_context_metadata_field(0, ContextMetadata::Caller, caller);
+        interpreter.set_context_metadata_field(0, ContextMetadata::CallValue, parent_value);
+        assert_eq!(
+            call_context(&mut interpreter)?,
+            [address, caller, parent_value]
+        );
+
+        let (label, args) = if delegate {
+            (
+                "sys_delegatecall",
+                vec![U256::MAX, address, 0.into(), 0.into(), 0.into(), 0.into()],
+            )
+        } else {
+            (
+                "sys_call",
+                vec![
+                    U256::MAX,
+                    address,
+                    call_value,
+                    0.into(),
+                    0.into(),
+                    0.into(),
+                    0.into(),
+                ],
+            )
+        };
+        enter_sub_context(&mut interpreter, label, &args)?;
+
+        // A delegate call runs the callee's code with the caller's address, caller and value.
+        let expected = if delegate {
+            [address, caller, parent_value]
+        } else {
+            [address, address, call_value]
+        };
+        assert_eq!(call_context(&mut interpreter)?, expected);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,301 @@
+use anyhow::Result;
+use ethereum_types::{Address, BigEndianHash, H256, U256};
+use keccak_hash::keccak;
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::balance::{prepare_interpreter, test_account};
+use crate::cpu::kernel::tests::kernel_kexit_info;
+use crate::cpu::kernel::tests::mpt::nibbles_64;
+use crate::fork::Fork;
+use crate::generation::mpt::AccountRlp;
+use crate::memory::segments::Segment;
+
+const GAS_LIMIT: u64 = 100_000;
+
+/// The maximum init code size of EIP-3860.
+const MAX_INITCODE_SIZE: usize = 0xc000;
+
+/// Some init code, stored at offset 0 of the creator's memory.
+const INIT_CODE: [u8; 3] = [0x60, 0x00, 0x00];
+
+/// Prepare the interpreter to run in the context of a freshly inserted account, which will act
+/// as the creator.
+fn prepare_context(interpreter: &mut Interpreter) -> Result<(Address, AccountRlp)> {
+    let address: Address = thread_rng().gen();
+    let account = test_account(U256::from(1000));
+    prepare_interpreter(interpreter, address, &account)?;
+    // Discard the state trie hash left by `prepare_interpreter`.
+    interpreter.pop();
+    assert!(interpreter.stack().is_empty());
+
+    let sender = U256::from_big_endian(address.as_bytes());
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, sender);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, GAS_LIMIT.into());
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, INIT_CODE.to_vec());
+    // Stop once the kernel hands control over to the constructor.
+    interpreter.halt_offsets.push(0);
+    Ok((address, account))
+}
+
+/// Run CREATE with `code_len` bytes of init code, read from offset 0 of the creator's memory.
+fn create(interpreter: &mut Interpreter, code_len: usize) -> Result<()> {
+    // CREATE(value, code_offset, code_len)
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_create"];
+    interpreter.push(code_len.into());
+    interpreter.push(U256::zero());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    let fault_exception = KERNEL.global_labels["fault_exception"];
+    interpreter.halt_offsets.push(fault_exception);
+    interpreter.run()
+}
+
+/// Insert another account in the state trie.
+fn insert_account(
+    interpreter: &mut Interpreter,
+    address: Address,
+    account: &AccountRlp,
+) -> Result<()> {
+    let trie_data = interpreter.get_trie_data_mut();
+    let value_ptr = trie_data.len();
+    trie_data.push(account.nonce);
+    trie_data.push(account.balance);
+    trie_data.push(H256::zero().into_uint());
+    trie_data.push(account.code_hash.into_uint());
+    let trie_data_len = trie_data.len().into();
+    interpreter.set_global_metadata_field(GlobalMetadata::TrieDataSize, trie_data_len);
+
+    let k = nibbles_64(U256::from_big_endian(
+        keccak(address.to_fixed_bytes()).as_bytes(),
+    ));
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["mpt_insert_state_trie"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(value_ptr.into());
+    interpreter.push(k.packed);
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+    Ok(())
+}
+
+/// Read the nonce of `address` from the state trie.
+fn nonce(interpreter: &mut Interpreter, address: Address) -> Result<U256> {
+    interpreter.set_is_kernel(true);
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["nonce"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(U256::from_big_endian(address.as_bytes()));
+    interpreter.run()?;
+    Ok(interpreter.pop())
+}
+
+#[test]
+fn test_create_enters_constructor() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let (sender, account) = prepare_context(&mut interpreter)?;
+
+    // CREATE(value, code_offset, code_len)
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_create"];
+    interpreter.push(INIT_CODE.len().into());
+    interpreter.push(U256::zero());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+
+    // We are now about to run the init code, in a new context.
+    let new_ctx = interpreter.context;
+    assert_ne!(new_ctx, 0);
+    assert_eq!(interpreter.generation_state.registers.program_counter, 0);
+    assert_eq!(
+        interpreter.generation_state.memory.contexts[new_ctx].segments[Segment::Code as usize]
+            .content,
+        INIT_CODE.map(U256::from).to_vec()
+    );
+
+    // The new address is KEC(RLP(sender, nonce))[12:].
+    let mut stream = rlp::RlpStream::new_list(2);
+    stream.append(&sender);
+    stream.append(&account.nonce);
+    let expected_address = Address::from_slice(&keccak(stream.out()).as_bytes()[12..]);
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Address),
+        U256::from_big_endian(expected_address.as_bytes())
+    );
+
+    // The creator pays for memory expansion, the base cost and the init code words, and then
+    // forwards all but one 64th of its remaining gas to the constructor.
+    let charged = 3 + 32_000 + 2;
+    let leftover_gas = GAS_LIMIT - charged;
+    let forwarded_gas = leftover_gas - leftover_gas / 64;
+    assert_eq!(
+        interpreter.get_context_metadata_field(new_ctx, ContextMetadata::GasLimit),
+        forwarded_gas.into()
+    );
+
+    // The creator's nonce was incremented, and the new account starts with nonce 1.
+    assert_eq!(nonce(&mut interpreter, sender)?, account.nonce + 1);
+    assert_eq!(nonce(&mut interpreter, expected_address)?, U256::one());
+
+    Ok(())
+}
+
+#[test]
+fn test_create_reverted_constructor_forgets_created_contract() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+    create(&mut interpreter, INIT_CODE.len())?;
+    let new_ctx = interpreter.context;
+    assert_ne!(new_ctx, 0);
+    let address = interpreter.get_context_metadata_field(new_ctx, ContextMetadata::Address);
+    assert_eq!(
+        interpreter.get_memory_segment(Segment::CreatedContracts)[..1],
+        [address]
+    );
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::CreatedContractsLen),
+        U256::one()
+    );
+
+    // REVERT(offset, size) from the constructor. The creator gets address 0 back, and the new
+    // contract is no longer one that may self-destruct.
+    interpreter.set_is_kernel(true);
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_revert"];
+    interpreter.push(U256::zero());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert_eq!(interpreter.context, 0);
+    assert_eq!(interpreter.stack(), &[U256::zero()]);
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::CreatedContractsLen),
+        U256::zero()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_create2_collision() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let (sender, account) = prepare_context(&mut interpreter)?;
+
+    // Deploy something at the CREATE2 address beforehand.
+    let salt = H256(thread_rng().gen());
+    let mut preimage = vec![0xff];
+    preimage.extend_from_slice(sender.as_bytes());
+    preimage.extend_from_slice(salt.as_bytes());
+    preimage.extend_from_slice(keccak(INIT_CODE).as_bytes());
+    let target = Address::from_slice(&keccak(preimage).as_bytes()[12..]);
+    insert_account(&mut interpreter, target, &test_account(U256::zero()))?;
+
+    // CREATE2(value, code_offset, code_len, salt)
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_create2"];
+    interpreter.push(salt.into_uint());
+    interpreter.push(INIT_CODE.len().into());
+    interpreter.push(U256::zero());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+
+    // The creation fails without entering the constructor, but still bumps the creator's nonce.
+    assert_eq!(interpreter.context, 0);
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        0xdeadbeef
+    );
+    assert_eq!(interpreter.stack(), &[U256::zero()]);
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        U256::zero()
+    );
+    assert_eq!(nonce(&mut interpreter, sender)?, account.nonce + 1);
+    assert_eq!(nonce(&mut interpreter, target)?, U256::from(1111));
+
+    Ok(())
+}
+
+#[test]
+fn test_create_depth_limit() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let (sender, account) = prepare_context(&mut interpreter)?;
+    interpreter.set_global_metadata_field(GlobalMetadata::CallStackDepth, 1025.into());
+
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_create"];
+    interpreter.push(INIT_CODE.len().into());
+    interpreter.push(U256::zero());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+
+    // The creation fails before the creator's nonce is incremented.
+    assert_eq!(interpreter.context, 0);
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        0xdeadbeef
+    );
+    assert_eq!(interpreter.stack(), &[U256::zero()]);
+    assert_eq!(nonce(&mut interpreter, sender)?, account.nonce);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_initcode_size_limit() -> Result<()> {
+    // Init code of exactly the maximum size is accepted.
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+    create(&mut interpreter, MAX_INITCODE_SIZE)?;
+    assert_ne!(interpreter.context, 0);
+    assert_eq!(interpreter.generation_state.registers.program_counter, 0);
+
+    // One more byte is an exceptional halt.
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+    create(&mut interpreter, MAX_INITCODE_SIZE + 1)?;
+    assert_eq!(interpreter.context, 0);
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        KERNEL.global_labels["fault_exception"]
+    );
+
+    // Before Shanghai, there is no limit.
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+    interpreter.set_global_metadata_field(GlobalMetadata::Fork, (Fork::Paris as u32).into());
+    create(&mut interpreter, MAX_INITCODE_SIZE + 1)?;
+    assert_ne!(interpreter.context, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_initcode_word_gas() -> Result<()> {
+    // 1000 bytes of init code are 32 words.
+    let code_len = 1000;
+    let memory_cost = 3 * 32 + 32 * 32 / 512;
+    let forwarded_gas = |fork: Fork| -> Result<U256> {
+        let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+        prepare_context(&mut interpreter)?;
+        interpreter.set_global_metadata_field(GlobalMetadata::Fork, (fork as u32).into());
+        create(&mut interpreter, code_len)?;
+        let new_ctx = interpreter.context;
+        assert_ne!(new_ctx, 0);
+        Ok(interpreter.get_context_metadata_field(new_ctx, ContextMetadata::GasLimit))
+    };
+    let expected = |charged: u64| {
+        let leftover_gas = GAS_LIMIT - charged;
+        U256::from(leftover_gas - leftover_gas / 64)
+    };
+
+    // From Shanghai, each init code word costs 2 gas.
+    assert_eq!(
+        forwarded_gas(Fork::Shanghai)?,
+        expected(memory_cost + 32_000 + 2 * 32)
+    );
+    assert_eq!(forwarded_gas(Fork::Paris)?, expected(memory_cost + 32_000));
+
+    Ok(())
+}
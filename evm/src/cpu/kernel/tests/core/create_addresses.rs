@@ -27,6 +27,32 @@ fn test_get_create_address() -> Result<()> {
     Ok(())
 }
 
+/// Addresses created by the same sender with its first few nonces. In particular, the RLP
+/// encoding of a zero nonce is the empty string.
+#[test]
+fn test_get_create_address_small_nonces() -> Result<()> {
+    let get_create_address = KERNEL.global_labels["get_create_address"];
+
+    let retaddr = 0xdeadbeefu32.into();
+    let sender = U256::from_big_endian(&hex!("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"));
+    let expected_addrs = [
+        hex!("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"),
+        hex!("343c43a37d37dff08ae8c4a11544c718abb4fcf8"),
+        hex!("f778b86fa74e846c4f0a1fbd1335fe81c00a0c91"),
+        hex!("fffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c"),
+    ];
+
+    for (nonce, expected_addr) in expected_addrs.iter().enumerate() {
+        let initial_stack = vec![retaddr, nonce.into(), sender];
+        let mut interpreter = Interpreter::new_with_kernel(get_create_address, initial_stack);
+        interpreter.run()?;
+
+        assert_eq!(interpreter.stack(), &[U256::from_big_endian(expected_addr)]);
+    }
+
+    Ok(())
+}
+
 struct Create2TestCase {
     code_hash: H256,
     salt: U256,
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+use crate::fork::Fork;
+use crate::memory::segments::Segment;
+use crate::witness::memory::MemoryAddress;
+
+/// Run the exception handler `label` as if `opcode` had trapped in user code with a stack of
+/// `user_stack_len` elements. Returns whether the handler jumped to `fault_exception`; an error
+/// means the handler refused the exception, i.e. it `PANIC`ed.
+fn run_exception_handler(label: &str, opcode: u8, user_stack_len: usize) -> Result<bool> {
+    let mut interpreter = exception_interpreter(label, opcode, user_stack_len);
+    let fault_exception = KERNEL.global_labels["fault_exception"];
+    interpreter.halt_offsets.push(fault_exception);
+    interpreter.run()?;
+    Ok(interpreter.generation_state.registers.program_counter == fault_exception)
+}
+
+/// Returns an interpreter about to run the exception handler `label`, as if `opcode` had trapped
+/// in user code with a stack of `user_stack_len` elements.
+fn exception_interpreter(label: &str, opcode: u8, user_stack_len: usize) -> Interpreter<'static> {
+    // In the interpreter, user code shares the code segment of context 0 with the kernel, so put
+    // the faulting opcode just past the end of the kernel.
+    let pc = KERNEL.code.len();
+    let trap_info = U256::from(pc);
+    let mut initial_stack = vec![U256::zero(); user_stack_len];
+    initial_stack.push(trap_info);
+
+    let mut interpreter = Interpreter::new_with_kernel(KERNEL.global_labels[label], initial_stack);
+    interpreter
+        .generation_state
+        .memory
+        .set(MemoryAddress::new(0, Segment::Code, pc), opcode.into());
+    interpreter
+}
+
+#[test]
+fn test_stack_underflow() -> Result<()> {
+    let add = get_opcode("ADD");
+    // Popping from an empty stack, or with a single element, is a genuine underflow.
+    assert!(run_exception_handler("exc_stack_underflow", add, 0)?);
+    assert!(run_exception_handler("exc_stack_underflow", add, 1)?);
+    // With enough elements, the kernel must refuse to fault.
+    assert!(run_exception_handler("exc_stack_underflow", add, 2).is_err());
+
+    // DUPn needs n elements, and SWAPn needs n + 1.
+    for (opcode, min_stack_len) in [("DUP3", 3), ("SWAP16", 17)] {
+        let opcode = get_opcode(opcode);
+        assert!(run_exception_handler(
+            "exc_stack_underflow",
+            opcode,
+            min_stack_len - 1
+        )?);
+        assert!(run_exception_handler("exc_stack_underflow", opcode, min_stack_len).is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_stack_overflow() -> Result<()> {
+    let push1 = get_push_opcode(1);
+    // Pushing onto a full stack is a genuine overflow.
+    assert!(run_exception_handler("exc_stack_overflow", push1, 1024)?);
+    // With room left, or for an opcode that doesn't grow the stack, the kernel must refuse.
+    assert!(run_exception_handler("exc_stack_overflow", push1, 1023).is_err());
+    assert!(run_exception_handler("exc_stack_overflow", get_opcode("POP"), 1024).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_out_of_gas() -> Result<()> {
+    let run = |opcode, gas_used: u64| {
+        let mut interpreter = exception_interpreter("exc_out_of_gas", opcode, 0);
+        let trap_info = interpreter.pop() + (U256::from(gas_used) << 192);
+        interpreter.push(trap_info);
+        interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100.into());
+        let fault_exception = KERNEL.global_labels["fault_exception"];
+        interpreter.halt_offsets.push(fault_exception);
+        interpreter.run()?;
+        Ok::<_, anyhow::Error>(
+            interpreter.generation_state.registers.program_counter == fault_exception,
+        )
+    };
+
+    // ADD costs 3 gas, so it needs one more gas than is left...
+    assert!(run(get_opcode("ADD"), 98)?);
+    // ...but draining the gas exactly is fine, and the kernel must refuse to fault.
+    assert!(run(get_opcode("ADD"), 97).is_err());
+    // Syscalls aren't charged natively, so they never run out of gas this way.
+    assert!(run(get_opcode("SSTORE"), 100).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_opcode() -> Result<()> {
+    // INVALID, and bytes with no assigned instruction, including 0x49 (BLOBHASH in Cancun, which
+    // isn't supported).
+    for opcode in [0xfe, 0x0c, 0x1e, 0x21, 0x49, 0x4f, 0xa5, 0xef, 0xf6, 0xfc] {
+        assert!(run_exception_handler("exc_invalid_opcode", opcode, 0)?);
+    }
+    // The kernel must refuse to fault on any assigned opcode: ADD, TLOAD, MCOPY, PUSH0,
+    // JUMPDEST, LOG4, CREATE2, STATICCALL and SELFDESTRUCT.
+    for opcode in [0x01, 0x5c, 0x5e, 0x5f, 0x5b, 0xa4, 0xf5, 0xfa, 0xff] {
+        assert!(run_exception_handler("exc_invalid_opcode", opcode, 0).is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_opcode_before_activation() -> Result<()> {
+    let run = |fork: Fork, opcode: u8| {
+        let mut interpreter = exception_interpreter("exc_invalid_opcode", opcode, 0);
+        interpreter.set_global_metadata_field(GlobalMetadata::Fork, (fork as u32).into());
+        let fault_exception = KERNEL.global_labels["fault_exception"];
+        interpreter.halt_offsets.push(fault_exception);
+        interpreter.run()?;
+        Ok::<_, anyhow::Error>(
+            interpreter.generation_state.registers.program_counter == fault_exception,
+        )
+    };
+    // BASEFEE, TLOAD, TSTORE, MCOPY and PUSH0 fault exactly in the forks that predate them.
+    for fork in Fork::all() {
+        for opcode in [0x48, 0x5c, 0x5d, 0x5e, get_push_opcode(0)] {
+            if fork.has_opcode(opcode) {
+                assert!(run(fork, opcode).is_err());
+            } else {
+                assert!(run(fork, opcode)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_opcode_consumes_all_gas() -> Result<()> {
+    let mut interpreter = exception_interpreter("exc_invalid_opcode", 0xfe, 0);
+    // The faulting context was entered with a checkpoint, from a parent (which for simplicity is
+    // the same context) resuming at 0xdeadbeef.
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+    interpreter.set_context_metadata_field(
+        0,
+        ContextMetadata::ParentProgramCounter,
+        0xdeadbeefu32.into(),
+    );
+    interpreter.set_context_metadata_field(0, ContextMetadata::ContextCheckpointsLen, 1.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::CurrentCheckpoint, 1.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::CallStackDepth, 1.into());
+    interpreter.run()?;
+
+    // The parent gets `0` for failure, on top of no leftover gas at all.
+    assert_eq!(interpreter.stack(), &[U256::zero(), U256::zero()]);
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        U256::zero()
+    );
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+
+const GAS_BASE: u64 = 2;
+
+#[test]
+fn test_gas_excludes_own_cost() -> Result<()> {
+    let gas_limit = 1000;
+    // As if the user code had already run PUSH1, PUSH1, ADD, PUSH1 before GAS.
+    let gas_used_before = 3 + 3 + 3 + 3;
+    let kexit_info = U256::from(0xdeadbeefu32) + (U256::from(gas_used_before) << 192);
+
+    let mut interpreter =
+        Interpreter::new_with_kernel(KERNEL.global_labels["sys_gas"], vec![kexit_info]);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, gas_limit.into());
+    interpreter.run()?;
+
+    // GAS reports what is left once its own cost has been paid.
+    let gas_used = gas_used_before + GAS_BASE;
+    assert_eq!(interpreter.stack(), &[(gas_limit - gas_used).into()]);
+    assert_eq!(interpreter.generation_state.registers.gas_used, gas_used);
+
+    Ok(())
+}
@@ -5,9 +5,13 @@ use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
 use crate::cpu::kernel::interpreter::Interpreter;
+use crate::fork::Fork;
+use crate::memory::segments::Segment;
 
 const GAS_TX: u32 = 21_000;
 const GAS_TXCREATE: u32 = 32_000;
+const GAS_TXDATAZERO: u32 = 4;
+const MAX_INITCODE_SIZE: usize = 0xc000;
 
 #[test]
 fn test_intrinsic_gas() -> Result<()> {
@@ -28,3 +32,30 @@ fn test_intrinsic_gas() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_intrinsic_gas_initcode() -> Result<()> {
+    let run = |fork: Fork, data_len: usize| -> Result<Vec<U256>> {
+        let intrinsic_gas = KERNEL.global_labels["intrinsic_gas"];
+        let mut interpreter =
+            Interpreter::new_with_kernel(intrinsic_gas, vec![0xdeadbeefu32.into()]);
+        interpreter.set_global_metadata_field(GlobalMetadata::ContractCreation, U256::one());
+        interpreter.set_global_metadata_field(GlobalMetadata::Fork, (fork as u32).into());
+        interpreter.set_txn_field(NormalizedTxnField::DataLen, data_len.into());
+        interpreter.set_memory_segment(Segment::TxnData, vec![U256::zero(); data_len]);
+        interpreter.run()?;
+        Ok(interpreter.stack().to_vec())
+    };
+
+    // 64 bytes of init code are 2 words, charged 2 gas each from Shanghai onwards.
+    let base = GAS_TX + GAS_TXCREATE + 64 * GAS_TXDATAZERO;
+    assert_eq!(run(Fork::Shanghai, 64)?, vec![(base + 4).into()]);
+    assert_eq!(run(Fork::Paris, 64)?, vec![base.into()]);
+
+    // A deployment with more init code than the limit is invalid, but only from Shanghai.
+    assert!(run(Fork::Shanghai, MAX_INITCODE_SIZE).is_ok());
+    assert!(run(Fork::Shanghai, MAX_INITCODE_SIZE + 1).is_err());
+    assert!(run(Fork::Paris, MAX_INITCODE_SIZE + 1).is_ok());
+
+    Ok(())
+}
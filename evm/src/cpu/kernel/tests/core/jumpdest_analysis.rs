@@ -40,3 +40,89 @@ fn test_jumpdest_analysis() -> Result<()> {
 
     Ok(())
 }
+
+/// Run the JUMPDEST analysis of `code` in a fresh context, then run `code` in user mode.
+fn run_user_code(code: Vec<u8>) -> Result<Interpreter<'static>> {
+    let jumpdest_analysis = KERNEL.global_labels["jumpdest_analysis"];
+    const CONTEXT: usize = 3; // arbitrary
+
+    let initial_stack = vec![0xDEADBEEFu32.into(), code.len().into(), CONTEXT.into()];
+    let mut interpreter = Interpreter::new_with_kernel(jumpdest_analysis, initial_stack);
+    interpreter.set_code(CONTEXT, code);
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), vec![]);
+
+    interpreter.set_context(CONTEXT);
+    interpreter.set_is_kernel(false);
+    interpreter.generation_state.registers.program_counter = 0;
+    interpreter.run()?;
+    Ok(interpreter)
+}
+
+#[test]
+fn test_valid_jump() -> Result<()> {
+    let push1 = get_push_opcode(1);
+    let jump = get_opcode("JUMP");
+    let jumpdest = get_opcode("JUMPDEST");
+    let stop = get_opcode("STOP");
+
+    #[rustfmt::skip]
+    let code: Vec<u8> = vec![
+        push1, 5,
+        jump,
+        push1, 1, // skipped
+        jumpdest,
+        push1, 2,
+        stop,
+    ];
+
+    let interpreter = run_user_code(code)?;
+    assert_eq!(interpreter.stack(), &[2.into()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_jump_into_push_data() {
+    let push1 = get_push_opcode(1);
+    let jump = get_opcode("JUMP");
+    let jumpdest = get_opcode("JUMPDEST");
+    let stop = get_opcode("STOP");
+
+    #[rustfmt::skip]
+    let code: Vec<u8> = vec![
+        push1, 4,
+        jump,
+        push1, jumpdest, // part of PUSH1
+        stop,
+    ];
+
+    assert!(run_user_code(code).is_err());
+}
+
+#[test]
+fn test_jumpi() -> Result<()> {
+    let push1 = get_push_opcode(1);
+    let jumpi = get_opcode("JUMPI");
+    let jumpdest = get_opcode("JUMPDEST");
+    let stop = get_opcode("STOP");
+
+    for (cond, expected) in [(0u8, 1u64), (1, 2), (0xff, 2)] {
+        #[rustfmt::skip]
+        let code: Vec<u8> = vec![
+            push1, cond,
+            push1, 8,
+            jumpi,
+            push1, 1, // reached if the condition is zero
+            stop,
+            jumpdest,
+            push1, 2,
+            stop,
+        ];
+
+        let interpreter = run_user_code(code)?;
+        assert_eq!(interpreter.stack(), &[expected.into()]);
+    }
+
+    Ok(())
+}
@@ -1,4 +1,11 @@
 mod access_lists;
+mod call;
+mod create;
 mod create_addresses;
+mod exception;
+mod gas;
 mod intrinsic_gas;
 mod jumpdest_analysis;
+mod precompiles;
+mod refund;
+mod terminate;
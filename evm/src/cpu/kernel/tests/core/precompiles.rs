@@ -0,0 +1,232 @@
+use anyhow::Result;
+use hex_literal::hex;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::memory::segments::Segment;
+use crate::witness::memory::MemoryAddress;
+
+const GAS_LIMIT: u64 = 100_000;
+
+/// The outcome of a precompile call, as seen by the caller.
+struct PrecompileResult {
+    success: bool,
+    gas_used: u64,
+    output: Vec<u8>,
+}
+
+/// Call the precompile at `address` with `input` from context 0, and stop once control returns
+/// to the caller. The caller's return data buffer starts out holding the output of some earlier
+/// call, which the precompile must replace.
+fn call_precompile(address: u8, input: &[u8]) -> Result<PrecompileResult> {
+    let new_ctx = 1;
+    // stack: address, retdest, new_ctx
+    let initial_stack = vec![new_ctx.into(), 0xdeadbeefu32.into(), address.into()];
+    let mut interpreter =
+        Interpreter::new_with_kernel(KERNEL.global_labels["handle_precompiles"], initial_stack);
+    interpreter.set_context_metadata_field(new_ctx, ContextMetadata::GasLimit, GAS_LIMIT.into());
+    interpreter.set_context_metadata_field(
+        new_ctx,
+        ContextMetadata::CalldataSize,
+        input.len().into(),
+    );
+    interpreter.set_memory_segment_bytes(Segment::Returndata, vec![0xff; 64]);
+    interpreter.set_context_metadata_field(0, ContextMetadata::ReturndataSize, 64.into());
+    for (i, &byte) in input.iter().enumerate() {
+        interpreter.generation_state.memory.set(
+            MemoryAddress::new(new_ctx, Segment::Calldata, i),
+            byte.into(),
+        );
+    }
+    interpreter
+        .halt_offsets
+        .push(KERNEL.global_labels["after_precompile"]);
+    interpreter.run()?;
+
+    // stack: success, leftover_gas, new_ctx
+    assert_eq!(interpreter.context, 0);
+    let success = !interpreter.pop().is_zero();
+    let leftover_gas = interpreter.pop().as_u64();
+    let returndata_size = interpreter
+        .get_context_metadata_field(0, ContextMetadata::ReturndataSize)
+        .as_usize();
+    let mut output = interpreter.get_memory_segment_bytes(Segment::Returndata);
+    output.resize(returndata_size, 0);
+    Ok(PrecompileResult {
+        success,
+        gas_used: GAS_LIMIT - leftover_gas,
+        output,
+    })
+}
+
+/// The 128-byte input of ECRECOVER: the message hash, `v`, `r` and `s`, as 32-byte words.
+fn ecrecover_input(hash: [u8; 32], v: u8, r: [u8; 32], s: [u8; 32]) -> Vec<u8> {
+    let mut input = hash.to_vec();
+    input.extend([0; 31]);
+    input.push(v);
+    input.extend(r);
+    input.extend(s);
+    input
+}
+
+#[test]
+fn test_ecrecover() -> Result<()> {
+    // The example transaction of EIP-155, signed by 0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f.
+    let hash = hex!("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+    let r = hex!("28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276");
+    let s = hex!("67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+    let result = call_precompile(1, &ecrecover_input(hash, 27, r, s))?;
+
+    assert!(result.success);
+    assert_eq!(result.gas_used, 3000);
+    let mut expected = [0; 32];
+    expected[12..].copy_from_slice(&hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"));
+    assert_eq!(result.output, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_ecrecover_malformed_input() -> Result<()> {
+    let hash = hex!("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+    let r = hex!("28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276");
+    let s = hex!("67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+
+    // An invalid `v`, an `r` of zero, and a truncated input which leaves `r` and `s` zero.
+    let inputs = [
+        ecrecover_input(hash, 29, r, s),
+        ecrecover_input(hash, 27, [0; 32], s),
+        ecrecover_input(hash, 27, r, s)[..64].to_vec(),
+    ];
+    for input in inputs {
+        let result = call_precompile(1, &input)?;
+        // The call still succeeds and is charged in full, but returns nothing.
+        assert!(result.success);
+        assert_eq!(result.gas_used, 3000);
+        assert_eq!(result.output, Vec::<u8>::new());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sha256() -> Result<()> {
+    let empty = call_precompile(2, &[])?;
+    assert!(empty.success);
+    assert_eq!(empty.gas_used, 60);
+    assert_eq!(
+        empty.output,
+        hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    );
+
+    // The two-block message from FIPS 180-2, 112 bytes or 4 words.
+    let input = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno\
+                  ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+    let result = call_precompile(2, input)?;
+    assert!(result.success);
+    assert_eq!(result.gas_used, 60 + 12 * 4);
+    assert_eq!(
+        result.output,
+        hex!("cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_identity() -> Result<()> {
+    // The stale return data of the caller is discarded, even when there is nothing to return.
+    let empty = call_precompile(4, &[])?;
+    assert!(empty.success);
+    assert_eq!(empty.gas_used, 15);
+    assert_eq!(empty.output, Vec::<u8>::new());
+
+    let word: Vec<u8> = (1..=32).collect();
+    let result = call_precompile(4, &word)?;
+    assert!(result.success);
+    assert_eq!(result.gas_used, 15 + 3);
+    assert_eq!(result.output, word);
+
+    // One extra byte takes up a whole second word.
+    let two_words: Vec<u8> = (1..=33).collect();
+    let result = call_precompile(4, &two_words)?;
+    assert!(result.success);
+    assert_eq!(result.gas_used, 15 + 3 * 2);
+    assert_eq!(result.output, two_words);
+
+    Ok(())
+}
+
+/// The input of MODEXP: the lengths of the base, exponent and modulus, as 32-byte words, followed
+/// by the three values themselves.
+fn expmod_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+    let mut input = vec![];
+    for value in [base, exponent, modulus] {
+        input.extend([0; 24]);
+        input.extend((value.len() as u64).to_be_bytes());
+    }
+    input.extend(base);
+    input.extend(exponent);
+    input.extend(modulus);
+    input
+}
+
+#[test]
+fn test_expmod_zero_to_the_zero() -> Result<()> {
+    let result = call_precompile(5, &expmod_input(&[0], &[0], &[13]))?;
+    assert!(result.success);
+    assert_eq!(result.gas_used, 200);
+    assert_eq!(result.output, vec![1]);
+    Ok(())
+}
+
+#[test]
+fn test_expmod_rsa() -> Result<()> {
+    let base = hex!("3fd4235992edcf451a1afe878b33e968617959ce3f1f65a8de5271007814e8a25f2dd97f1cfb10f62827688de6a16a3b0d464138a62332553fc1ea36f17fd374");
+    let modulus = hex!("c6a5387777330bdbd7210dff076ce2ef87b0b125ec1d7da0a6eb8c9ebd69fe29d76d4330f1446beab0c11fdecb91ce375bc8fbbcbde5c0994164d8399f767c45");
+    let result = call_precompile(5, &expmod_input(&base, &[0x01, 0x00, 0x01], &modulus))?;
+
+    assert!(result.success);
+    // The multiplication complexity is ceil(64 / 8)^2 = 64, and 65537 needs 16 iterations.
+    assert_eq!(result.gas_used, 64 * 16 / 3);
+    assert_eq!(
+        result.output,
+        hex!("ae26d498a14a1ba2900a0dd8f7f551f00c999badef38ff0e68735eac93b0b1855bc9063bc3ddfda5e3e8ac433b287d73e447b8454d07c77012fb157d457a431e")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_expmod_eip_2565_gas() -> Result<()> {
+    // The first example of EIP-198: 3^(p - 1) mod p, for the secp256k1 base field order p.
+    let exponent = hex!("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e");
+    let modulus = hex!("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f");
+    let result = call_precompile(5, &expmod_input(&[3], &exponent, &modulus))?;
+
+    assert!(result.success);
+    assert_eq!(result.gas_used, 1360);
+    let mut one = [0; 32];
+    one[31] = 1;
+    assert_eq!(result.output, one);
+
+    Ok(())
+}
+
+#[test]
+fn test_expmod_zero_modulus() -> Result<()> {
+    // A zero modulus gives an output of zeros, as long as the modulus.
+    let result = call_precompile(5, &expmod_input(&[2], &[3], &[0; 32]))?;
+    assert!(result.success);
+    assert_eq!(result.gas_used, 200);
+    assert_eq!(result.output, [0; 32]);
+
+    // An empty modulus gives an empty output.
+    let result = call_precompile(5, &expmod_input(&[2], &[3], &[]))?;
+    assert!(result.success);
+    assert_eq!(result.gas_used, 200);
+    assert_eq!(result.output, Vec::<u8>::new());
+
+    Ok(())
+}
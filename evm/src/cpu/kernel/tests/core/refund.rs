@@ -0,0 +1,166 @@
+use anyhow::Result;
+use ethereum_types::{Address, U256};
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::balance::{prepare_interpreter, test_account};
+use crate::cpu::kernel::tests::kernel_kexit_info;
+use crate::fork::Fork;
+
+const REFUND_SCLEAR: u64 = 4800;
+const REFUND_SCLEAR_BEFORE_LONDON: u64 = 15_000;
+const GAS_SRESET: u64 = 2900;
+const GAS_WARMACCESS: u64 = 100;
+
+fn sstore(interpreter: &mut Interpreter, slot: U256, value: U256) -> Result<()> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_sstore"];
+    interpreter.push(value);
+    interpreter.push(slot);
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+    Ok(())
+}
+
+fn refund_counter(interpreter: &Interpreter) -> U256 {
+    interpreter.get_global_metadata_field(GlobalMetadata::RefundCounter)
+}
+
+/// Returns an interpreter where `slot` of the current account holds `value` at the start of the
+/// transaction, i.e. `value` is also the slot's original value.
+fn prepare_slot(slot: U256, value: U256) -> Result<Interpreter<'static>> {
+    let mut rng = thread_rng();
+    let address: Address = rng.gen();
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_interpreter(&mut interpreter, address, &test_account(U256::from(1000)))?;
+    // Discard the state trie hash left by `prepare_interpreter`.
+    interpreter.pop();
+    let address = U256::from_big_endian(address.as_bytes());
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, address);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 100_000.into());
+
+    sstore(&mut interpreter, slot, value)?;
+    // Start a fresh transaction, which forgets the original values and refunds seen so far.
+    interpreter.set_global_metadata_field(GlobalMetadata::AccessedStorageKeysLen, 0.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::RefundCounter, 0.into());
+    Ok(interpreter)
+}
+
+#[test]
+fn test_sstore_clear_refund() -> Result<()> {
+    let slot = U256::from(7);
+    let mut interpreter = prepare_slot(slot, 0x1234.into())?;
+
+    sstore(&mut interpreter, slot, U256::zero())?;
+    assert_eq!(refund_counter(&interpreter), REFUND_SCLEAR.into());
+    Ok(())
+}
+
+#[test]
+fn test_sstore_clear_refund_before_london() -> Result<()> {
+    let slot = U256::from(7);
+    let mut interpreter = prepare_slot(slot, 0x1234.into())?;
+    interpreter.set_global_metadata_field(GlobalMetadata::Fork, (Fork::Berlin as u32).into());
+
+    sstore(&mut interpreter, slot, U256::zero())?;
+    assert_eq!(
+        refund_counter(&interpreter),
+        REFUND_SCLEAR_BEFORE_LONDON.into()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sstore_refund_clawback() -> Result<()> {
+    let slot = U256::from(7);
+    let original_value = U256::from(0x1234);
+    let mut interpreter = prepare_slot(slot, original_value)?;
+
+    sstore(&mut interpreter, slot, U256::zero())?;
+    assert_eq!(refund_counter(&interpreter), REFUND_SCLEAR.into());
+
+    // Dirtying the cleared slot again takes back the clearing refund.
+    sstore(&mut interpreter, slot, 0x5678.into())?;
+    assert_eq!(refund_counter(&interpreter), U256::zero());
+
+    // Restoring the original value refunds the difference with a warm access.
+    sstore(&mut interpreter, slot, original_value)?;
+    assert_eq!(
+        refund_counter(&interpreter),
+        (GAS_SRESET - GAS_WARMACCESS).into()
+    );
+    Ok(())
+}
+
+/// Ends a message transaction to an account without code, which therefore used only its
+/// `intrinsic_gas`, with `refund` in the refund counter, in `fork`. The gas price is 1 wei, with
+/// no tip. Returns the gas used, net of refunds, and the wei credited back to the sender.
+fn end_txn(fork: Fork, intrinsic_gas: u64, refund: u64) -> Result<(U256, U256)> {
+    const GAS_LIMIT: u64 = 100_000;
+    let sender: Address = thread_rng().gen();
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_interpreter(&mut interpreter, sender, &test_account(U256::zero()))?;
+    // Discard the state trie hash left by `prepare_interpreter`.
+    interpreter.pop();
+    let sender = U256::from_big_endian(sender.as_bytes());
+    interpreter.set_txn_field(NormalizedTxnField::Origin, sender);
+    interpreter.set_txn_field(NormalizedTxnField::GasLimit, GAS_LIMIT.into());
+    interpreter.set_txn_field(NormalizedTxnField::IntrinsicGas, intrinsic_gas.into());
+    interpreter.set_txn_field(NormalizedTxnField::ComputedFeePerGas, U256::one());
+    interpreter.set_txn_field(NormalizedTxnField::ComputedPriorityFeePerGas, U256::zero());
+    interpreter.set_global_metadata_field(GlobalMetadata::RefundCounter, refund.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::Fork, (fork as u32).into());
+
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["process_message_txn_return"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+    let gas_used = interpreter.get_global_metadata_field(GlobalMetadata::TxnGasUsed);
+
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["balance"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(sender);
+    interpreter.run()?;
+    let refunded = interpreter.pop();
+    Ok((gas_used, refunded))
+}
+
+#[test]
+fn test_refund_capped_at_fifth_of_gas_used() -> Result<()> {
+    let gas_used = 21_000;
+    let unused_gas = 100_000 - gas_used;
+
+    // A refund counter below gas_used / 5 is refunded in full...
+    let (net_gas_used, refunded) = end_txn(Fork::default(), gas_used, 4_000)?;
+    assert_eq!(net_gas_used, (gas_used - 4_000).into());
+    assert_eq!(refunded, (unused_gas + 4_000).into());
+
+    // ...but a larger one is capped, so the sender gets back exactly gas_used / 5.
+    assert!(2 * REFUND_SCLEAR > gas_used / 5);
+    let (net_gas_used, refunded) = end_txn(Fork::default(), gas_used, 2 * REFUND_SCLEAR)?;
+    assert_eq!(net_gas_used, (gas_used - gas_used / 5).into());
+    assert_eq!(refunded, (unused_gas + gas_used / 5).into());
+    Ok(())
+}
+
+#[test]
+fn test_refund_capped_at_half_of_gas_used_before_london() -> Result<()> {
+    let gas_used = 21_000;
+    let unused_gas = 100_000 - gas_used;
+
+    // Before London, refunds are capped at gas_used / 2 instead.
+    let (net_gas_used, refunded) = end_txn(Fork::Berlin, gas_used, 10_000)?;
+    assert_eq!(net_gas_used, (gas_used - 10_000).into());
+    assert_eq!(refunded, (unused_gas + 10_000).into());
+
+    assert!(REFUND_SCLEAR_BEFORE_LONDON > gas_used / 2);
+    let (net_gas_used, refunded) = end_txn(Fork::Berlin, gas_used, REFUND_SCLEAR_BEFORE_LONDON)?;
+    assert_eq!(net_gas_used, (gas_used - gas_used / 2).into());
+    assert_eq!(refunded, (unused_gas + gas_used / 2).into());
+    Ok(())
+}
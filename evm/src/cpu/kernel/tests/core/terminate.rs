@@ -0,0 +1,308 @@
+use anyhow::Result;
+use ethereum_types::{Address, U256};
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::balance::{prepare_interpreter, test_account};
+use crate::cpu::kernel::tests::kernel_kexit_info;
+use crate::fork::Fork;
+use crate::memory::segments::Segment;
+
+const GAS_LIMIT: u32 = 100_000;
+
+/// Run `sload_current` on `slot`.
+fn sload(interpreter: &mut Interpreter, slot: U256) -> Result<U256> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sload_current"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(slot);
+    interpreter.run()?;
+    Ok(interpreter.pop())
+}
+
+/// Run `balance` on `address`.
+fn balance(interpreter: &mut Interpreter, address: U256) -> Result<U256> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["balance"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(address);
+    interpreter.run()?;
+    Ok(interpreter.pop())
+}
+
+/// Prepare the interpreter to run in a context of a freshly inserted account, as if it had just
+/// been entered through a call: a checkpoint has been taken, and the parent (which for simplicity
+/// is the same context) resumes at 0xdeadbeef. Returns the account's address.
+fn prepare_context(interpreter: &mut Interpreter) -> Result<U256> {
+    let mut rng = thread_rng();
+    let address: Address = rng.gen();
+    let account = test_account(U256::from(1000));
+    prepare_interpreter(interpreter, address, &account)?;
+    // Discard the state trie hash left by `prepare_interpreter`.
+    interpreter.pop();
+    assert!(interpreter.stack().is_empty());
+
+    let address = U256::from_big_endian(address.as_bytes());
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, address);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, GAS_LIMIT.into());
+    interpreter.set_context_metadata_field(
+        0,
+        ContextMetadata::ParentProgramCounter,
+        0xdeadbeefu32.into(),
+    );
+    interpreter.set_context_metadata_field(0, ContextMetadata::ContextCheckpointsLen, 1.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::CurrentCheckpoint, 1.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::CallStackDepth, 1.into());
+    Ok(address)
+}
+
+/// Run SELFDESTRUCT(recipient), and return the leftover gas handed back to the parent.
+fn selfdestruct(interpreter: &mut Interpreter, recipient: U256) -> Result<U256> {
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["sys_selfdestruct"];
+    interpreter.push(recipient);
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    // The parent gets `1` for success, on top of the leftover gas.
+    assert_eq!(interpreter.pop(), U256::one());
+    Ok(interpreter.pop())
+}
+
+fn selfdestruct_list(interpreter: &Interpreter) -> Vec<U256> {
+    let len = interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen);
+    interpreter.generation_state.memory.contexts[0].segments[Segment::SelfDestructList as usize]
+        .content[..len.as_usize()]
+        .to_vec()
+}
+
+#[test]
+fn test_revert_discards_storage_write() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+
+    // SSTORE(slot, value)
+    let (slot, value) = (U256::from(7), U256::from(0x1234));
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_sstore"];
+    interpreter.push(value);
+    interpreter.push(slot);
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+    assert_eq!(sload(&mut interpreter, slot)?, value);
+
+    // REVERT(offset, size) with some error data in memory.
+    let revert_data = vec![0xaa, 0xbb, 0xcc];
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, revert_data.clone());
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_revert"];
+    interpreter.push(revert_data.len().into());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+
+    // The parent gets `0` for failure, on top of the leftover gas. Only memory expansion was
+    // charged to this kexit_info.
+    assert_eq!(interpreter.stack(), &[(GAS_LIMIT - 3).into(), U256::zero()]);
+
+    // The return data is preserved...
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        revert_data.len().into()
+    );
+    assert_eq!(
+        interpreter.get_memory_segment_bytes(Segment::Returndata),
+        revert_data
+    );
+
+    // ...but the storage write is rolled back.
+    interpreter.pop();
+    interpreter.pop();
+    assert_eq!(sload(&mut interpreter, slot)?, U256::zero());
+
+    Ok(())
+}
+
+#[test]
+fn test_return_keeps_storage_write() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+
+    // SSTORE(slot, value)
+    let (slot, value) = (U256::from(7), U256::from(0x1234));
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_sstore"];
+    interpreter.push(value);
+    interpreter.push(slot);
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+
+    // RETURN(offset, size) with a word in memory, preceded by a byte that isn't returned.
+    let return_data: Vec<u8> = (0..32).map(|_| thread_rng().gen()).collect();
+    let mut memory = vec![0xff];
+    memory.extend(&return_data);
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, memory);
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_return"];
+    interpreter.push(return_data.len().into());
+    interpreter.push(U256::one());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+
+    // The parent gets `1` for success, on top of the leftover gas. Only the expansion to two
+    // words of memory was charged to this kexit_info.
+    assert_eq!(interpreter.stack(), &[(GAS_LIMIT - 6).into(), U256::one()]);
+    interpreter.pop();
+    interpreter.pop();
+
+    // Exactly the returned bytes are available to the parent...
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        return_data.len().into()
+    );
+    assert_eq!(
+        interpreter.get_memory_segment_bytes(Segment::Returndata),
+        return_data
+    );
+
+    // ...which can copy them to its memory with RETURNDATACOPY(dest_offset, offset, size)...
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, vec![]);
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["sys_returndatacopy"];
+    interpreter.push(return_data.len().into());
+    interpreter.push(U256::zero());
+    interpreter.push(U256::zero());
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert!(interpreter.stack().is_empty());
+    assert_eq!(
+        interpreter.get_memory_segment_bytes(Segment::MainMemory),
+        return_data
+    );
+
+    // ...and unlike with REVERT, the storage write is kept.
+    assert_eq!(sload(&mut interpreter, slot)?, value);
+
+    Ok(())
+}
+
+#[test]
+fn test_stop_returns_empty_data() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    prepare_context(&mut interpreter)?;
+    // Some stale return data from an earlier call.
+    interpreter.set_context_metadata_field(0, ContextMetadata::ReturndataSize, 5.into());
+
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["sys_stop"];
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+
+    // The parent gets `1` for success, and all of the gas back since STOP is free.
+    assert_eq!(interpreter.stack(), &[GAS_LIMIT.into(), U256::one()]);
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+        U256::zero()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_zero_size_return_at_large_offset() -> Result<()> {
+    // Nothing is returned, so neither RETURN nor REVERT expands memory, whatever the offset.
+    for (label, success) in [("sys_return", U256::one()), ("sys_revert", U256::zero())] {
+        let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+        prepare_context(&mut interpreter)?;
+        // Some stale return data from an earlier call.
+        interpreter.set_context_metadata_field(0, ContextMetadata::ReturndataSize, 5.into());
+
+        interpreter.generation_state.registers.program_counter = KERNEL.global_labels[label];
+        interpreter.push(U256::zero());
+        interpreter.push(U256::one() << 32);
+        interpreter.push(kernel_kexit_info());
+        interpreter.run()?;
+
+        assert_eq!(interpreter.stack(), &[GAS_LIMIT.into(), success], "{label}");
+        assert_eq!(
+            interpreter.get_context_metadata_field(0, ContextMetadata::ReturndataSize),
+            U256::zero(),
+            "{label}"
+        );
+        assert_eq!(
+            interpreter.get_context_metadata_field(0, ContextMetadata::MemWords),
+            U256::zero(),
+            "{label}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_preexisting_account() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter)?;
+    let recipient: Address = thread_rng().gen();
+    let recipient = U256::from_big_endian(recipient.as_bytes());
+
+    // The base cost, plus a cold access to the recipient, which gets created.
+    let leftover_gas = selfdestruct(&mut interpreter, recipient)?;
+    assert_eq!(leftover_gas, (GAS_LIMIT - 5_000 - 2_600 - 25_000).into());
+
+    // Since EIP-6780, only the balance moves: the account isn't scheduled for deletion.
+    assert_eq!(balance(&mut interpreter, address)?, U256::zero());
+    assert_eq!(balance(&mut interpreter, recipient)?, U256::from(1000));
+    assert!(selfdestruct_list(&interpreter).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_preexisting_account_to_itself() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter)?;
+
+    // The balance isn't burnt, since the account survives.
+    selfdestruct(&mut interpreter, address)?;
+    assert_eq!(balance(&mut interpreter, address)?, U256::from(1000));
+    assert!(selfdestruct_list(&interpreter).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_created_account() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter)?;
+    interpreter.set_memory_segment(Segment::CreatedContracts, vec![address]);
+    interpreter.set_global_metadata_field(GlobalMetadata::CreatedContractsLen, 1.into());
+    let recipient: Address = thread_rng().gen();
+    let recipient = U256::from_big_endian(recipient.as_bytes());
+
+    // An account created in the same transaction is deleted at the end of it.
+    selfdestruct(&mut interpreter, recipient)?;
+    assert_eq!(balance(&mut interpreter, address)?, U256::zero());
+    assert_eq!(balance(&mut interpreter, recipient)?, U256::from(1000));
+    assert_eq!(selfdestruct_list(&interpreter), vec![address]);
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::RefundCounter),
+        U256::zero()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_refund_before_london() -> Result<()> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    let address = prepare_context(&mut interpreter)?;
+    interpreter.set_global_metadata_field(GlobalMetadata::Fork, (Fork::Berlin as u32).into());
+
+    // Before Cancun, any account is deleted, and before London, deleting it earns a refund.
+    selfdestruct(&mut interpreter, address)?;
+    assert_eq!(selfdestruct_list(&interpreter), vec![address]);
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::RefundCounter),
+        U256::from(24_000)
+    );
+
+    Ok(())
+}
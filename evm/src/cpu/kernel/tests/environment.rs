@@ -0,0 +1,102 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+const GAS_BASE: u64 = 2;
+const GAS_BLOCKHASH: u64 = 20;
+
+/// Run the syscall handler `label`, which takes no arguments, with `field` set to `value`, and
+/// check that it pushes `value` for `GAS_BASE` gas.
+fn test_global_metadata_syscall(label: &str, field: GlobalMetadata, value: U256) -> Result<()> {
+    let mut interpreter = syscall_interpreter(label, &[]);
+    interpreter.set_global_metadata_field(field, value);
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), &[value]);
+    assert_eq!(interpreter.generation_state.registers.gas_used, GAS_BASE);
+    Ok(())
+}
+
+/// Run the syscall handler `label`, which takes no arguments, with the transaction field `field`
+/// set to `value`, and check that it pushes `value` for `GAS_BASE` gas.
+fn test_txn_field_syscall(label: &str, field: NormalizedTxnField, value: U256) -> Result<()> {
+    let mut interpreter = syscall_interpreter(label, &[]);
+    interpreter.set_txn_field(field, value);
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), &[value]);
+    assert_eq!(interpreter.generation_state.registers.gas_used, GAS_BASE);
+    Ok(())
+}
+
+#[test]
+fn test_chainid() -> Result<()> {
+    test_global_metadata_syscall("sys_chainid", GlobalMetadata::BlockChainId, 1.into())?;
+    test_global_metadata_syscall("sys_chainid", GlobalMetadata::BlockChainId, 137.into())
+}
+
+#[test]
+fn test_basefee() -> Result<()> {
+    test_global_metadata_syscall(
+        "sys_basefee",
+        GlobalMetadata::BlockBaseFee,
+        0x0a_0000_0000u64.into(),
+    )
+}
+
+#[test]
+fn test_origin() -> Result<()> {
+    let origin = U256::from_big_endian(&[0xab; 20]);
+    test_txn_field_syscall("sys_origin", NormalizedTxnField::Origin, origin)
+}
+
+#[test]
+fn test_gasprice() -> Result<()> {
+    // GASPRICE is the effective gas price, min(max_fee, base_fee + max_priority_fee), which
+    // `compute_fees` stores before the transaction runs.
+    test_txn_field_syscall(
+        "sys_gasprice",
+        NormalizedTxnField::ComputedFeePerGas,
+        0x0a_0000_0001u64.into(),
+    )
+}
+
+/// Run BLOCKHASH on `block_number` in block 1000, where the hash of block `n` is `n + 1`.
+fn blockhash(block_number: u64) -> Result<U256> {
+    let cur_block_number = 1000u64;
+    let prev_hashes = (cur_block_number - 256..cur_block_number)
+        .map(|n| (n + 1).into())
+        .collect();
+
+    let mut interpreter = syscall_interpreter("sys_blockhash", &[block_number.into()]);
+    interpreter.set_global_metadata_field(GlobalMetadata::BlockNumber, cur_block_number.into());
+    interpreter.set_memory_segment(Segment::BlockHashes, prev_hashes);
+    interpreter.run()?;
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        GAS_BLOCKHASH
+    );
+    assert_eq!(interpreter.stack().len(), 1);
+    Ok(interpreter.stack()[0])
+}
+
+#[test]
+fn test_blockhash_in_window() -> Result<()> {
+    assert_eq!(blockhash(999)?, 1000.into());
+    assert_eq!(blockhash(900)?, 901.into());
+    assert_eq!(blockhash(744)?, 745.into());
+    Ok(())
+}
+
+#[test]
+fn test_blockhash_out_of_window() -> Result<()> {
+    // The current block, and future blocks, aren't complete yet.
+    assert_eq!(blockhash(1000)?, U256::zero());
+    assert_eq!(blockhash(2000)?, U256::zero());
+    // Blocks older than 256 are no longer available.
+    assert_eq!(blockhash(743)?, U256::zero());
+    assert_eq!(blockhash(0)?, U256::zero());
+    Ok(())
+}
@@ -45,3 +45,27 @@ fn test_exp() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_exp_edge_cases() -> Result<()> {
+    let exp = KERNEL.global_labels["exp"];
+    let two = U256::from(2);
+    let retdest = 0xDEADBEEFu32.into();
+
+    // 2^0 == 1
+    let initial_stack = vec![retdest, U256::zero(), two];
+    let stack = run_interpreter(exp, initial_stack)?.stack().to_vec();
+    assert_eq!(stack, vec![U256::one()]);
+
+    // 2^255 is the top bit
+    let initial_stack = vec![retdest, U256::from(255), two];
+    let stack = run_interpreter(exp, initial_stack)?.stack().to_vec();
+    assert_eq!(stack, vec![U256::one() << 255]);
+
+    // 0^0 == 1
+    let initial_stack = vec![retdest, U256::zero(), U256::zero()];
+    let stack = run_interpreter(exp, initial_stack)?.stack().to_vec();
+    assert_eq!(stack, vec![U256::one()]);
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use ethereum_types::U256;
+use hex_literal::hex;
+use keccak_hash::keccak;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+#[test]
+fn test_keccak256_empty() -> Result<()> {
+    let mut interpreter = syscall_interpreter("sys_keccak256", &[U256::zero(), U256::zero()]);
+    interpreter.run()?;
+
+    let expected = U256::from(hex!(
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    ));
+    assert_eq!(interpreter.stack(), &[expected]);
+    assert_eq!(
+        expected,
+        U256::from_big_endian(keccak(b"").as_bytes()),
+        "EMPTY_STRING_HASH no longer matches keccak(b'')"
+    );
+    // Only the static cost is charged, and memory isn't expanded.
+    assert_eq!(interpreter.generation_state.registers.gas_used, 30);
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::MemWords),
+        U256::zero()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_keccak256_memory() -> Result<()> {
+    let memory: Vec<u8> = (0..100).map(|i| (i * 7 + 3) as u8).collect();
+
+    // The hashed region, the number of words hashed, and the number of words of memory covered.
+    for (offset, len, hashed_words, words) in
+        [(0, 2, 1, 1), (0, 32, 1, 1), (31, 2, 1, 2), (10, 90, 3, 4)]
+    {
+        let mut interpreter = syscall_interpreter("sys_keccak256", &[offset.into(), len.into()]);
+        interpreter.set_memory_segment_bytes(Segment::MainMemory, memory.clone());
+        interpreter.run()?;
+
+        let expected = U256::from_big_endian(keccak(&memory[offset..offset + len]).as_bytes());
+        assert_eq!(interpreter.stack(), &[expected]);
+        // 30 gas, plus 6 per word hashed, plus the cost of expanding memory from nothing.
+        let memory_cost = 3 * words + words * words / 512;
+        assert_eq!(
+            interpreter.generation_state.registers.gas_used,
+            30 + 6 * hashed_words + memory_cost
+        );
+        assert_eq!(
+            interpreter.get_context_metadata_field(0, ContextMetadata::MemWords),
+            words.into()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sys_keccak256_empty_at_huge_offset() -> Result<()> {
+    // A zero-length region doesn't expand memory, wherever it starts.
+    for offset in [U256::from(1u64 << 40), U256::MAX] {
+        let mut interpreter = syscall_interpreter("sys_keccak256", &[offset, U256::zero()]);
+        interpreter.run()?;
+
+        assert_eq!(
+            interpreter.stack(),
+            &[U256::from_big_endian(keccak(b"").as_bytes())]
+        );
+        // Only the static cost is charged.
+        assert_eq!(interpreter.generation_state.registers.gas_used, 30);
+        assert_eq!(
+            interpreter.get_context_metadata_field(0, ContextMetadata::MemWords),
+            U256::zero()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_keccak256_main_memory_matches_opcode() -> Result<()> {
+    let memory: Vec<u8> = (0..200).map(|i| (i * 7 + 3) as u8).collect();
+    let retdest = U256::from(0xdeadbeefu32);
+
+    // Regions within a word, across words and past the sponge's 136-byte rate, plus empty ones.
+    for (offset, len) in [(0, 0), (5, 0), (0, 32), (7, 50), (3, 136), (10, 180)] {
+        let expected = U256::from_big_endian(keccak(&memory[offset..offset + len]).as_bytes());
+
+        let mut interpreter = syscall_interpreter("sys_keccak256", &[offset.into(), len.into()]);
+        interpreter.set_memory_segment_bytes(Segment::MainMemory, memory.clone());
+        interpreter.run()?;
+        assert_eq!(interpreter.stack(), &[expected]);
+
+        // stack: offset, len, retdest
+        let initial_stack = vec![retdest, len.into(), offset.into()];
+        let mut interpreter = Interpreter::new_with_kernel(
+            KERNEL.global_labels["keccak256_main_memory"],
+            initial_stack,
+        );
+        interpreter.set_memory_segment_bytes(Segment::MainMemory, memory.clone());
+        interpreter.run()?;
+        assert_eq!(interpreter.stack(), &[expected]);
+    }
+
+    Ok(())
+}
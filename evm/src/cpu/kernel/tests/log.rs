@@ -0,0 +1,83 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+const ADDRESS: u32 = 0xabcdef;
+
+/// Run the syscall handler `label` on `args` (top of the stack first) from
+/// the context of `ADDRESS`, with main memory initialized to `memory`.
+fn run_log_syscall(label: &str, args: &[U256], memory: Vec<u8>) -> Result<Interpreter<'static>> {
+    let mut interpreter = syscall_interpreter(label, args);
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, ADDRESS.into());
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, memory);
+    interpreter.run()?;
+    Ok(interpreter)
+}
+
+#[test]
+fn test_log0_empty() -> Result<()> {
+    let interpreter = run_log_syscall("sys_log0", &[U256::zero(), U256::zero()], vec![])?;
+    assert!(interpreter.stack().is_empty());
+    assert_eq!(interpreter.generation_state.registers.gas_used, 375);
+
+    let expected_logs_data: Vec<U256> = vec![ADDRESS.into(), 0.into(), 0.into()];
+    assert_eq!(
+        interpreter.get_memory_segment(Segment::Logs),
+        vec![U256::zero()]
+    );
+    assert_eq!(
+        interpreter.get_memory_segment(Segment::LogsData),
+        expected_logs_data
+    );
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::LogsLen),
+        1.into()
+    );
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::LogsDataLen),
+        expected_logs_data.len().into()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_log4() -> Result<()> {
+    let data = vec![0x11, 0x22, 0x33, 0x44, 0x55];
+    let topics: Vec<U256> = (1..=4).map(|i| U256::from(i) << 200).collect();
+    let (offset, size) = (1, 3);
+    let mut args = vec![offset.into(), size.into()];
+    args.extend_from_slice(&topics);
+
+    let interpreter = run_log_syscall("sys_log4", &args, data.clone())?;
+    assert!(interpreter.stack().is_empty());
+    // 375 static + 8 per data byte + 375 per topic + 3 to expand memory to 1 word.
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        375 + 8 * 3 + 375 * 4 + 3
+    );
+
+    let mut expected_logs_data: Vec<U256> = vec![ADDRESS.into(), 4.into()];
+    expected_logs_data.extend_from_slice(&topics);
+    expected_logs_data.push(size.into());
+    expected_logs_data.extend(data[offset..offset + size].iter().map(|&b| U256::from(b)));
+    assert_eq!(
+        interpreter.get_memory_segment(Segment::Logs),
+        vec![U256::zero()]
+    );
+    assert_eq!(
+        interpreter.get_memory_segment(Segment::LogsData),
+        expected_logs_data
+    );
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::LogsDataLen),
+        expected_logs_data.len().into()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+/// Run MCOPY on a memory of 64 bytes `[1, 2, ..., 64]`, and check the resulting memory and gas.
+fn test_mcopy(dest_offset: usize, offset: usize, size: usize, expected_gas: u64) -> Result<()> {
+    let memory: Vec<u8> = (1..=64).collect();
+    let args = [dest_offset.into(), offset.into(), size.into()];
+    let mut interpreter = syscall_interpreter("sys_mcopy", &args);
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, memory.clone());
+    interpreter.set_context_metadata_field(0, ContextMetadata::MemWords, 2.into());
+    interpreter.run()?;
+
+    assert!(interpreter.stack().is_empty());
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        expected_gas
+    );
+
+    let expected_mem_words = if size == 0 {
+        2
+    } else {
+        ((dest_offset.max(offset) + size + 31) / 32).max(2)
+    };
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::MemWords),
+        expected_mem_words.into()
+    );
+
+    // `copy_within` has the semantics of memmove.
+    let mut expected = memory;
+    if size > 0 {
+        expected.resize(expected.len().max(dest_offset + size), 0);
+        expected.copy_within(offset..offset + size, dest_offset);
+    }
+    let mut output = interpreter.get_memory_segment_bytes(Segment::MainMemory);
+    output.resize(expected.len(), 0);
+    assert_eq!(output, expected);
+    Ok(())
+}
+
+#[test]
+fn test_mcopy_non_overlapping() -> Result<()> {
+    test_mcopy(32, 0, 16, 3 + 3)?;
+    // Copying past the end of memory also charges for the expansion from 2 to 3 words.
+    test_mcopy(64, 0, 32, 3 + 3 + 3)
+}
+
+#[test]
+fn test_mcopy_forward_overlapping() -> Result<()> {
+    test_mcopy(4, 0, 40, 3 + 3 * 2)
+}
+
+#[test]
+fn test_mcopy_backward_overlapping() -> Result<()> {
+    test_mcopy(0, 4, 40, 3 + 3 * 2)
+}
+
+#[test]
+fn test_mcopy_empty() -> Result<()> {
+    // Zero-length copies charge the base cost only, and never expand memory.
+    test_mcopy(1000, 2000, 0, 3)
+}
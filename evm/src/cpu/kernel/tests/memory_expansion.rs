@@ -0,0 +1,66 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata::MemWords;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::syscall_interpreter;
+
+const GAS_VERYLOW: u64 = 3;
+
+/// The Yellow Paper's `C_mem`, the total cost of a memory of `num_words` words.
+fn memory_cost(num_words: u64) -> u64 {
+    3 * num_words + num_words * num_words / 512
+}
+
+/// Run the memory syscall `label` on `args`, starting with `mem_words` words of active memory.
+fn run_with_mem_words(label: &str, args: &[U256], mem_words: u64) -> Result<Interpreter<'static>> {
+    let mut interpreter = syscall_interpreter(label, args);
+    interpreter.set_context_metadata_field(0, MemWords, mem_words.into());
+    interpreter.run()?;
+    Ok(interpreter)
+}
+
+#[test]
+fn test_expansion_from_zero_to_one_word() -> Result<()> {
+    let interpreter = run_with_mem_words("sys_mstore8", &[31.into(), 0xff.into()], 0)?;
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, MemWords),
+        1.into()
+    );
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        GAS_VERYLOW + memory_cost(1)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_large_expansion() -> Result<()> {
+    let offset = 100_000;
+    let interpreter = run_with_mem_words("sys_mstore", &[offset.into(), U256::MAX], 2)?;
+
+    // The word at `offset` ends at byte `offset + 32`, rounded up to a whole number of words.
+    let new_words = (offset + 32 + 31) / 32;
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, MemWords),
+        new_words.into()
+    );
+    // Only the delta from the previous size is charged. The quadratic term dominates here.
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        GAS_VERYLOW + memory_cost(new_words) - memory_cost(2)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_no_expansion() -> Result<()> {
+    // Touching memory below the current size neither shrinks it nor charges for it.
+    let interpreter = run_with_mem_words("sys_mload", &[64.into()], 10)?;
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, MemWords),
+        10.into()
+    );
+    assert_eq!(interpreter.generation_state.registers.gas_used, GAS_VERYLOW);
+    Ok(())
+}
@@ -4,24 +4,60 @@ mod bignum;
 mod blake2_f;
 mod bls381;
 mod bn254;
+mod calldata;
+mod code;
 mod core;
 mod ecc;
+mod environment;
 mod exp;
 mod hash;
+mod keccak;
+mod log;
+mod mcopy;
+mod memory_expansion;
 mod mpt;
+mod mstore8;
 mod packing;
+mod push;
+mod returndata;
 mod rlp;
 mod signed_syscalls;
 mod transaction_parsing;
+mod transient_storage;
 
 use std::str::FromStr;
 
 use anyhow::Result;
 use ethereum_types::U256;
 
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+
 pub(crate) fn u256ify<'a>(hexes: impl IntoIterator<Item = &'a str>) -> Result<Vec<U256>> {
     Ok(hexes
         .into_iter()
         .map(U256::from_str)
         .collect::<Result<Vec<_>, _>>()?)
 }
+
+/// kexit_info returning in kernel mode to 0xdeadbeef with no gas used so far, so that the
+/// interpreter halts but can keep running kernel code without enforcing the JUMPDEST rule.
+pub(crate) fn kernel_kexit_info() -> U256 {
+    U256::from(0xdeadbeefu32) + (U256::one() << 32)
+}
+
+/// Returns an interpreter about to run the syscall handler `label` on `args`
+/// (top of the stack first), as if trapped from user code that should resume
+/// at 0xdeadbeef. The current context is given a generous gas limit.
+pub(crate) fn syscall_interpreter(label: &str, args: &[U256]) -> Interpreter<'static> {
+    // kexit_info for user mode at pc 0xdeadbeef with no gas used so far.
+    let kexit_info = U256::from(0xdeadbeefu32);
+    let mut initial_stack = vec![kexit_info];
+    initial_stack.extend_from_slice(args);
+    initial_stack.reverse();
+
+    let mut interpreter = Interpreter::new_with_kernel(KERNEL.global_labels[label], initial_stack);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 1_000_000.into());
+    interpreter
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+/// Run the memory syscall `label` on `args`, with two words of active memory holding `memory`.
+/// Returns the resulting memory, stack and gas used.
+fn run_on_memory(label: &str, args: &[U256], memory: Vec<u8>) -> Result<(Vec<u8>, Vec<U256>, u64)> {
+    let mut interpreter = syscall_interpreter(label, args);
+    interpreter.set_memory_segment_bytes(Segment::MainMemory, memory);
+    interpreter.set_context_metadata_field(0, ContextMetadata::MemWords, 2.into());
+    interpreter.run()?;
+    Ok((
+        interpreter.get_memory_segment_bytes(Segment::MainMemory),
+        interpreter.stack().to_vec(),
+        interpreter.generation_state.registers.gas_used,
+    ))
+}
+
+#[test]
+fn test_mstore8_then_mload() -> Result<()> {
+    let memory = vec![0xaa; 64];
+
+    // MSTORE8(31, 0x1234) only writes the low byte of the value.
+    let (memory, stack, gas_used) =
+        run_on_memory("sys_mstore8", &[31.into(), 0x1234.into()], memory)?;
+    assert!(stack.is_empty());
+    assert_eq!(gas_used, 3);
+    let mut expected = vec![0xaa; 64];
+    expected[31] = 0x34;
+    assert_eq!(memory, expected);
+
+    // Memory is big-endian, so byte 31 is the least significant byte of the word at offset 0.
+    let (_, stack, _) = run_on_memory("sys_mload", &[0.into()], memory)?;
+    assert_eq!(stack, vec![U256::from_big_endian(&expected[..32])]);
+    assert_eq!(stack[0].low_u32() & 0xffff, 0xaa34);
+
+    Ok(())
+}
+
+#[test]
+fn test_mstore_then_unaligned_mload() -> Result<()> {
+    let memory: Vec<u8> = (0..64).collect();
+    let value_bytes: Vec<u8> = (0x80..0xa0).collect();
+    let value = U256::from_big_endian(&value_bytes);
+
+    let (memory, stack, _) = run_on_memory("sys_mstore", &[0.into(), value], memory)?;
+    assert!(stack.is_empty());
+    assert_eq!(memory[..32], value_bytes[..]);
+    assert_eq!(memory[32..], (32..64).collect::<Vec<u8>>()[..]);
+
+    // MLOAD(16) straddles both words: the tail of the stored word, then the head of the next.
+    let (_, stack, gas_used) = run_on_memory("sys_mload", &[16.into()], memory)?;
+    assert_eq!(gas_used, 3);
+    let mut loaded = [0; 32];
+    stack[0].to_big_endian(&mut loaded);
+    assert_eq!(loaded[..16], value_bytes[16..]);
+    assert_eq!(loaded[16..], (32..48).collect::<Vec<u8>>()[..]);
+
+    Ok(())
+}
+
+#[test]
+fn test_unaligned_mstore() -> Result<()> {
+    let memory: Vec<u8> = (0..64).collect();
+    let value_bytes: Vec<u8> = (0x80..0xa0).collect();
+    let value = U256::from_big_endian(&value_bytes);
+
+    // MSTORE(16, value) overwrites the tail of the first word and the head of the second.
+    let (memory, _, _) = run_on_memory("sys_mstore", &[16.into(), value], memory)?;
+    let mut expected: Vec<u8> = (0..64).collect();
+    expected[16..48].copy_from_slice(&value_bytes);
+    assert_eq!(memory, expected);
+
+    // The aligned word at offset 0 now ends with the head of the stored value.
+    let (_, stack, _) = run_on_memory("sys_mload", &[0.into()], memory)?;
+    assert_eq!(stack, vec![U256::from_big_endian(&expected[..32])]);
+    assert_eq!(
+        stack[0].low_u128(),
+        U256::from_big_endian(&value_bytes[..16]).low_u128()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::interpreter::run;
+use crate::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+
+#[test]
+fn test_push0() -> Result<()> {
+    // PUSH0, PC, PUSH1 0x2a, PUSH0, STOP
+    let code = [
+        get_push_opcode(0),
+        get_opcode("GETPC"),
+        get_push_opcode(1),
+        0x2a,
+        get_push_opcode(0),
+        get_opcode("STOP"),
+    ];
+    let prover_inputs = HashMap::new();
+    let interpreter = run(&code, 0, vec![], &prover_inputs)?;
+
+    // PUSH0 consumes no immediate, so the PC opcode is at offset 1.
+    let expected_stack: Vec<U256> = vec![0.into(), 1.into(), 0x2a.into(), 0.into()];
+    assert_eq!(interpreter.stack(), &expected_stack);
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        code.len()
+    );
+    Ok(())
+}
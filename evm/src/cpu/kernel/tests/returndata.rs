@@ -0,0 +1,119 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::syscall_interpreter;
+use crate::memory::segments::Segment;
+
+const RETURNDATA_LEN: usize = 40;
+
+fn returndata() -> Vec<u8> {
+    (1..=RETURNDATA_LEN as u8).collect()
+}
+
+/// Run the syscall handler `label` on `args` (top of the stack first) with
+/// return data `[1, 2, ..., RETURNDATA_LEN]`. Execution also halts on
+/// reaching `fault_exception`.
+fn run_returndata_syscall(label: &str, args: &[U256]) -> Result<Interpreter<'static>> {
+    let mut interpreter = syscall_interpreter(label, args);
+    interpreter.set_memory_segment_bytes(Segment::Returndata, returndata());
+    interpreter.set_context_metadata_field(
+        0,
+        ContextMetadata::ReturndataSize,
+        RETURNDATA_LEN.into(),
+    );
+    interpreter
+        .halt_offsets
+        .push(KERNEL.global_labels["fault_exception"]);
+    interpreter.run()?;
+    Ok(interpreter)
+}
+
+#[test]
+fn test_returndatasize() -> Result<()> {
+    let interpreter = run_returndata_syscall("sys_returndatasize", &[])?;
+    assert_eq!(interpreter.stack(), &[RETURNDATA_LEN.into()]);
+    assert_eq!(interpreter.generation_state.registers.gas_used, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_returndatacopy_in_bounds() -> Result<()> {
+    // Copy the last 8 bytes of return data to memory offset 4.
+    let (dest_offset, offset, size) = (4, RETURNDATA_LEN - 8, 8);
+    let interpreter = run_returndata_syscall(
+        "sys_returndatacopy",
+        &[dest_offset.into(), offset.into(), size.into()],
+    )?;
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        0xdeadbeef
+    );
+    assert!(interpreter.stack().is_empty());
+
+    let memory = interpreter.get_memory_segment_bytes(Segment::MainMemory);
+    let mut expected = vec![0; dest_offset];
+    expected.extend_from_slice(&returndata()[offset..]);
+    assert_eq!(memory, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_returndatacopy_out_of_bounds() -> Result<()> {
+    let fault_exception = KERNEL.global_labels["fault_exception"];
+
+    // Unlike CALLDATACOPY, reading past the end of the return data faults
+    // rather than zero-padding, even by a single byte.
+    for (offset, size) in [
+        (RETURNDATA_LEN - 8, 9),
+        (RETURNDATA_LEN, 1),
+        (RETURNDATA_LEN + 1, 0),
+    ] {
+        let interpreter = run_returndata_syscall(
+            "sys_returndatacopy",
+            &[U256::zero(), offset.into(), size.into()],
+        )?;
+        assert_eq!(
+            interpreter.generation_state.registers.program_counter, fault_exception,
+            "offset {offset}, size {size}"
+        );
+    }
+
+    // offset + size overflowing 256 bits also faults.
+    let interpreter = run_returndata_syscall(
+        "sys_returndatacopy",
+        &[U256::zero(), U256::MAX, U256::one()],
+    )?;
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        fault_exception
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_returndatacopy_empty_at_large_offset() -> Result<()> {
+    // An empty copy doesn't expand memory, however far away its destination is.
+    let dest_offset = U256::one() << 32;
+    let interpreter = run_returndata_syscall(
+        "sys_returndatacopy",
+        &[dest_offset, RETURNDATA_LEN.into(), U256::zero()],
+    )?;
+    assert_eq!(
+        interpreter.generation_state.registers.program_counter,
+        0xdeadbeef
+    );
+    assert!(interpreter.stack().is_empty());
+    assert_eq!(interpreter.generation_state.registers.gas_used, 3);
+    assert_eq!(
+        interpreter.get_context_metadata_field(0, ContextMetadata::MemWords),
+        U256::zero()
+    );
+
+    Ok(())
+}
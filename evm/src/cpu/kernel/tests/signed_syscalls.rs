@@ -1,7 +1,7 @@
 use ethereum_types::U256;
 
 use crate::cpu::kernel::aggregator::KERNEL;
-use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::interpreter::{run, Interpreter};
 
 /// Generate a list of inputs suitable for testing the signed operations
 ///
@@ -130,6 +130,25 @@ fn run_test(fn_label: &str, expected_fn: fn(U256, U256) -> U256, opname: &str) {
     }
 }
 
+/// Run the kernel routine `fn_label` on each `(x, y)` pair of `cases`
+/// and check the output against the given expected value.
+fn run_cases(fn_label: &str, cases: &[(U256, U256, U256)], opname: &str) {
+    let fn_label = KERNEL.global_labels[fn_label];
+    let retdest = U256::from(0xDEADBEEFu32);
+
+    for &(x, y, expected_output) in cases {
+        let stack = vec![retdest, y, x];
+        let mut interpreter = Interpreter::new_with_kernel(fn_label, stack);
+        interpreter.run().unwrap();
+        assert_eq!(
+            interpreter.stack(),
+            &[expected_output],
+            "{opname}({x}, {y}): expected {expected_output} but got {:?}",
+            interpreter.stack()
+        );
+    }
+}
+
 #[test]
 fn test_sdiv() {
     // Double-check that the expected output calculation is correct in the special case.
@@ -140,6 +159,49 @@ fn test_sdiv() {
     run_test("_sys_sdiv", u256_sdiv, "SDIV");
 }
 
+/// Run the interpreter's native implementation of the binary opcode
+/// `opcode` on a few hand-picked signed inputs.
+fn run_opcode_test(opcode: u8, expected_fn: fn(U256, U256) -> U256, opname: &str) {
+    let minus = |x: u32| U256::from(x).overflowing_neg().0;
+    let int_min = U256::one() << 255;
+    let cases = [
+        (minus(1), minus(1)),
+        (int_min, minus(1)),
+        (int_min, U256::one()),
+        (minus(7), U256::from(2)),
+        (U256::from(7), minus(2)),
+        (minus(7), minus(2)),
+        (U256::one(), U256::from(2)),
+        (minus(1), U256::from(2)),
+        (minus(5), U256::zero()),
+    ];
+    // OPCODE, PUSH4 deadbeef, JUMP
+    let code = [opcode, 0x63, 0xde, 0xad, 0xbe, 0xef, 0x56];
+
+    for (x, y) in cases {
+        let stack = run(&code, 0, vec![y, x], &KERNEL.prover_inputs)
+            .unwrap()
+            .stack()
+            .to_vec();
+        let expected_output = expected_fn(x, y);
+        assert_eq!(
+            stack,
+            vec![expected_output],
+            "{opname}({x}, {y}): expected {expected_output} but got {stack:?}"
+        );
+    }
+}
+
+#[test]
+fn test_sdiv_opcode() {
+    run_opcode_test(0x05, u256_sdiv, "SDIV");
+}
+
+#[test]
+fn test_smod_opcode() {
+    run_opcode_test(0x07, u256_smod, "SMOD");
+}
+
 #[test]
 fn test_smod() {
     run_test("_sys_smod", u256_smod, "SMOD");
@@ -150,11 +212,66 @@ fn test_signextend() {
     run_test("_sys_signextend", u256_signextend, "SIGNEXTEND");
 }
 
+#[test]
+fn test_signextend_edge_cases() {
+    let minus_one = U256::MAX;
+    let cases = [
+        // byte_index = 0 with the sign bit clear leaves the low byte alone.
+        (U256::zero(), U256::from(0x7f), U256::from(0x7f)),
+        // byte_index = 0 with the sign bit set fills every higher byte.
+        (
+            U256::zero(),
+            U256::from(0x80),
+            minus_one << 8 | U256::from(0x80),
+        ),
+        // Higher bytes are overwritten, not just filled.
+        (U256::zero(), U256::from(0x1234_ff), minus_one),
+        (
+            U256::zero(),
+            minus_one << 8 | U256::from(0x7f),
+            U256::from(0x7f),
+        ),
+        // byte_index >= 31 is a passthrough.
+        (U256::from(31), U256::from(0x80), U256::from(0x80)),
+        (U256::from(31), minus_one, minus_one),
+        (U256::from(32), U256::from(0x80), U256::from(0x80)),
+        (minus_one, U256::from(0x80), U256::from(0x80)),
+    ];
+    run_cases("_sys_signextend", &cases, "SIGNEXTEND");
+}
+
 #[test]
 fn test_sar() {
     run_test("_sys_sar", u256_sar, "SAR");
 }
 
+#[test]
+fn test_sar_edge_cases() {
+    let minus_one = U256::MAX;
+    let int_min = U256_TOP_BIT;
+    let positive = U256::from(0x1234_5678u32) << 200;
+    let cases = [
+        // -1 stays -1 however far it is shifted.
+        (U256::one(), minus_one, minus_one),
+        (U256::from(255), minus_one, minus_one),
+        (U256::from(256), minus_one, minus_one),
+        // INT_MIN fills the vacated high bits with ones.
+        (U256::one(), int_min, int_min | int_min >> 1),
+        (U256::from(255), int_min, minus_one),
+        (U256::from(256), int_min, minus_one),
+        // Positive values shift in zeros.
+        (U256::one(), positive, positive >> 1),
+        (U256::from(255), positive, U256::zero()),
+        (U256::from(256), positive, U256::zero()),
+        // Shifts far beyond 255 saturate according to the sign bit.
+        (int_min, int_min, minus_one),
+        (int_min, positive, U256::zero()),
+        (minus_one, int_min, minus_one),
+        (minus_one, positive, U256::zero()),
+    ];
+    run_cases("_sys_sar", &cases, "SAR");
+}
+
 #[test]
 fn test_slt() {
     run_test("_sys_slt", u256_slt, "SLT");
@@ -164,3 +281,30 @@ fn test_slt() {
 fn test_sgt() {
     run_test("_sys_sgt", u256_sgt, "SGT");
 }
+
+#[test]
+fn test_slt_sgt_edge_cases() {
+    let minus_one = U256::MAX;
+    let int_min = U256_TOP_BIT;
+    let int_max = U256_TOP_BIT - 1;
+    let (zero, one) = (U256::zero(), U256::one());
+    // Unsigned comparisons would give the opposite results whenever exactly one side is negative.
+    let slt_cases = [
+        (minus_one, one, one),
+        (one, minus_one, zero),
+        (minus_one, zero, one),
+        (int_min, int_max, one),
+        (int_max, int_min, zero),
+        (minus_one, minus_one, zero),
+    ];
+    run_cases("_sys_slt", &slt_cases, "SLT");
+    let sgt_cases = [
+        (minus_one, one, zero),
+        (one, minus_one, one),
+        (zero, minus_one, one),
+        (int_min, int_max, zero),
+        (int_max, int_min, one),
+        (minus_one, minus_one, zero),
+    ];
+    run_cases("_sys_sgt", &sgt_cases, "SGT");
+}
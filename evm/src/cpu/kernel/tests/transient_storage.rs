@@ -0,0 +1,81 @@
+use anyhow::Result;
+use ethereum_types::U256;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::cpu::kernel::tests::kernel_kexit_info;
+
+const GAS_WARMACCESS: u64 = 100;
+
+fn prepare_interpreter(address: U256) -> Interpreter<'static> {
+    let mut interpreter = Interpreter::new_with_kernel(0, vec![]);
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, address);
+    interpreter.set_context_metadata_field(0, ContextMetadata::GasLimit, 1_000_000.into());
+    interpreter
+}
+
+/// Run the syscall handler `label` on `args` (top of the stack first), and check the gas charged.
+fn run_syscall(interpreter: &mut Interpreter, label: &str, args: &[U256]) -> Result<()> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels[label];
+    for &arg in args.iter().rev() {
+        interpreter.push(arg);
+    }
+    interpreter.push(kernel_kexit_info());
+    interpreter.run()?;
+    assert_eq!(
+        interpreter.generation_state.registers.gas_used,
+        GAS_WARMACCESS
+    );
+    Ok(())
+}
+
+fn tstore(interpreter: &mut Interpreter, key: U256, value: U256) -> Result<()> {
+    run_syscall(interpreter, "sys_tstore", &[key, value])?;
+    assert!(interpreter.stack().is_empty());
+    Ok(())
+}
+
+fn tload(interpreter: &mut Interpreter, key: U256) -> Result<U256> {
+    run_syscall(interpreter, "sys_tload", &[key])?;
+    assert_eq!(interpreter.stack().len(), 1);
+    Ok(interpreter.pop())
+}
+
+#[test]
+fn test_tstore_tload() -> Result<()> {
+    let address = 0xabcd.into();
+    let mut interpreter = prepare_interpreter(address);
+
+    assert_eq!(tload(&mut interpreter, 1.into())?, U256::zero());
+    tstore(&mut interpreter, 1.into(), 0x1234.into())?;
+    tstore(&mut interpreter, 2.into(), 0x5678.into())?;
+    assert_eq!(tload(&mut interpreter, 1.into())?, 0x1234.into());
+    assert_eq!(tload(&mut interpreter, 2.into())?, 0x5678.into());
+
+    // Overwrite an existing key.
+    tstore(&mut interpreter, 1.into(), 0x9abc.into())?;
+    assert_eq!(tload(&mut interpreter, 1.into())?, 0x9abc.into());
+    assert_eq!(tload(&mut interpreter, 2.into())?, 0x5678.into());
+
+    // Transient storage is keyed by address as well as key.
+    interpreter.set_context_metadata_field(0, ContextMetadata::Address, 0xef01.into());
+    assert_eq!(tload(&mut interpreter, 1.into())?, U256::zero());
+
+    Ok(())
+}
+
+#[test]
+fn test_transient_storage_cleared_between_txns() -> Result<()> {
+    let mut interpreter = prepare_interpreter(0xabcd.into());
+    tstore(&mut interpreter, 1.into(), 0x1234.into())?;
+
+    // This is what `process_normalized_txn` does at the start of each transaction.
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["clear_transient_storage"];
+    interpreter.push(0xdeadbeefu32.into());
+    interpreter.run()?;
+
+    assert_eq!(tload(&mut interpreter, 1.into())?, U256::zero());
+    Ok(())
+}
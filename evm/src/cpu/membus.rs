@@ -1,3 +1,6 @@
+use std::borrow::Borrow;
+
+use ethereum_types::U256;
 use plonky2::field::extension::Extendable;
 use plonky2::field::packed::PackedField;
 use plonky2::field::types::PrimeField64;
@@ -5,7 +8,8 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
-use crate::cpu::columns::CpuColumnsView;
+use crate::cpu::columns::{CpuColumnsView, NUM_CPU_COLUMNS};
+use crate::memory::segments::Segment;
 
 /// General-purpose memory channels; they can read and write to all contexts/segments/addresses.
 pub const NUM_GP_CHANNELS: usize = 5;
@@ -32,6 +36,80 @@ pub mod channel_indices {
 /// These limitations save us numerous columns in the CPU table.
 pub const NUM_CHANNELS: usize = channel_indices::GP.end;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MemoryOpKind {
+    Read,
+    Write,
+}
+
+/// A memory operation that the CPU emits on the memory bus, as the memory STARK sees it.
+///
+/// The operation in channel `i` of the row at clock `c` has timestamp `c * NUM_CHANNELS + i`,
+/// where the indices are those of `channel_indices`. Timestamps therefore strictly increase
+/// through the trace, and order the operations of a single row by channel.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemoryOp {
+    /// The `(context, segment, virtual)` address.
+    pub address: (usize, usize, usize),
+    pub value: U256,
+    pub timestamp: usize,
+    pub kind: MemoryOpKind,
+}
+
+/// Extracts the memory operations emitted by the rows of a CPU trace, in timestamp order.
+pub fn memory_ops<F: PrimeField64>(trace: &[[F; NUM_CPU_COLUMNS]]) -> Vec<MemoryOp> {
+    let to_usize = |x: F| x.to_canonical_u64() as usize;
+    let mut ops = vec![];
+    for row in trace {
+        let row: &CpuColumnsView<F> = row.borrow();
+        let clock = to_usize(row.clock);
+
+        // The code channel reads the opcode of every CPU cycle.
+        if row.is_cpu_cycle == F::ONE {
+            let opcode = row
+                .opcode_bits
+                .iter()
+                .rev()
+                .fold(0, |acc, bit| (acc << 1) | bit.to_canonical_u64());
+            ops.push(MemoryOp {
+                address: (
+                    to_usize(row.code_context),
+                    Segment::Code as usize,
+                    to_usize(row.program_counter),
+                ),
+                value: opcode.into(),
+                timestamp: clock * NUM_CHANNELS + channel_indices::CODE,
+                kind: MemoryOpKind::Read,
+            });
+        }
+
+        for (i, channel) in row.mem_channels.iter().enumerate() {
+            if channel.used != F::ONE {
+                continue;
+            }
+            let value = channel.value.iter().rev().fold(U256::zero(), |acc, limb| {
+                (acc << 32) | U256::from(limb.to_canonical_u64())
+            });
+            let kind = if channel.is_read == F::ONE {
+                MemoryOpKind::Read
+            } else {
+                MemoryOpKind::Write
+            };
+            ops.push(MemoryOp {
+                address: (
+                    to_usize(channel.addr_context),
+                    to_usize(channel.addr_segment),
+                    to_usize(channel.addr_virtual),
+                ),
+                value,
+                timestamp: clock * NUM_CHANNELS + channel_indices::GP.start + i,
+                kind,
+            });
+        }
+    }
+    ops
+}
+
 /// Calculates `lv.stack_len_bounds_aux`. Note that this must be run after decode.
 pub fn generate<F: PrimeField64>(lv: &mut CpuColumnsView<F>) {
     let cycle_filter = lv.is_cpu_cycle;
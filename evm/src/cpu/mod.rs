@@ -1,12 +1,12 @@
 pub(crate) mod bootstrap_kernel;
-pub(crate) mod columns;
+pub mod columns;
 mod contextops;
 pub(crate) mod control_flow;
 pub mod cpu_stark;
 pub(crate) mod decode;
 mod dup_swap;
 mod exceptions;
-mod gas;
+pub mod gas;
 mod jumps;
 pub mod kernel;
 pub(crate) mod membus;
@@ -14,7 +14,11 @@ mod memio;
 mod modfp254;
 mod pc;
 mod push0;
+#[cfg(test)]
+mod reference;
 mod shift;
+#[cfg(test)]
+pub(crate) mod signed;
 pub(crate) mod simple_logic;
 mod stack;
 pub(crate) mod stack_bounds;
@@ -24,6 +24,9 @@ pub fn eval_packed<P: PackedField>(
     // We want to use all the same logic as the usual mod operations, but without needing to read
     // the modulus from the stack. We simply constrain `mem_channels[2]` to be our prime (that's
     // where the modulus goes in the generalized operations).
+    // The operands don't need to be reduced: like any modular operation, the arithmetic table
+    // constrains the output to be the canonical representative, i.e. to be less than the prime.
+    // The kernel relies on this, e.g. `bn_check` evaluates the curve equation on unchecked inputs.
     let channel_val = lv.mem_channels[2].value;
     for (channel_limb, p_limb) in izip!(channel_val, P_LIMBS) {
         let p_limb = P::Scalar::from_canonical_u32(p_limb);
@@ -13,6 +13,8 @@ pub fn eval_packed<P: PackedField>(
 ) {
     let filter = lv.op.pc;
     let push_value = lv.mem_channels[NUM_GP_CHANNELS - 1].value;
+    // `lv.program_counter` is the offset of the `PC` instruction itself; the increment is only
+    // visible in the next row.
     yield_constr.constraint(filter * (push_value[0] - lv.program_counter));
     for &limb in &push_value[1..] {
         yield_constr.constraint(filter * limb);
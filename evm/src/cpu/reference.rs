@@ -0,0 +1,376 @@
+//! A property test checking the semantics of user code in the CPU against a minimal reference
+//! EVM.
+//!
+//! Random bytecode runs both through `run_user_code` and through the reference interpreter below,
+//! until control passes to the kernel, i.e. at the first syscall or exception. The two must then
+//! agree on the halting instruction, and on the stack and gas used right before it. Natively
+//! executed instructions never touch memory, which is only accessed by syscalls, so there is no
+//! memory to compare.
+//!
+//! Exceptions aren't told apart by kind: the CPU charges gas before noticing that a push
+//! overflows the stack, while the EVM checks the stack first, but all exceptional halts behave
+//! the same by consuming all the gas of the context.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use ethereum_types::{U256, U512};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::{Field, PrimeField64};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::cpu::cpu_stark::{run_user_code, CpuInputs, USER_CONTEXT};
+use crate::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+use crate::cpu::stack_bounds::MAX_USER_STACK_SIZE;
+use crate::memory::segments::Segment;
+use crate::witness::memory::MemoryAddress;
+
+type F = GoldilocksField;
+
+/// The instruction at which control passes to the kernel.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Halt {
+    /// A syscall, including `STOP`, at the given offset.
+    Syscall(usize),
+    /// An exceptional halt at the given offset.
+    Exception(usize),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Outcome {
+    halt: Halt,
+    /// The stack right before the halting instruction, from bottom to top.
+    stack: Vec<U256>,
+    /// The gas used right before the halting instruction.
+    gas_used: u64,
+}
+
+/// The number of stack items that `opcode` pops and pushes, or `None` for invalid opcodes. The
+/// blob opcodes of Cancun aren't supported, and count as invalid.
+fn stack_io(opcode: u8) -> Option<(usize, usize)> {
+    Some(match opcode {
+        0x00 | 0x5b => (0, 0),
+        0x01..=0x07 | 0x0a | 0x0b | 0x10..=0x14 | 0x16..=0x18 | 0x1a..=0x1d | 0x20 => (2, 1),
+        0x08 | 0x09 => (3, 1),
+        0x15 | 0x19 | 0x31 | 0x35 | 0x3b | 0x3f | 0x40 | 0x51 | 0x54 | 0x5c => (1, 1),
+        0x30 | 0x32..=0x34 | 0x36 | 0x38 | 0x3a | 0x3d | 0x41..=0x48 | 0x58..=0x5a => (0, 1),
+        0x5f..=0x7f => (0, 1),
+        0x37 | 0x39 | 0x3e | 0x5e => (3, 0),
+        0x3c => (4, 0),
+        0x50 | 0x56 | 0xff => (1, 0),
+        0x52 | 0x53 | 0x55 | 0x57 | 0x5d | 0xf3 | 0xfd => (2, 0),
+        0x80..=0x8f => {
+            let n = (opcode - 0x7f) as usize;
+            (n, n + 1)
+        }
+        0x90..=0x9f => {
+            let n = (opcode - 0x8f) as usize;
+            (n + 1, n + 1)
+        }
+        0xa0..=0xa4 => ((opcode - 0xa0) as usize + 2, 0),
+        0xf0 => (3, 1),
+        0xf1 | 0xf2 => (7, 1),
+        0xf4 | 0xfa => (6, 1),
+        0xf5 => (4, 1),
+        _ => return None,
+    })
+}
+
+/// The gas cost of the opcodes that the CPU executes natively, or `None` for the opcodes that are
+/// implemented by the kernel.
+fn native_gas(opcode: u8) -> Option<u64> {
+    Some(match opcode {
+        0x5b => 1,
+        0x50 | 0x58 | 0x5f => 2,
+        0x01 | 0x03 | 0x10 | 0x11 | 0x14..=0x1c | 0x60..=0x9f => 3,
+        0x02 | 0x04 | 0x06 => 5,
+        0x08 | 0x09 | 0x56 => 8,
+        0x57 => 10,
+        _ => return None,
+    })
+}
+
+/// Marks the offsets of `code` holding a `JUMPDEST`, rather than `PUSH` data.
+fn jumpdests(code: &[u8]) -> Vec<bool> {
+    let mut jumpdests = vec![false; code.len()];
+    let mut pc = 0;
+    while pc < code.len() {
+        match code[pc] {
+            0x5b => jumpdests[pc] = true,
+            opcode @ 0x60..=0x7f => pc += (opcode - 0x5f) as usize,
+            _ => (),
+        }
+        pc += 1;
+    }
+    jumpdests
+}
+
+/// `x mod n`, where anything modulo zero is zero.
+fn modulo(x: U512, n: U256) -> U256 {
+    if n.is_zero() {
+        U256::zero()
+    } else {
+        U256::try_from(x % n).unwrap()
+    }
+}
+
+/// Executes the native instruction at `pc`, and returns the offset of the next instruction, or
+/// `None` if it jumps to an invalid destination. The stack must hold enough items.
+fn step(code: &[u8], jumpdests: &[bool], pc: usize, stack: &mut Vec<U256>) -> Option<usize> {
+    let opcode = code[pc];
+    let is_jumpdest = |dst: U256| dst < U256::from(jumpdests.len()) && jumpdests[dst.as_usize()];
+    let (num_pops, _) = stack_io(opcode).unwrap();
+    // The popped items, top of the stack first.
+    let mut args = stack.split_off(stack.len() - num_pops);
+    args.reverse();
+
+    let bool_word = |b: bool| U256::from(b as u8);
+    // The pushed items, top of the stack first.
+    let results = match opcode {
+        0x01 => vec![args[0].overflowing_add(args[1]).0],
+        0x02 => vec![args[0].overflowing_mul(args[1]).0],
+        0x03 => vec![args[0].overflowing_sub(args[1]).0],
+        0x04 => vec![args[0].checked_div(args[1]).unwrap_or_default()],
+        0x06 => vec![args[0].checked_rem(args[1]).unwrap_or_default()],
+        0x08 => vec![modulo(U512::from(args[0]) + U512::from(args[1]), args[2])],
+        0x09 => vec![modulo(args[0].full_mul(args[1]), args[2])],
+        0x10 => vec![bool_word(args[0] < args[1])],
+        0x11 => vec![bool_word(args[0] > args[1])],
+        0x14 => vec![bool_word(args[0] == args[1])],
+        0x15 => vec![bool_word(args[0].is_zero())],
+        0x16 => vec![args[0] & args[1]],
+        0x17 => vec![args[0] | args[1]],
+        0x18 => vec![args[0] ^ args[1]],
+        0x19 => vec![!args[0]],
+        0x1a if args[0] < U256::from(32) => vec![args[1].byte(31 - args[0].as_usize()).into()],
+        0x1b if args[0] < U256::from(256) => vec![args[1] << args[0].as_usize()],
+        0x1c if args[0] < U256::from(256) => vec![args[1] >> args[0].as_usize()],
+        0x1a..=0x1c => vec![U256::zero()],
+        0x50 | 0x5b => vec![],
+        0x56 => {
+            if !is_jumpdest(args[0]) {
+                return None;
+            }
+            return Some(args[0].as_usize());
+        }
+        0x57 => {
+            if args[1].is_zero() {
+                return Some(pc + 1);
+            }
+            if !is_jumpdest(args[0]) {
+                return None;
+            }
+            return Some(args[0].as_usize());
+        }
+        0x58 => vec![pc.into()],
+        0x5f..=0x7f => {
+            let n = (opcode - 0x5f) as usize;
+            let data: Vec<u8> = (1..=n)
+                .map(|i| code.get(pc + i).copied().unwrap_or(0))
+                .collect();
+            stack.push(U256::from_big_endian(&data));
+            return Some(pc + 1 + n);
+        }
+        0x80..=0x8f => {
+            let mut results = vec![args[num_pops - 1]];
+            results.extend(&args);
+            results
+        }
+        0x90..=0x9f => {
+            args.swap(0, num_pops - 1);
+            args
+        }
+        _ => unreachable!("{opcode:#04x} isn't executed natively"),
+    };
+    stack.extend(results.into_iter().rev());
+    Some(pc + 1)
+}
+
+/// Runs `code` in the reference interpreter, on an empty stack and with the given gas limit.
+fn run_reference(code: &[u8], gas_limit: u64) -> Outcome {
+    let jumpdests = jumpdests(code);
+    let mut stack = vec![];
+    let mut gas_used = 0;
+    let mut pc = 0;
+    loop {
+        // Running past the end of the code executes `STOP`s.
+        let opcode = code.get(pc).copied().unwrap_or(0);
+        let pre_state = (stack.clone(), gas_used);
+        let halt = match stack_io(opcode) {
+            None => Some(Halt::Exception(pc)),
+            Some((pops, _)) if stack.len() < pops => Some(Halt::Exception(pc)),
+            Some((pops, pushes)) if stack.len() - pops + pushes > MAX_USER_STACK_SIZE => {
+                Some(Halt::Exception(pc))
+            }
+            _ => match native_gas(opcode) {
+                None => Some(Halt::Syscall(pc)),
+                Some(gas) if gas_used + gas > gas_limit => Some(Halt::Exception(pc)),
+                Some(gas) => {
+                    gas_used += gas;
+                    match step(code, &jumpdests, pc, &mut stack) {
+                        Some(next_pc) => {
+                            pc = next_pc;
+                            None
+                        }
+                        None => Some(Halt::Exception(pc)),
+                    }
+                }
+            },
+        };
+        if let Some(halt) = halt {
+            let (stack, gas_used) = pre_state;
+            return Outcome {
+                halt,
+                stack,
+                gas_used,
+            };
+        }
+    }
+}
+
+/// Runs `code` through the CPU's trace generation, on an empty stack and with the given gas
+/// limit.
+fn run_cpu(code: &[u8], gas_limit: u64) -> anyhow::Result<Outcome> {
+    let inputs = CpuInputs {
+        initial_stack: vec![],
+        gas_limit: Some(gas_limit),
+    };
+    let state = run_user_code::<F>(code, inputs)?;
+    let last = state.traces.cpu.last().unwrap();
+
+    let pc = last.program_counter.to_canonical_u64() as usize;
+    let halt = if last.op.exception == F::ONE {
+        Halt::Exception(pc)
+    } else {
+        anyhow::ensure!(last.op.syscall == F::ONE, "The last row traps neither way");
+        Halt::Syscall(pc)
+    };
+    let stack = (0..last.stack_len.to_canonical_u64() as usize)
+        .map(|i| {
+            state
+                .memory
+                .get(MemoryAddress::new(USER_CONTEXT, Segment::Stack, i))
+        })
+        .collect();
+    Ok(Outcome {
+        halt,
+        stack,
+        gas_used: last.gas.to_canonical_u64(),
+    })
+}
+
+/// Whether the CPU and the reference interpreter disagree on `code`. A panic counts as a
+/// disagreement, so that it can be shrunk like any other.
+fn diverges(code: &[u8], gas_limit: u64) -> bool {
+    let cpu = catch_unwind(AssertUnwindSafe(|| run_cpu(code, gas_limit)));
+    !matches!(cpu, Ok(Ok(outcome)) if outcome == run_reference(code, gas_limit))
+}
+
+/// Returns random bytecode. It is biased towards natively executed instructions and small `PUSH`
+/// immediates, so that jumps often land on valid destinations and shifts on small amounts.
+fn random_code<R: Rng>(rng: &mut R) -> Vec<u8> {
+    let native_opcodes: Vec<u8> = (0..=u8::MAX)
+        .filter(|&opcode| native_gas(opcode).is_some() && !(0x5f..=0x7f).contains(&opcode))
+        .collect();
+    let mut code = vec![];
+    for _ in 0..rng.gen_range(1..=32) {
+        match rng.gen_range(0..10) {
+            0..=3 => {
+                let n = if rng.gen_bool(0.75) {
+                    rng.gen_range(0..=2)
+                } else {
+                    rng.gen_range(3..=32)
+                };
+                code.push(get_push_opcode(n));
+                if n == 1 {
+                    code.push(rng.gen_range(0..64));
+                } else {
+                    code.extend((0..n).map(|_| rng.gen::<u8>()));
+                }
+            }
+            4..=8 => code.push(native_opcodes[rng.gen_range(0..native_opcodes.len())]),
+            _ => code.push(rng.gen()),
+        }
+    }
+    code
+}
+
+/// Shrinks `code` to bytecode on which the CPU still diverges, but from which no chunk of bytes
+/// can be removed without the divergence disappearing.
+fn shrink(mut code: Vec<u8>, gas_limit: u64) -> Vec<u8> {
+    let mut chunk_len = code.len();
+    while chunk_len > 0 {
+        let mut shrunk = false;
+        let mut start = 0;
+        while start + chunk_len <= code.len() {
+            let mut candidate = code.clone();
+            candidate.drain(start..start + chunk_len);
+            if diverges(&candidate, gas_limit) {
+                code = candidate;
+                shrunk = true;
+            } else {
+                start += 1;
+            }
+        }
+        if !shrunk {
+            chunk_len /= 2;
+        }
+    }
+    code
+}
+
+/// Compares the CPU against the reference interpreter on `num_cases` random programs generated
+/// from `seed`, and panics with a minimal diverging one, if any.
+fn check_against_reference(seed: u64, num_cases: usize) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    for case in 0..num_cases {
+        let code = random_code(&mut rng);
+        let gas_limit = rng.gen_range(0..500);
+        if diverges(&code, gas_limit) {
+            let code = shrink(code, gas_limit);
+            panic!(
+                "Case {case} of seed {seed:#x} diverges from the reference with gas limit \
+                 {gas_limit} on bytecode {}: expected {:?}, got {:?}",
+                hex::encode(&code),
+                run_reference(&code, gas_limit),
+                run_cpu(&code, gas_limit),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_reference_sanity() -> anyhow::Result<()> {
+    let push1 = get_push_opcode(1);
+    let code = [push1, 1, push1, 2, get_opcode("ADD"), get_opcode("STOP")];
+    let expected = Outcome {
+        halt: Halt::Syscall(5),
+        stack: vec![3.into()],
+        gas_used: 9,
+    };
+    assert_eq!(run_reference(&code, 100), expected);
+    assert_eq!(run_cpu(&code, 100)?, expected);
+
+    // One gas short, the ADD halts exceptionally instead.
+    let expected = Outcome {
+        halt: Halt::Exception(4),
+        stack: vec![1.into(), 2.into()],
+        gas_used: 6,
+    };
+    assert_eq!(run_reference(&code, 8), expected);
+    assert_eq!(run_cpu(&code, 8)?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_cpu_matches_reference() {
+    check_against_reference(0x6feb51b7ec230f25, 256);
+}
+
+/// A longer run on a fresh seed. A failure reports the seed, so that it can be reproduced.
+#[test]
+#[ignore]
+fn test_cpu_matches_reference_random_seed() {
+    let seed = thread_rng().gen();
+    check_against_reference(seed, 4096);
+}
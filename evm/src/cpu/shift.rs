@@ -38,6 +38,12 @@ pub(crate) fn eval_packed<P: PackedField>(
     yield_constr.constraint(is_shift * t);
     yield_constr.constraint(is_shift * high_limbs_sum * high_limbs_are_zero);
 
+    // When the displacement is >= 2^32, the shifting factor must be zero, so that the result
+    // saturates rather than depending on the low limb of the displacement alone.
+    for limb in two_exp.value {
+        yield_constr.constraint(is_shift * (P::ONES - high_limbs_are_zero) * limb);
+    }
+
     // When the shift displacement is < 2^32, constrain the two_exp
     // mem_channel to be the entry corresponding to `displacement` in
     // the shift table lookup (will be zero if displacement >= 256).
@@ -87,6 +93,13 @@ pub(crate) fn eval_ext_circuit<F: RichField + Extendable<D>, const D: usize>(
     let t = builder.mul_many_extension([is_shift, high_limbs_sum, high_limbs_are_zero]);
     yield_constr.constraint(builder, t);
 
+    let high_limbs_are_nonzero = builder.sub_extension(one, high_limbs_are_zero);
+    let filter = builder.mul_extension(is_shift, high_limbs_are_nonzero);
+    for limb in two_exp.value {
+        let t = builder.mul_extension(filter, limb);
+        yield_constr.constraint(builder, t);
+    }
+
     let t = builder.mul_extension(is_shift, two_exp.addr_context);
     yield_constr.constraint(builder, t);
     let t = builder.arithmetic_extension(
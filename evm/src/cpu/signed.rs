@@ -0,0 +1,77 @@
+//! Two's-complement helpers for 256-bit EVM words.
+//!
+//! The signed opcodes (`SDIV`, `SMOD`, `SLT`, `SGT`, `SAR` and `SIGNEXTEND`) aren't constrained
+//! natively by the CPU table, as the kernel implements them as syscalls. These helpers give their
+//! native implementations, e.g. in the interpreter, a single definition of the sign conventions.
+
+use ethereum_types::U256;
+
+/// -1 in two's complement representation consists in all bits set to 1.
+pub(crate) const MINUS_ONE: U256 = U256::MAX;
+
+/// -2^255 in two's complement representation consists in the MSB set to 1.
+pub(crate) const INT_MIN: U256 = U256([0, 0, 0, 1 << 63]);
+
+/// Whether `x` is negative when read as a two's complement integer.
+pub(crate) fn sign_bit(x: U256) -> bool {
+    x.bit(255)
+}
+
+/// Computes `-x` in two's complement, wrapping for `INT_MIN`.
+pub(crate) fn twos_complement_neg(x: U256) -> U256 {
+    (!x).overflowing_add(U256::one()).0
+}
+
+/// The 32 bytes of `x`, most significant first.
+pub(crate) fn byte_decompose(x: U256) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    x.to_big_endian(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use ethereum_types::U256;
+
+    use crate::cpu::signed::{byte_decompose, sign_bit, twos_complement_neg, INT_MIN, MINUS_ONE};
+
+    /// 2^255 - 1 in two's complement representation consists in all bits but the MSB set to 1.
+    const INT_MAX: U256 = U256([u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 1]);
+
+    #[test]
+    fn test_sign_bit() {
+        assert!(!sign_bit(U256::zero()));
+        assert!(!sign_bit(INT_MAX));
+        assert!(sign_bit(INT_MIN));
+        assert!(sign_bit(MINUS_ONE));
+    }
+
+    #[test]
+    fn test_twos_complement_neg() {
+        assert_eq!(twos_complement_neg(U256::zero()), U256::zero());
+        assert_eq!(twos_complement_neg(U256::one()), MINUS_ONE);
+        assert_eq!(twos_complement_neg(MINUS_ONE), U256::one());
+        assert_eq!(twos_complement_neg(INT_MAX), INT_MIN + 1);
+        // -INT_MIN isn't representable, and wraps back to INT_MIN.
+        assert_eq!(twos_complement_neg(INT_MIN), INT_MIN);
+    }
+
+    #[test]
+    fn test_byte_decompose() {
+        assert_eq!(byte_decompose(U256::zero()), [0; 32]);
+        assert_eq!(byte_decompose(MINUS_ONE), [0xff; 32]);
+
+        let mut int_min = [0; 32];
+        int_min[0] = 0x80;
+        assert_eq!(byte_decompose(INT_MIN), int_min);
+
+        let mut int_max = [0xff; 32];
+        int_max[0] = 0x7f;
+        assert_eq!(byte_decompose(INT_MAX), int_max);
+
+        // Recomposing gives back the original word.
+        for x in [U256::zero(), INT_MIN, MINUS_ONE, INT_MAX] {
+            assert_eq!(U256::from_big_endian(&byte_decompose(x)), x);
+        }
+    }
+}
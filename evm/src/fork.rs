@@ -0,0 +1,97 @@
+//! The Ethereum hard forks whose instruction sets the EVM can be configured with.
+
+use ethereum_types::U256;
+use serde::{Deserialize, Serialize};
+
+/// A hard fork, ordered by activation. Only forks from Berlin onwards are supported, as the
+/// kernel implements the access-list gas schedule of EIP-2929.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum Fork {
+    Berlin,
+    London,
+    Paris,
+    Shanghai,
+    #[default]
+    Cancun,
+}
+
+impl Fork {
+    /// The fork that introduced `opcode`, or `None` if it predates every supported fork.
+    fn activation(opcode: u8) -> Option<Self> {
+        match opcode {
+            0x48 => Some(Self::London),        // BASEFEE
+            0x5f => Some(Self::Shanghai),      // PUSH0
+            0x5c..=0x5e => Some(Self::Cancun), // TLOAD, TSTORE, MCOPY
+            _ => None,
+        }
+    }
+
+    /// Whether `opcode` is part of this fork's instruction set, provided it is a valid opcode at
+    /// all.
+    pub fn has_opcode(self, opcode: u8) -> bool {
+        Self::activation(opcode).map_or(true, |fork| fork <= self)
+    }
+
+    /// The bitmask of opcodes introduced by this fork, in the same layout as
+    /// `INVALID_OPCODES_USER`. The kernel derives the opcodes that are not yet active in the
+    /// configured fork from these.
+    pub(crate) fn introduced_opcodes(self) -> U256 {
+        (0..=u8::MAX)
+            .filter(|&opcode| Self::activation(opcode) == Some(self))
+            .fold(U256::zero(), |acc, opcode| acc | (U256::one() << opcode))
+    }
+
+    pub(crate) const COUNT: usize = 5;
+
+    pub(crate) fn all() -> [Self; Self::COUNT] {
+        [
+            Self::Berlin,
+            Self::London,
+            Self::Paris,
+            Self::Shanghai,
+            Self::Cancun,
+        ]
+    }
+
+    /// The name for this fork in our kernel assembly.
+    pub(crate) fn var_name(&self) -> &'static str {
+        match self {
+            Self::Berlin => "FORK_BERLIN",
+            Self::London => "FORK_LONDON",
+            Self::Paris => "FORK_PARIS",
+            Self::Shanghai => "FORK_SHANGHAI",
+            Self::Cancun => "FORK_CANCUN",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethereum_types::U256;
+
+    use crate::fork::Fork;
+
+    #[test]
+    fn test_push0_activation() {
+        assert!(!Fork::Berlin.has_opcode(0x5f));
+        assert!(!Fork::Paris.has_opcode(0x5f));
+        assert!(Fork::Shanghai.has_opcode(0x5f));
+        assert!(Fork::Cancun.has_opcode(0x5f));
+        // Opcodes older than every supported fork are always active.
+        assert!(Fork::Berlin.has_opcode(0x01));
+    }
+
+    #[test]
+    fn test_introduced_opcodes() {
+        assert_eq!(Fork::Berlin.introduced_opcodes(), U256::zero());
+        assert_eq!(Fork::London.introduced_opcodes(), U256::one() << 0x48);
+        assert_eq!(Fork::Paris.introduced_opcodes(), U256::zero());
+        assert_eq!(Fork::Shanghai.introduced_opcodes(), U256::one() << 0x5f);
+        assert_eq!(
+            Fork::Cancun.introduced_opcodes(),
+            (U256::one() << 0x5c) | (U256::one() << 0x5d) | (U256::one() << 0x5e)
+        );
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use eth_trie_utils::partial_trie::HashedPartialTrie;
 use ethereum_types::{Address, BigEndianHash, H256, U256};
+use keccak_hash::keccak;
 use plonky2::field::extension::Extendable;
 use plonky2::field::polynomial::PolynomialValues;
 use plonky2::hash::hash_types::RichField;
@@ -16,12 +17,15 @@ use GlobalMetadata::{
 use crate::all_stark::{AllStark, NUM_TABLES};
 use crate::config::StarkConfig;
 use crate::cpu::bootstrap_kernel::generate_bootstrap_kernel;
+use crate::cpu::cpu_stark::CpuPublicValues;
 use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::fork::Fork;
 use crate::generation::outputs::{get_outputs, GenerationOutputs};
 use crate::generation::state::GenerationState;
 use crate::memory::segments::Segment;
-use crate::proof::{BlockMetadata, PublicValues, TrieRoots};
+use crate::proof::{BlockHashes, BlockMetadata, PublicValues, TrieRoots};
 use crate::witness::memory::{MemoryAddress, MemoryChannel};
 use crate::witness::transition::transition;
 
@@ -47,12 +51,18 @@ pub struct GenerationInputs {
 
     pub block_metadata: BlockMetadata,
 
+    pub block_hashes: BlockHashes,
+
     /// A list of known addresses in the input state trie (which itself doesn't hold addresses,
     /// only state keys). This is only useful for debugging, so that we can return addresses in the
     /// post-state rather than state keys. (See `GenerationOutputs`, and in particular
     /// `AddressOrStateKey`.) If the caller is not interested in the post-state, this can be left
     /// empty.
     pub addresses: Vec<Address>,
+
+    /// The hard fork whose instruction set user code is executed with.
+    #[serde(default)]
+    pub fork: Fork,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -77,7 +87,8 @@ pub struct TrieInputs {
 fn apply_metadata_memops<F: RichField + Extendable<D>, const D: usize>(
     state: &mut GenerationState<F>,
     metadata: &BlockMetadata,
-) {
+    block_hashes: &BlockHashes,
+) -> anyhow::Result<()> {
     let fields = [
         (
             GlobalMetadata::BlockBeneficiary,
@@ -89,20 +100,40 @@ fn apply_metadata_memops<F: RichField + Extendable<D>, const D: usize>(
         (GlobalMetadata::BlockGasLimit, metadata.block_gaslimit),
         (GlobalMetadata::BlockChainId, metadata.block_chain_id),
         (GlobalMetadata::BlockBaseFee, metadata.block_base_fee),
+        (GlobalMetadata::Fork, (state.inputs.fork as u32).into()),
     ];
 
     let channel = MemoryChannel::GeneralPurpose(0);
-    let ops = fields.map(|(field, val)| {
-        mem_write_log(
+    let mut ops = fields
+        .map(|(field, val)| {
+            mem_write_log(
+                channel,
+                MemoryAddress::new(0, Segment::GlobalMetadata, field as usize),
+                state,
+                val,
+            )
+        })
+        .to_vec();
+
+    // Right-align the hashes, so that the parent block's hash is always at index 255.
+    anyhow::ensure!(
+        block_hashes.prev_hashes.len() <= 256,
+        "Expected at most 256 previous block hashes, got {}",
+        block_hashes.prev_hashes.len()
+    );
+    let first_index = 256 - block_hashes.prev_hashes.len();
+    for (i, hash) in block_hashes.prev_hashes.iter().enumerate() {
+        ops.push(mem_write_log(
             channel,
-            MemoryAddress::new(0, Segment::GlobalMetadata, field as usize),
+            MemoryAddress::new(0, Segment::BlockHashes, first_index + i),
             state,
-            val,
-        )
-    });
+            hash.into_uint(),
+        ));
+    }
 
     state.memory.apply_ops(&ops);
     state.traces.memory_ops.extend(ops);
+    Ok(())
 }
 
 pub(crate) fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
@@ -117,7 +148,7 @@ pub(crate) fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
 )> {
     let mut state = GenerationState::<F>::new(inputs.clone(), &KERNEL.code);
 
-    apply_metadata_memops(&mut state, &inputs.block_metadata);
+    apply_metadata_memops(&mut state, &inputs.block_metadata, &inputs.block_hashes)?;
 
     generate_bootstrap_kernel::<F>(&mut state);
 
@@ -147,10 +178,27 @@ pub(crate) fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
         receipts_root: H256::from_uint(&read_metadata(ReceiptTrieRootDigestAfter)),
     };
 
+    let origin = state.memory.get(MemoryAddress::new(
+        0,
+        Segment::TxnFields,
+        NormalizedTxnField::Origin as usize,
+    ));
+    let mut sender = [0; 32];
+    origin.to_big_endian(&mut sender);
+    let cpu = CpuPublicValues {
+        txn_hash: inputs.signed_txns.last().map_or(H256::zero(), keccak),
+        sender: Address::from_slice(&sender[12..]),
+        gas_used: read_metadata(GlobalMetadata::TxnGasUsed),
+        status: !read_metadata(GlobalMetadata::TxnStatus).is_zero(),
+    };
+
     let public_values = PublicValues {
         trie_roots_before,
         trie_roots_after,
         block_metadata: inputs.block_metadata,
+        block_hashes: inputs.block_hashes,
+        fork: inputs.fork,
+        cpu,
     };
 
     let tables = timed!(
@@ -16,6 +16,7 @@ pub mod cross_table_lookup;
 pub mod curve_pairings;
 pub mod extension_tower;
 pub mod fixed_recursive_verifier;
+pub mod fork;
 pub mod generation;
 mod get_challenges;
 pub mod keccak;
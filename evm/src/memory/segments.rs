@@ -57,10 +57,22 @@ pub enum Segment {
     TouchedAddresses = 29,
     /// List of checkpoints for the current context. Length in `ContextMetadata`.
     ContextCheckpoints = 30,
+    /// List of pointers to log entries in `LogsData`. Length in `GlobalMetadata`.
+    Logs = 31,
+    /// Log entries, each laid out as `address, num_topics, topics..., data_len, data...`.
+    LogsData = 32,
+    /// Transient storage as a list of `(address, key, value)` triples. Cleared at the start of
+    /// each transaction. Length in `GlobalMetadata`.
+    TransientStorage = 33,
+    /// Hashes of the 256 blocks preceding the current one, oldest first.
+    BlockHashes = 34,
+    /// Addresses of the contracts created in the current transaction. Cleared at the start of
+    /// each transaction. Length in `GlobalMetadata`. Note: This array allows duplicates.
+    CreatedContracts = 35,
 }
 
 impl Segment {
-    pub(crate) const COUNT: usize = 31;
+    pub(crate) const COUNT: usize = 36;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -95,6 +107,11 @@ impl Segment {
             Self::JournalCheckpoints,
             Self::TouchedAddresses,
             Self::ContextCheckpoints,
+            Self::Logs,
+            Self::LogsData,
+            Self::TransientStorage,
+            Self::BlockHashes,
+            Self::CreatedContracts,
         ]
     }
 
@@ -132,6 +149,11 @@ impl Segment {
             Segment::JournalCheckpoints => "SEGMENT_JOURNAL_CHECKPOINTS",
             Segment::TouchedAddresses => "SEGMENT_TOUCHED_ADDRESSES",
             Segment::ContextCheckpoints => "SEGMENT_CONTEXT_CHECKPOINTS",
+            Segment::Logs => "SEGMENT_LOGS",
+            Segment::LogsData => "SEGMENT_LOGS_DATA",
+            Segment::TransientStorage => "SEGMENT_TRANSIENT_STORAGE",
+            Segment::BlockHashes => "SEGMENT_BLOCK_HASHES",
+            Segment::CreatedContracts => "SEGMENT_CREATED_CONTRACTS",
         }
     }
 
@@ -169,6 +191,11 @@ impl Segment {
             Segment::JournalCheckpoints => 256,
             Segment::TouchedAddresses => 256,
             Segment::ContextCheckpoints => 256,
+            Segment::Logs => 256,
+            Segment::LogsData => 256,
+            Segment::TransientStorage => 256,
+            Segment::BlockHashes => 256,
+            Segment::CreatedContracts => 256,
         }
     }
 }
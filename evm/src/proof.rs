@@ -1,8 +1,13 @@
 use ethereum_types::{Address, H256, U256};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::polynomial::PolynomialCoeffs;
 use plonky2::fri::oracle::PolynomialBatch;
-use plonky2::fri::proof::{FriChallenges, FriChallengesTarget, FriProof, FriProofTarget};
+use plonky2::fri::proof::{
+    FriChallenges, FriChallengesTarget, FriInitialTreeProof, FriProof, FriProofTarget,
+    FriQueryRound, FriQueryStep,
+};
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
@@ -10,13 +15,15 @@ use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
-use plonky2::plonk::config::{GenericConfig, Hasher};
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::plonk::config::{GenericConfig, Hasher, PoseidonGoldilocksConfig};
+use plonky2::util::serialization::{Buffer, IoError, IoResult, Read, Remaining, Write};
 use plonky2_maybe_rayon::*;
 use serde::{Deserialize, Serialize};
 
 use crate::all_stark::NUM_TABLES;
 use crate::config::StarkConfig;
+use crate::cpu::cpu_stark::CpuPublicValues;
+use crate::fork::Fork;
 use crate::permutation::GrandProductChallengeSet;
 
 /// A STARK proof for each table, plus some metadata used to create recursive wrapper proofs.
@@ -27,6 +34,9 @@ pub struct AllProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, co
     pub public_values: PublicValues,
 }
 
+/// An `AllProof` over the Goldilocks field with Poseidon, as created by `prove_evm`.
+pub type EvmProof = AllProof<GoldilocksField, PoseidonGoldilocksConfig, 2>;
+
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> AllProof<F, C, D> {
     pub fn degree_bits(&self, config: &StarkConfig) -> [usize; NUM_TABLES] {
         core::array::from_fn(|i| self.stark_proofs[i].proof.recover_degree_bits(config))
@@ -47,21 +57,26 @@ pub(crate) struct AllChallengerState<F: RichField + Extendable<D>, H: Hasher<F>,
 }
 
 /// Memory values which are public.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct PublicValues {
     pub trie_roots_before: TrieRoots,
     pub trie_roots_after: TrieRoots,
     pub block_metadata: BlockMetadata,
+    pub block_hashes: BlockHashes,
+    /// The fork whose instruction set and semantics the kernel executed with. This is unverified:
+    /// the kernel reads it from `GlobalMetadata::Fork`, which nothing yet binds to this value.
+    pub fork: Fork,
+    pub cpu: CpuPublicValues,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TrieRoots {
     pub state_root: H256,
     pub transactions_root: H256,
     pub receipts_root: H256,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct BlockMetadata {
     pub block_beneficiary: Address,
     pub block_timestamp: U256,
@@ -72,6 +87,13 @@ pub struct BlockMetadata {
     pub block_base_fee: U256,
 }
 
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BlockHashes {
+    /// The hashes of up to 256 blocks preceding the current one, ending with its parent. These are
+    /// the values available to `BLOCKHASH`.
+    pub prev_hashes: Vec<H256>,
+}
+
 /// Memory values which are public.
 /// Note: All the larger integers are encoded with 32-bit limbs in little-endian order.
 pub struct PublicValuesTarget {
@@ -136,6 +158,176 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> S
     pub fn num_ctl_zs(&self) -> usize {
         self.openings.ctl_zs_last.len()
     }
+
+    /// Serializes the proof, prefixed with `STARK_PROOF_FORMAT_VERSION`. Field elements are
+    /// written in canonical form as little-endian `u64`s, so the encoding is deterministic.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_u32(STARK_PROOF_FORMAT_VERSION)
+            .expect("Writing to a byte-vector cannot fail.");
+        self.to_buffer(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes a proof written by `to_bytes`. Fails on a version mismatch, or if `bytes` is
+    /// truncated or has trailing data.
+    pub fn from_bytes(bytes: Vec<u8>) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        if buffer.read_u32()? != STARK_PROOF_FORMAT_VERSION {
+            return Err(IoError);
+        }
+        let proof = Self::from_buffer(&mut buffer)?;
+        if buffer.remaining() != 0 {
+            return Err(IoError);
+        }
+        Ok(proof)
+    }
+
+    /// Unlike plonky2's proofs, a STARK proof has no common data describing its shape, so every
+    /// vector is prefixed with its length.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        write_merkle_cap(buffer, &self.trace_cap)?;
+        write_merkle_cap(buffer, &self.permutation_ctl_zs_cap)?;
+        write_merkle_cap(buffer, &self.quotient_polys_cap)?;
+        self.openings.to_buffer(buffer)?;
+        write_fri_proof::<F, C, D>(buffer, &self.opening_proof)
+    }
+
+    pub fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+        let trace_cap = read_merkle_cap(buffer)?;
+        let permutation_ctl_zs_cap = read_merkle_cap(buffer)?;
+        let quotient_polys_cap = read_merkle_cap(buffer)?;
+        let openings = StarkOpeningSet::from_buffer(buffer)?;
+        let opening_proof = read_fri_proof::<F, C, D>(buffer)?;
+
+        Ok(Self {
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+}
+
+/// The version of the encoding used by `StarkProof::to_bytes`, to be bumped whenever the format
+/// changes so that stale proofs are rejected rather than misread.
+pub const STARK_PROOF_FORMAT_VERSION: u32 = 1;
+
+fn write_field_vec<F: RichField>(buffer: &mut Vec<u8>, v: &[F]) -> IoResult<()> {
+    buffer.write_usize(v.len())?;
+    buffer.write_field_vec(v)
+}
+
+fn read_field_vec<F: RichField>(buffer: &mut Buffer) -> IoResult<Vec<F>> {
+    let len = buffer.read_usize()?;
+    buffer.read_field_vec(len)
+}
+
+fn write_field_ext_vec<F: RichField + Extendable<D>, const D: usize>(
+    buffer: &mut Vec<u8>,
+    v: &[F::Extension],
+) -> IoResult<()> {
+    buffer.write_usize(v.len())?;
+    buffer.write_field_ext_vec::<F, D>(v)
+}
+
+fn read_field_ext_vec<F: RichField + Extendable<D>, const D: usize>(
+    buffer: &mut Buffer,
+) -> IoResult<Vec<F::Extension>> {
+    let len = buffer.read_usize()?;
+    buffer.read_field_ext_vec::<F, D>(len)
+}
+
+fn write_merkle_cap<F: RichField, H: Hasher<F>>(
+    buffer: &mut Vec<u8>,
+    cap: &MerkleCap<F, H>,
+) -> IoResult<()> {
+    buffer.write_usize(cap.0.len())?;
+    buffer.write_merkle_cap(cap)
+}
+
+fn read_merkle_cap<F: RichField, H: Hasher<F>>(buffer: &mut Buffer) -> IoResult<MerkleCap<F, H>> {
+    let len = buffer.read_usize()?;
+    Ok(MerkleCap(buffer.read_hash_vec::<F, H>(len)?))
+}
+
+fn write_fri_proof<F, C, const D: usize>(
+    buffer: &mut Vec<u8>,
+    proof: &FriProof<F, C::Hasher, D>,
+) -> IoResult<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    buffer.write_usize(proof.commit_phase_merkle_caps.len())?;
+    for cap in &proof.commit_phase_merkle_caps {
+        write_merkle_cap(buffer, cap)?;
+    }
+    buffer.write_usize(proof.query_round_proofs.len())?;
+    for round in &proof.query_round_proofs {
+        let evals_proofs = &round.initial_trees_proof.evals_proofs;
+        buffer.write_usize(evals_proofs.len())?;
+        for (evals, merkle_proof) in evals_proofs {
+            write_field_vec(buffer, evals)?;
+            buffer.write_merkle_proof(merkle_proof)?;
+        }
+        buffer.write_usize(round.steps.len())?;
+        for step in &round.steps {
+            write_field_ext_vec::<F, D>(buffer, &step.evals)?;
+            buffer.write_merkle_proof(&step.merkle_proof)?;
+        }
+    }
+    write_field_ext_vec::<F, D>(buffer, &proof.final_poly.coeffs)?;
+    buffer.write_field(proof.pow_witness)
+}
+
+fn read_fri_proof<F, C, const D: usize>(buffer: &mut Buffer) -> IoResult<FriProof<F, C::Hasher, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    // Lengths aren't trusted for allocations: a truncated buffer must fail when reading, not
+    // when reserving memory for a bogus length.
+    let num_caps = buffer.read_usize()?;
+    let commit_phase_merkle_caps = (0..num_caps)
+        .map(|_| read_merkle_cap(buffer))
+        .collect::<IoResult<Vec<_>>>()?;
+    let num_rounds = buffer.read_usize()?;
+    let query_round_proofs = (0..num_rounds)
+        .map(|_| -> IoResult<_> {
+            let num_trees = buffer.read_usize()?;
+            let evals_proofs = (0..num_trees)
+                .map(|_| -> IoResult<_> {
+                    Ok((read_field_vec(buffer)?, buffer.read_merkle_proof()?))
+                })
+                .collect::<IoResult<Vec<_>>>()?;
+            let num_steps = buffer.read_usize()?;
+            let steps = (0..num_steps)
+                .map(|_| -> IoResult<_> {
+                    Ok(FriQueryStep {
+                        evals: read_field_ext_vec::<F, D>(buffer)?,
+                        merkle_proof: buffer.read_merkle_proof()?,
+                    })
+                })
+                .collect::<IoResult<Vec<_>>>()?;
+            Ok(FriQueryRound {
+                initial_trees_proof: FriInitialTreeProof { evals_proofs },
+                steps,
+            })
+        })
+        .collect::<IoResult<Vec<_>>>()?;
+    let final_poly = PolynomialCoeffs::new(read_field_ext_vec::<F, D>(buffer)?);
+    let pow_witness = buffer.read_field()?;
+
+    Ok(FriProof {
+        commit_phase_merkle_caps,
+        query_round_proofs,
+        final_poly,
+        pow_witness,
+    })
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -258,6 +450,34 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
         }
     }
 
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        write_field_ext_vec::<F, D>(buffer, &self.local_values)?;
+        write_field_ext_vec::<F, D>(buffer, &self.next_values)?;
+        write_field_ext_vec::<F, D>(buffer, &self.permutation_ctl_zs)?;
+        write_field_ext_vec::<F, D>(buffer, &self.permutation_ctl_zs_next)?;
+        write_field_vec(buffer, &self.ctl_zs_last)?;
+        write_field_ext_vec::<F, D>(buffer, &self.quotient_polys)?;
+        Ok(())
+    }
+
+    pub fn from_buffer(buffer: &mut Buffer) -> IoResult<Self> {
+        let local_values = read_field_ext_vec::<F, D>(buffer)?;
+        let next_values = read_field_ext_vec::<F, D>(buffer)?;
+        let permutation_ctl_zs = read_field_ext_vec::<F, D>(buffer)?;
+        let permutation_ctl_zs_next = read_field_ext_vec::<F, D>(buffer)?;
+        let ctl_zs_last = read_field_vec(buffer)?;
+        let quotient_polys = read_field_ext_vec::<F, D>(buffer)?;
+
+        Ok(Self {
+            local_values,
+            next_values,
+            permutation_ctl_zs,
+            permutation_ctl_zs_next,
+            ctl_zs_last,
+            quotient_polys,
+        })
+    }
+
     pub(crate) fn to_fri_openings(&self) -> FriOpenings<F, D> {
         let zeta_batch = FriOpeningBatch {
             values: self
@@ -364,3 +584,223 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use eth_trie_utils::partial_trie::HashedPartialTrie;
+    use ethereum_types::U256;
+    use keccak_hash::keccak;
+    use plonky2::field::extension::Extendable;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
+    use plonky2::field::types::{Field, Field64, Sample};
+    use plonky2::fri::oracle::PolynomialBatch;
+    use plonky2::fri::proof::{FriInitialTreeProof, FriProof, FriQueryRound, FriQueryStep};
+    use plonky2::hash::hash_types::HashOut;
+    use plonky2::hash::merkle_proofs::MerkleProof;
+    use plonky2::hash::merkle_tree::MerkleCap;
+    use plonky2::iop::challenger::Challenger;
+    use plonky2::plonk::config::PoseidonGoldilocksConfig;
+    use plonky2::util::timing::TimingTree;
+
+    use crate::all_stark::AllStark;
+    use crate::config::StarkConfig;
+    use crate::cross_table_lookup::{CtlCheckVars, CtlData, CtlZData};
+    use crate::generation::{GenerationInputs, TrieInputs};
+    use crate::logic::{LogicStark, Op, Operation};
+    use crate::permutation::GrandProductChallenge;
+    use crate::proof::{StarkOpeningSet, StarkProof, STARK_PROOF_FORMAT_VERSION};
+    use crate::prover::{prove, prove_single_table};
+    use crate::verifier::{verify_proof, verify_stark_proof_with_challenges};
+    use crate::Node;
+
+    type F = GoldilocksField;
+    type FE = <F as Extendable<2>>::Extension;
+    type C = PoseidonGoldilocksConfig;
+    const D: usize = 2;
+
+    /// A proof of arbitrary values, with the shapes of a small CPU proof.
+    fn random_proof() -> StarkProof<F, C, D> {
+        let cap = || MerkleCap((0..4).map(|_| HashOut::rand()).collect());
+        let merkle_proof = || MerkleProof {
+            siblings: (0..6).map(|_| HashOut::rand()).collect(),
+        };
+        let query_round = || FriQueryRound {
+            initial_trees_proof: FriInitialTreeProof {
+                evals_proofs: vec![
+                    (F::rand_vec(30), merkle_proof()),
+                    (F::rand_vec(10), merkle_proof()),
+                    (F::rand_vec(4), merkle_proof()),
+                ],
+            },
+            steps: vec![
+                FriQueryStep {
+                    evals: FE::rand_vec(8),
+                    merkle_proof: merkle_proof(),
+                },
+                FriQueryStep {
+                    evals: FE::rand_vec(4),
+                    merkle_proof: merkle_proof(),
+                },
+            ],
+        };
+        StarkProof {
+            trace_cap: cap(),
+            permutation_ctl_zs_cap: cap(),
+            quotient_polys_cap: cap(),
+            openings: StarkOpeningSet {
+                local_values: FE::rand_vec(30),
+                next_values: FE::rand_vec(30),
+                permutation_ctl_zs: FE::rand_vec(10),
+                permutation_ctl_zs_next: FE::rand_vec(10),
+                ctl_zs_last: F::rand_vec(6),
+                quotient_polys: FE::rand_vec(4),
+            },
+            opening_proof: FriProof {
+                commit_phase_merkle_caps: vec![cap(), cap()],
+                query_round_proofs: (0..3).map(|_| query_round()).collect(),
+                final_poly: PolynomialCoeffs::new(FE::rand_vec(8)),
+                pow_witness: F::rand(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_stark_proof_round_trip() {
+        let proof = random_proof();
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes[..4], STARK_PROOF_FORMAT_VERSION.to_le_bytes());
+
+        let decoded = StarkProof::<F, C, D>::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(decoded.trace_cap, proof.trace_cap);
+        assert_eq!(decoded.openings.local_values, proof.openings.local_values);
+        assert_eq!(decoded.opening_proof, proof.opening_proof);
+        // The encoding is deterministic.
+        assert_eq!(decoded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_stark_proof_rejects_bad_bytes() {
+        let bytes = random_proof().to_bytes();
+
+        // Every truncation fails cleanly, as does trailing data.
+        for len in 0..bytes.len() {
+            assert!(StarkProof::<F, C, D>::from_bytes(bytes[..len].to_vec()).is_err());
+        }
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(StarkProof::<F, C, D>::from_bytes(extended).is_err());
+
+        // So does a proof written in another version of the format.
+        let mut other_version = bytes.clone();
+        other_version[..4].copy_from_slice(&(STARK_PROOF_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(StarkProof::<F, C, D>::from_bytes(other_version).is_err());
+
+        // And so does a field element which isn't in canonical form, here the PoW witness, which
+        // is written last.
+        let mut non_canonical = bytes;
+        let len = non_canonical.len();
+        non_canonical[len - 8..].copy_from_slice(&F::ORDER.to_le_bytes());
+        assert!(StarkProof::<F, C, D>::from_bytes(non_canonical).is_err());
+    }
+
+    #[test]
+    fn test_deserialized_logic_proof_verifies() -> anyhow::Result<()> {
+        let stark = LogicStark::<F, D>::default();
+        let config = StarkConfig::standard_fast_config();
+        let mut timing = TimingTree::new("prove", log::Level::Debug);
+
+        let operations = vec![
+            Operation::new(Op::And, U256::from(0xf0), U256::from(0x3c)),
+            Operation::new(Op::Or, U256::MAX, U256::one()),
+            Operation::new(Op::Xor, U256::from(0xff), U256::from(0x0f)),
+        ];
+        let trace_poly_values = stark.generate_trace(operations, 1 << 8, &mut timing);
+        let trace_commitment = PolynomialBatch::<F, C, D>::from_values(
+            trace_poly_values.clone(),
+            config.fri_config.rate_bits,
+            false,
+            config.fri_config.cap_height,
+            &mut timing,
+            None,
+        );
+
+        // Fake CTL data, whose all-zero Zs satisfy the checks of a lookup without columns.
+        let degree = trace_poly_values[0].len();
+        let no_challenge = GrandProductChallenge {
+            beta: F::ZERO,
+            gamma: F::ZERO,
+        };
+        let ctl_z_data = CtlZData {
+            z: PolynomialValues::zero(degree),
+            challenge: no_challenge,
+            columns: vec![],
+            filter_column: None,
+        };
+        let ctl_data = CtlData {
+            zs_columns: vec![ctl_z_data; config.num_challenges],
+        };
+
+        let proof = prove_single_table(
+            &stark,
+            &config,
+            &trace_poly_values,
+            &trace_commitment,
+            &ctl_data,
+            &mut Challenger::new(),
+            &mut timing,
+        )?
+        .proof;
+        let proof = StarkProof::<F, C, D>::from_bytes(proof.to_bytes())
+            .map_err(|_| anyhow::Error::msg("Failed to decode a STARK proof"))?;
+
+        let challenges = proof.get_challenges(&mut Challenger::new(), false, 0, &config);
+        let no_columns = vec![];
+        let no_filter = None;
+        let ctl_vars = (0..config.num_challenges)
+            .map(|i| CtlCheckVars {
+                local_z: proof.openings.permutation_ctl_zs[i],
+                next_z: proof.openings.permutation_ctl_zs_next[i],
+                challenges: no_challenge,
+                columns: &no_columns,
+                filter_column: &no_filter,
+            })
+            .collect::<Vec<_>>();
+        verify_stark_proof_with_challenges(&stark, &proof, &challenges, &ctl_vars, &config)
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_deserialized_proof_verifies() -> anyhow::Result<()> {
+        let all_stark = AllStark::<F, D>::default();
+        let config = StarkConfig::standard_fast_config();
+
+        // Execute the empty list of transactions.
+        let inputs = GenerationInputs {
+            signed_txns: vec![],
+            tries: TrieInputs {
+                state_trie: HashedPartialTrie::from(Node::Empty),
+                transactions_trie: HashedPartialTrie::from(Node::Empty),
+                receipts_trie: HashedPartialTrie::from(Node::Empty),
+                storage_tries: vec![],
+            },
+            contract_code: HashMap::from([(keccak(vec![]), vec![])]),
+            block_metadata: Default::default(),
+            block_hashes: Default::default(),
+            addresses: vec![],
+            fork: Default::default(),
+        };
+        let mut timing = TimingTree::new("prove", log::Level::Debug);
+        let mut all_proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+
+        // Replace the proof of each table, starting with the CPU's, by its decoded encoding.
+        for stark_proof in all_proof.stark_proofs.iter_mut() {
+            let bytes = stark_proof.proof.to_bytes();
+            stark_proof.proof = StarkProof::from_bytes(bytes)
+                .map_err(|_| anyhow::Error::msg("Failed to decode a STARK proof"))?;
+        }
+        verify_proof(&all_stark, all_proof, &config)
+    }
+}
@@ -36,7 +36,9 @@ use crate::permutation::{
     compute_permutation_z_polys, get_grand_product_challenge_set,
     get_n_grand_product_challenge_sets, GrandProductChallengeSet, PermutationCheckVars,
 };
-use crate::proof::{AllProof, PublicValues, StarkOpeningSet, StarkProof, StarkProofWithMetadata};
+use crate::proof::{
+    AllProof, EvmProof, PublicValues, StarkOpeningSet, StarkProof, StarkProofWithMetadata,
+};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 use crate::vars::StarkEvaluationVars;
@@ -62,6 +64,14 @@ where
     Ok(proof)
 }
 
+/// Generate traces, then create all STARK proofs, with the tables of `AllStark::default()` and the
+/// standard fast config. The proof can be checked with `verify_evm`.
+pub fn prove_evm(inputs: GenerationInputs) -> Result<EvmProof> {
+    let all_stark = AllStark::default();
+    let config = StarkConfig::standard_fast_config();
+    prove(&all_stark, &config, inputs, &mut TimingTree::default())
+}
+
 /// Generate traces, then create all STARK proofs. Returns information about the post-state,
 /// intended for debugging, in addition to the proof.
 pub fn prove_with_outputs<F, C, const D: usize>(
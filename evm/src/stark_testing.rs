@@ -15,7 +15,7 @@ use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer
 use crate::stark::Stark;
 use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
-const WITNESS_SIZE: usize = 1 << 5;
+pub(crate) const WITNESS_SIZE: usize = 1 << 5;
 
 /// Tests that the constraints imposed by the given STARK are low-degree by applying them to random
 /// low-degree witness polynomials.
@@ -26,8 +26,33 @@ where
     [(); S::COLUMNS]:,
 {
     let rate_bits = log2_ceil(stark.constraint_degree() + 1);
+    let degree = constraint_eval_degree::<F, { S::COLUMNS }>(rate_bits, |vars, consumer| {
+        stark.eval_packed_base(vars, consumer)
+    });
+    if let Some(constraint_eval_degree) = degree {
+        let maximum_degree = WITNESS_SIZE * stark.constraint_degree() - 1;
+
+        ensure!(
+            constraint_eval_degree <= maximum_degree,
+            "Expected degrees at most {} * {} - 1 = {}, actual {:?}",
+            WITNESS_SIZE,
+            stark.constraint_degree(),
+            maximum_degree,
+            constraint_eval_degree
+        );
+    }
+
+    Ok(())
+}
 
-    let trace_ldes = random_low_degree_matrix::<F>(S::COLUMNS, rate_bits);
+/// Returns the degree of the combination of the constraints imposed by `eval` on random witness
+/// polynomials of degree `WITNESS_SIZE - 1`, evaluated over an LDE of rate `rate_bits`, or `None`
+/// if `eval` imposes no constraints.
+pub(crate) fn constraint_eval_degree<F: Field, const COLUMNS: usize>(
+    rate_bits: usize,
+    eval: impl Fn(StarkEvaluationVars<F, F, COLUMNS>, &mut ConstraintConsumer<F>),
+) -> Option<usize> {
+    let trace_ldes = random_low_degree_matrix::<F>(COLUMNS, rate_bits);
     let size = trace_ldes.len();
 
     let lagrange_first = PolynomialValues::selector(WITNESS_SIZE, 0).lde(rate_bits);
@@ -53,27 +78,13 @@ where
                 lagrange_first.values[i],
                 lagrange_last.values[i],
             );
-            stark.eval_packed_base(vars, &mut consumer);
+            eval(vars, &mut consumer);
             consumer.accumulators()[0]
         })
         .collect::<Vec<_>>();
 
     let constraint_poly_values = PolynomialValues::new(constraint_evals);
-    if !constraint_poly_values.is_zero() {
-        let constraint_eval_degree = constraint_poly_values.degree();
-        let maximum_degree = WITNESS_SIZE * stark.constraint_degree() - 1;
-
-        ensure!(
-            constraint_eval_degree <= maximum_degree,
-            "Expected degrees at most {} * {} - 1 = {}, actual {:?}",
-            WITNESS_SIZE,
-            stark.constraint_degree(),
-            maximum_degree,
-            constraint_eval_degree
-        );
-    }
-
-    Ok(())
+    (!constraint_poly_values.is_zero()).then(|| constraint_poly_values.degree())
 }
 
 /// Tests that the circuit constraints imposed by the given STARK are coherent with the native constraints.
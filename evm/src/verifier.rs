@@ -20,12 +20,29 @@ use crate::logic::LogicStark;
 use crate::memory::memory_stark::MemoryStark;
 use crate::permutation::PermutationCheckVars;
 use crate::proof::{
-    AllProof, AllProofChallenges, StarkOpeningSet, StarkProof, StarkProofChallenges,
+    AllProof, AllProofChallenges, EvmProof, PublicValues, StarkOpeningSet, StarkProof,
+    StarkProofChallenges,
 };
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 use crate::vars::StarkEvaluationVars;
 
+/// Verify a proof created by `prove_evm`, checking that it claims the given public values.
+///
+/// The public values are only compared with the proof's claim. They aren't yet bound to the
+/// traces, so a valid proof doesn't establish them.
+pub fn verify_evm(proof: EvmProof, public_values: &PublicValues) -> Result<()> {
+    ensure!(
+        proof.public_values == *public_values,
+        "Proof claims public values {:?}, expected {:?}",
+        proof.public_values,
+        public_values
+    );
+    let all_stark = AllStark::default();
+    let config = StarkConfig::standard_fast_config();
+    verify_proof(&all_stark, proof, &config)
+}
+
 pub fn verify_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     all_stark: &AllStark<F, D>,
     all_proof: AllProof<F, C, D>,
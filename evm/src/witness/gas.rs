@@ -1,3 +1,11 @@
+use ethereum_types::U256;
+use plonky2::field::types::Field;
+
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::generation::state::GenerationState;
+use crate::memory::segments::Segment;
+use crate::witness::errors::ProgramError;
+use crate::witness::memory::MemoryAddress;
 use crate::witness::operation::Operation;
 
 const KERNEL_ONLY_INSTR: u64 = 0;
@@ -14,7 +22,7 @@ pub(crate) fn gas_to_charge(op: Operation) -> u64 {
     match op {
         Iszero => G_VERYLOW,
         Not => G_VERYLOW,
-        Syscall(_, _, _) => KERNEL_ONLY_INSTR,
+        Syscall(_) => KERNEL_ONLY_INSTR,
         Eq => G_VERYLOW,
         BinaryLogic(_) => G_VERYLOW,
         BinaryArithmetic(Add) => G_VERYLOW,
@@ -49,3 +57,22 @@ pub(crate) fn gas_to_charge(op: Operation) -> u64 {
         MstoreGeneral => KERNEL_ONLY_INSTR,
     }
 }
+
+/// Fails with `OutOfGas` if charging for `op` would take the current context over its gas limit.
+/// This only covers the static costs charged natively; the dynamic costs of syscalls, such as
+/// memory expansion, are charged by their kernel handlers through `%charge_gas`.
+pub(crate) fn check_gas<F: Field>(
+    state: &GenerationState<F>,
+    op: Operation,
+) -> Result<(), ProgramError> {
+    let gas_limit = state.memory.get(MemoryAddress::new(
+        state.registers.context,
+        Segment::ContextMetadata,
+        ContextMetadata::GasLimit as usize,
+    ));
+    let gas_used = U256::from(state.registers.gas_used) + gas_to_charge(op);
+    if gas_used > gas_limit {
+        return Err(ProgramError::OutOfGas);
+    }
+    Ok(())
+}
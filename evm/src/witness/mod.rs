@@ -3,6 +3,7 @@ mod gas;
 pub(crate) mod memory;
 mod operation;
 pub(crate) mod state;
+mod syscall;
 pub(crate) mod traces;
 pub mod transition;
 pub(crate) mod util;
@@ -16,6 +16,7 @@ use crate::witness::errors::MemoryError::{ContextTooLarge, SegmentTooLarge, Virt
 use crate::witness::errors::ProgramError;
 use crate::witness::errors::ProgramError::MemoryError;
 use crate::witness::memory::{MemoryAddress, MemoryChannel, MemoryOp, MemoryOpKind};
+use crate::witness::syscall::Syscall;
 use crate::witness::util::{
     keccak_sponge_log, mem_read_gp_with_log_and_fill, mem_write_gp_log_and_fill,
     stack_pop_with_log_and_fill, stack_push_log_and_fill,
@@ -28,7 +29,7 @@ pub(crate) enum Operation {
     Not,
     Shl,
     Shr,
-    Syscall(u8, usize, bool), // (syscall number, minimum stack length, increases stack length)
+    Syscall(Syscall),
     Eq,
     BinaryLogic(logic::Op),
     BinaryArithmetic(arithmetic::BinaryOperator),
@@ -460,9 +461,19 @@ fn append_shift<F: Field>(
     } else {
         // The shift constraints still expect the address to be set, even though no read will occur.
         let channel = &mut row.mem_channels[LOOKUP_CHANNEL];
+        channel.is_read = F::ONE;
         channel.addr_context = F::from_canonical_usize(lookup_addr.context);
         channel.addr_segment = F::from_canonical_usize(lookup_addr.segment);
         channel.addr_virtual = F::from_canonical_usize(lookup_addr.virt);
+
+        // Prove that the high limbs of the displacement aren't all zero.
+        let high_limbs_sum_u64 = (input0.0[0] >> 32)
+            + input0.0[1..]
+                .iter()
+                .map(|&limb| ((limb as u32) as u64) + (limb >> 32))
+                .sum::<u64>();
+        let high_limbs_sum = F::from_canonical_u64(high_limbs_sum_u64);
+        row.general.shift_mut().high_limb_sum_inv = high_limbs_sum.inverse();
     }
 
     state.traces.push_memory(log_in0);
@@ -503,9 +514,7 @@ pub(crate) fn generate_shr<F: Field>(
 }
 
 pub(crate) fn generate_syscall<F: Field>(
-    opcode: u8,
-    stack_values_read: usize,
-    stack_len_increased: bool,
+    syscall: Syscall,
     state: &mut GenerationState<F>,
     mut row: CpuColumnsView<F>,
 ) -> Result<(), ProgramError> {
@@ -513,10 +522,10 @@ pub(crate) fn generate_syscall<F: Field>(
         return Err(ProgramError::GasLimitError);
     }
 
-    if state.registers.stack_len < stack_values_read {
+    if state.registers.stack_len < syscall.stack_values_read() {
         return Err(ProgramError::StackUnderflow);
     }
-    if stack_len_increased
+    if syscall.increases_stack_len()
         && !state.registers.is_kernel
         && state.registers.stack_len >= MAX_USER_STACK_SIZE
     {
@@ -525,7 +534,7 @@ pub(crate) fn generate_syscall<F: Field>(
 
     let handler_jumptable_addr = KERNEL.global_labels["syscall_jumptable"];
     let handler_addr_addr =
-        handler_jumptable_addr + (opcode as usize) * (BYTES_PER_OFFSET as usize);
+        handler_jumptable_addr + (syscall.opcode() as usize) * (BYTES_PER_OFFSET as usize);
     assert_eq!(BYTES_PER_OFFSET, 3, "Code below assumes 3 bytes per offset");
     let (handler_addr0, log_in0) = mem_read_gp_with_log_and_fill(
         0,
@@ -684,6 +693,7 @@ pub(crate) fn generate_exception<F: Field>(
         return Err(ProgramError::GasLimitError);
     }
 
+    row.op.exception = F::ONE;
     row.stack_len_bounds_aux = (row.stack_len + F::ONE).inverse();
 
     row.general.exception_mut().exc_code_bits = [
@@ -0,0 +1,219 @@
+//! The instructions that aren't implemented natively, but by the kernel. Executing one of them is
+//! a syscall: the CPU reads the address of its handler from `syscall_jumptable` (indexed by
+//! opcode) and jumps there in kernel mode.
+
+/// An instruction implemented in terms of a syscall. Its discriminant is its opcode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Syscall {
+    Stop = 0x00,
+    Sdiv = 0x05,
+    Smod = 0x07,
+    Exp = 0x0a,
+    Signextend = 0x0b,
+    Slt = 0x12,
+    Sgt = 0x13,
+    Sar = 0x1d,
+    Keccak256 = 0x20,
+    Address = 0x30,
+    Balance = 0x31,
+    Origin = 0x32,
+    Caller = 0x33,
+    Callvalue = 0x34,
+    Calldataload = 0x35,
+    Calldatasize = 0x36,
+    Calldatacopy = 0x37,
+    Codesize = 0x38,
+    Codecopy = 0x39,
+    Gasprice = 0x3a,
+    Extcodesize = 0x3b,
+    Extcodecopy = 0x3c,
+    Returndatasize = 0x3d,
+    Returndatacopy = 0x3e,
+    Extcodehash = 0x3f,
+    Blockhash = 0x40,
+    Coinbase = 0x41,
+    Timestamp = 0x42,
+    Number = 0x43,
+    Prevrandao = 0x44,
+    Gaslimit = 0x45,
+    Chainid = 0x46,
+    Selfbalance = 0x47,
+    Basefee = 0x48,
+    Mload = 0x51,
+    Mstore = 0x52,
+    Mstore8 = 0x53,
+    Sload = 0x54,
+    Sstore = 0x55,
+    Msize = 0x59,
+    Gas = 0x5a,
+    Tload = 0x5c,
+    Tstore = 0x5d,
+    Mcopy = 0x5e,
+    Log0 = 0xa0,
+    Log1 = 0xa1,
+    Log2 = 0xa2,
+    Log3 = 0xa3,
+    Log4 = 0xa4,
+    Create = 0xf0,
+    Call = 0xf1,
+    Callcode = 0xf2,
+    Return = 0xf3,
+    Delegatecall = 0xf4,
+    Create2 = 0xf5,
+    Staticcall = 0xfa,
+    Revert = 0xfd,
+    Selfdestruct = 0xff,
+}
+
+impl Syscall {
+    /// Returns the syscall with the given opcode, or `None` if `opcode` is implemented natively or
+    /// is invalid. In both cases, the jumptable entry for `opcode` points to `panic`.
+    pub(crate) fn from_opcode(opcode: u8) -> Option<Self> {
+        use Syscall::*;
+        Some(match opcode {
+            0x00 => Stop,
+            0x05 => Sdiv,
+            0x07 => Smod,
+            0x0a => Exp,
+            0x0b => Signextend,
+            0x12 => Slt,
+            0x13 => Sgt,
+            0x1d => Sar,
+            0x20 => Keccak256,
+            0x30 => Address,
+            0x31 => Balance,
+            0x32 => Origin,
+            0x33 => Caller,
+            0x34 => Callvalue,
+            0x35 => Calldataload,
+            0x36 => Calldatasize,
+            0x37 => Calldatacopy,
+            0x38 => Codesize,
+            0x39 => Codecopy,
+            0x3a => Gasprice,
+            0x3b => Extcodesize,
+            0x3c => Extcodecopy,
+            0x3d => Returndatasize,
+            0x3e => Returndatacopy,
+            0x3f => Extcodehash,
+            0x40 => Blockhash,
+            0x41 => Coinbase,
+            0x42 => Timestamp,
+            0x43 => Number,
+            0x44 => Prevrandao,
+            0x45 => Gaslimit,
+            0x46 => Chainid,
+            0x47 => Selfbalance,
+            0x48 => Basefee,
+            0x51 => Mload,
+            0x52 => Mstore,
+            0x53 => Mstore8,
+            0x54 => Sload,
+            0x55 => Sstore,
+            0x59 => Msize,
+            0x5a => Gas,
+            0x5c => Tload,
+            0x5d => Tstore,
+            0x5e => Mcopy,
+            0xa0 => Log0,
+            0xa1 => Log1,
+            0xa2 => Log2,
+            0xa3 => Log3,
+            0xa4 => Log4,
+            0xf0 => Create,
+            0xf1 => Call,
+            0xf2 => Callcode,
+            0xf3 => Return,
+            0xf4 => Delegatecall,
+            0xf5 => Create2,
+            0xfa => Staticcall,
+            0xfd => Revert,
+            0xff => Selfdestruct,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn opcode(self) -> u8 {
+        self as u8
+    }
+
+    /// The minimum stack length, i.e. the number of arguments, below which the syscall underflows.
+    pub(crate) fn stack_values_read(self) -> usize {
+        use Syscall::*;
+        match self {
+            Stop | Address | Origin | Caller | Callvalue | Calldatasize | Codesize | Gasprice
+            | Returndatasize | Coinbase | Timestamp | Number | Prevrandao | Gaslimit | Chainid
+            | Selfbalance | Basefee | Msize | Gas => 0,
+            Balance | Calldataload | Extcodesize | Extcodehash | Blockhash | Mload | Sload
+            | Tload | Selfdestruct => 1,
+            Sdiv | Smod | Exp | Signextend | Slt | Sgt | Sar | Keccak256 | Mstore | Mstore8
+            | Sstore | Tstore | Log0 | Return | Revert => 2,
+            Calldatacopy | Codecopy | Returndatacopy | Mcopy | Log1 | Create => 3,
+            Extcodecopy | Log2 | Create2 => 4,
+            Log3 => 5,
+            Log4 | Delegatecall | Staticcall => 6,
+            Call | Callcode => 7,
+        }
+    }
+
+    /// Whether the syscall increases the stack length, and can therefore overflow.
+    pub(crate) fn increases_stack_len(self) -> bool {
+        use Syscall::*;
+        match self {
+            Address | Origin | Caller | Callvalue | Calldatasize | Codesize | Gasprice
+            | Returndatasize | Coinbase | Timestamp | Number | Prevrandao | Gaslimit | Chainid
+            | Selfbalance | Basefee | Msize | Gas => true,
+            Stop | Sdiv | Smod | Exp | Signextend | Slt | Sgt | Sar | Keccak256 | Balance
+            | Calldataload | Calldatacopy | Codecopy | Extcodesize | Extcodecopy
+            | Returndatacopy | Extcodehash | Blockhash | Mload | Mstore | Mstore8 | Sload
+            | Sstore | Tload | Tstore | Mcopy | Log0 | Log1 | Log2 | Log3 | Log4 | Create
+            | Call | Callcode | Return | Delegatecall | Create2 | Staticcall | Revert
+            | Selfdestruct => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cpu::kernel::aggregator::KERNEL;
+    use crate::cpu::kernel::assembler::BYTES_PER_OFFSET;
+    use crate::witness::syscall::Syscall;
+
+    /// The handler that `syscall_jumptable` dispatches `opcode` to.
+    fn jumptable_entry(opcode: u8) -> usize {
+        let start =
+            KERNEL.global_labels["syscall_jumptable"] + opcode as usize * BYTES_PER_OFFSET as usize;
+        KERNEL.code[start..start + BYTES_PER_OFFSET as usize]
+            .iter()
+            .fold(0, |acc, &byte| (acc << 8) + byte as usize)
+    }
+
+    #[test]
+    fn test_syscall_dispatch() {
+        let panic = KERNEL.global_labels["panic"];
+        let mut num_syscalls = 0;
+        for opcode in 0..=u8::MAX {
+            match Syscall::from_opcode(opcode) {
+                Some(syscall) => {
+                    assert_eq!(syscall.opcode(), opcode);
+                    // Each syscall `Foo` is handled by `sys_foo`.
+                    let handler = format!("sys_{syscall:?}").to_lowercase();
+                    assert_eq!(
+                        jumptable_entry(opcode),
+                        KERNEL.global_labels[&handler],
+                        "{syscall:?} isn't dispatched to {handler}"
+                    );
+                    num_syscalls += 1;
+                }
+                // Opcodes outside of the known set can't be dispatched anywhere but `panic`, so
+                // that a syscall there can never be proven.
+                None => assert_eq!(
+                    jumptable_entry(opcode),
+                    panic,
+                    "Opcode {opcode:#04x} isn't a syscall, but doesn't panic"
+                ),
+            }
+        }
+        assert_eq!(num_syscalls, 58);
+    }
+}
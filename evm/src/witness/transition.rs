@@ -5,13 +5,15 @@ use plonky2::field::types::Field;
 use crate::cpu::columns::CpuColumnsView;
 use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::stack_bounds::MAX_USER_STACK_SIZE;
+use crate::fork::Fork;
 use crate::generation::state::GenerationState;
 use crate::memory::segments::Segment;
 use crate::witness::errors::ProgramError;
-use crate::witness::gas::gas_to_charge;
+use crate::witness::gas::{check_gas, gas_to_charge};
 use crate::witness::memory::MemoryAddress;
 use crate::witness::operation::*;
 use crate::witness::state::RegistersState;
+use crate::witness::syscall::Syscall;
 use crate::witness::util::mem_read_code_with_log_and_fill;
 use crate::{arithmetic, logic};
 
@@ -27,24 +29,28 @@ fn read_code_memory<F: Field>(state: &mut GenerationState<F>, row: &mut CpuColum
     opcode
 }
 
-fn decode(registers: RegistersState, opcode: u8) -> Result<Operation, ProgramError> {
+/// Decodes `opcode` under the instruction set of `fork`. Opcodes that `fork` predates are invalid
+/// in user code, but the kernel may still use them.
+fn decode(fork: Fork, registers: RegistersState, opcode: u8) -> Result<Operation, ProgramError> {
+    if !registers.is_kernel && !fork.has_opcode(opcode) {
+        log::warn!("Opcode {} is not active in {:?}", opcode, fork);
+        return Err(ProgramError::InvalidOpcode);
+    }
+    if let Some(syscall) = Syscall::from_opcode(opcode) {
+        return Ok(Operation::Syscall(syscall));
+    }
     match (opcode, registers.is_kernel) {
-        (0x00, _) => Ok(Operation::Syscall(opcode, 0, false)), // STOP
         (0x01, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Add)),
         (0x02, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Mul)),
         (0x03, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Sub)),
         (0x04, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Div)),
-        (0x05, _) => Ok(Operation::Syscall(opcode, 2, false)), // SDIV
         (0x06, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Mod)),
-        (0x07, _) => Ok(Operation::Syscall(opcode, 2, false)), // SMOD
         (0x08, _) => Ok(Operation::TernaryArithmetic(
             arithmetic::TernaryOperator::AddMod,
         )),
         (0x09, _) => Ok(Operation::TernaryArithmetic(
             arithmetic::TernaryOperator::MulMod,
         )),
-        (0x0a, _) => Ok(Operation::Syscall(opcode, 2, false)), // EXP
-        (0x0b, _) => Ok(Operation::Syscall(opcode, 2, false)), // SIGNEXTEND
         (0x0c, true) => Ok(Operation::BinaryArithmetic(
             arithmetic::BinaryOperator::AddFp254,
         )),
@@ -59,8 +65,6 @@ fn decode(registers: RegistersState, opcode: u8) -> Result<Operation, ProgramErr
         )),
         (0x10, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Lt)),
         (0x11, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Gt)),
-        (0x12, _) => Ok(Operation::Syscall(opcode, 2, false)), // SLT
-        (0x13, _) => Ok(Operation::Syscall(opcode, 2, false)), // SGT
         (0x14, _) => Ok(Operation::Eq),
         (0x15, _) => Ok(Operation::Iszero),
         (0x16, _) => Ok(Operation::BinaryLogic(logic::Op::And)),
@@ -72,55 +76,16 @@ fn decode(registers: RegistersState, opcode: u8) -> Result<Operation, ProgramErr
         )),
         (0x1b, _) => Ok(Operation::Shl),
         (0x1c, _) => Ok(Operation::Shr),
-        (0x1d, _) => Ok(Operation::Syscall(opcode, 2, false)), // SAR
-        (0x20, _) => Ok(Operation::Syscall(opcode, 2, false)), // KECCAK256
         (0x21, true) => Ok(Operation::KeccakGeneral),
-        (0x30, _) => Ok(Operation::Syscall(opcode, 0, true)), // ADDRESS
-        (0x31, _) => Ok(Operation::Syscall(opcode, 1, false)), // BALANCE
-        (0x32, _) => Ok(Operation::Syscall(opcode, 0, true)), // ORIGIN
-        (0x33, _) => Ok(Operation::Syscall(opcode, 0, true)), // CALLER
-        (0x34, _) => Ok(Operation::Syscall(opcode, 0, true)), // CALLVALUE
-        (0x35, _) => Ok(Operation::Syscall(opcode, 1, false)), // CALLDATALOAD
-        (0x36, _) => Ok(Operation::Syscall(opcode, 0, true)), // CALLDATASIZE
-        (0x37, _) => Ok(Operation::Syscall(opcode, 3, false)), // CALLDATACOPY
-        (0x38, _) => Ok(Operation::Syscall(opcode, 0, true)), // CODESIZE
-        (0x39, _) => Ok(Operation::Syscall(opcode, 3, false)), // CODECOPY
-        (0x3a, _) => Ok(Operation::Syscall(opcode, 0, true)), // GASPRICE
-        (0x3b, _) => Ok(Operation::Syscall(opcode, 1, false)), // EXTCODESIZE
-        (0x3c, _) => Ok(Operation::Syscall(opcode, 4, false)), // EXTCODECOPY
-        (0x3d, _) => Ok(Operation::Syscall(opcode, 0, true)), // RETURNDATASIZE
-        (0x3e, _) => Ok(Operation::Syscall(opcode, 3, false)), // RETURNDATACOPY
-        (0x3f, _) => Ok(Operation::Syscall(opcode, 1, false)), // EXTCODEHASH
-        (0x40, _) => Ok(Operation::Syscall(opcode, 1, false)), // BLOCKHASH
-        (0x41, _) => Ok(Operation::Syscall(opcode, 0, true)), // COINBASE
-        (0x42, _) => Ok(Operation::Syscall(opcode, 0, true)), // TIMESTAMP
-        (0x43, _) => Ok(Operation::Syscall(opcode, 0, true)), // NUMBER
-        (0x44, _) => Ok(Operation::Syscall(opcode, 0, true)), // DIFFICULTY
-        (0x45, _) => Ok(Operation::Syscall(opcode, 0, true)), // GASLIMIT
-        (0x46, _) => Ok(Operation::Syscall(opcode, 0, true)), // CHAINID
-        (0x47, _) => Ok(Operation::Syscall(opcode, 0, true)), // SELFBALANCE
-        (0x48, _) => Ok(Operation::Syscall(opcode, 0, true)), // BASEFEE
         (0x49, true) => Ok(Operation::ProverInput),
         (0x50, _) => Ok(Operation::Pop),
-        (0x51, _) => Ok(Operation::Syscall(opcode, 1, false)), // MLOAD
-        (0x52, _) => Ok(Operation::Syscall(opcode, 2, false)), // MSTORE
-        (0x53, _) => Ok(Operation::Syscall(opcode, 2, false)), // MSTORE8
-        (0x54, _) => Ok(Operation::Syscall(opcode, 1, false)), // SLOAD
-        (0x55, _) => Ok(Operation::Syscall(opcode, 2, false)), // SSTORE
         (0x56, _) => Ok(Operation::Jump),
         (0x57, _) => Ok(Operation::Jumpi),
         (0x58, _) => Ok(Operation::Pc),
-        (0x59, _) => Ok(Operation::Syscall(opcode, 0, true)), // MSIZE
-        (0x5a, _) => Ok(Operation::Syscall(opcode, 0, true)), // GAS
         (0x5b, _) => Ok(Operation::Jumpdest),
         (0x5f..=0x7f, _) => Ok(Operation::Push(opcode - 0x5f)),
         (0x80..=0x8f, _) => Ok(Operation::Dup(opcode & 0xf)),
         (0x90..=0x9f, _) => Ok(Operation::Swap(opcode & 0xf)),
-        (0xa0, _) => Ok(Operation::Syscall(opcode, 2, false)), // LOG0
-        (0xa1, _) => Ok(Operation::Syscall(opcode, 3, false)), // LOG1
-        (0xa2, _) => Ok(Operation::Syscall(opcode, 4, false)), // LOG2
-        (0xa3, _) => Ok(Operation::Syscall(opcode, 5, false)), // LOG3
-        (0xa4, _) => Ok(Operation::Syscall(opcode, 6, false)), // LOG4
         (0xa5, true) => {
             log::warn!(
                 "Kernel panic at {}",
@@ -128,20 +93,11 @@ fn decode(registers: RegistersState, opcode: u8) -> Result<Operation, ProgramErr
             );
             Err(ProgramError::KernelPanic)
         }
-        (0xf0, _) => Ok(Operation::Syscall(opcode, 3, false)), // CREATE
-        (0xf1, _) => Ok(Operation::Syscall(opcode, 7, false)), // CALL
-        (0xf2, _) => Ok(Operation::Syscall(opcode, 7, false)), // CALLCODE
-        (0xf3, _) => Ok(Operation::Syscall(opcode, 2, false)), // RETURN
-        (0xf4, _) => Ok(Operation::Syscall(opcode, 6, false)), // DELEGATECALL
-        (0xf5, _) => Ok(Operation::Syscall(opcode, 4, false)), // CREATE2
         (0xf6, true) => Ok(Operation::GetContext),
         (0xf7, true) => Ok(Operation::SetContext),
         (0xf9, true) => Ok(Operation::ExitKernel),
-        (0xfa, _) => Ok(Operation::Syscall(opcode, 6, false)), // STATICCALL
         (0xfb, true) => Ok(Operation::MloadGeneral),
         (0xfc, true) => Ok(Operation::MstoreGeneral),
-        (0xfd, _) => Ok(Operation::Syscall(opcode, 2, false)), // REVERT
-        (0xff, _) => Ok(Operation::Syscall(opcode, 1, false)), // SELFDESTRUCT
         _ => {
             log::warn!("Invalid opcode: {}", opcode);
             Err(ProgramError::InvalidOpcode)
@@ -158,7 +114,7 @@ fn fill_op_flag<F: Field>(op: Operation, row: &mut CpuColumnsView<F>) {
         Operation::Swap(_) => &mut flags.swap,
         Operation::Iszero => &mut flags.iszero,
         Operation::Not => &mut flags.not,
-        Operation::Syscall(_, _, _) => &mut flags.syscall,
+        Operation::Syscall(_) => &mut flags.syscall,
         Operation::Eq => &mut flags.eq,
         Operation::BinaryLogic(logic::Op::And) => &mut flags.and,
         Operation::BinaryLogic(logic::Op::Or) => &mut flags.or,
@@ -207,9 +163,7 @@ fn perform_op<F: Field>(
         Operation::Not => generate_not(state, row)?,
         Operation::Shl => generate_shl(state, row)?,
         Operation::Shr => generate_shr(state, row)?,
-        Operation::Syscall(opcode, stack_values_read, stack_len_increased) => {
-            generate_syscall(opcode, stack_values_read, stack_len_increased, state, row)?
-        }
+        Operation::Syscall(syscall) => generate_syscall(syscall, state, row)?,
         Operation::Eq => generate_eq(state, row)?,
         Operation::BinaryLogic(binary_logic_op) => {
             generate_binary_logic_op(binary_logic_op, state, row)?
@@ -231,7 +185,7 @@ fn perform_op<F: Field>(
     };
 
     state.registers.program_counter += match op {
-        Operation::Syscall(_, _, _) | Operation::ExitKernel => 0,
+        Operation::Syscall(_) | Operation::ExitKernel => 0,
         Operation::Push(n) => n as usize + 1,
         Operation::Jump | Operation::Jumpi => 0,
         _ => 1,
@@ -261,7 +215,7 @@ fn base_row<F: Field>(state: &mut GenerationState<F>) -> (CpuColumnsView<F>, u8)
 
 fn try_perform_instruction<F: Field>(state: &mut GenerationState<F>) -> Result<(), ProgramError> {
     let (mut row, opcode) = base_row(state);
-    let op = decode(state.registers, opcode)?;
+    let op = decode(state.inputs.fork, state.registers, opcode)?;
 
     if state.registers.is_kernel {
         log_kernel_instruction(state, op);
@@ -282,6 +236,7 @@ fn try_perform_instruction<F: Field>(state: &mut GenerationState<F>) -> Result<(
             // This is a stack overflow that should have been caught earlier.
             return Err(ProgramError::InterpreterError);
         }
+        check_gas(state, op)?;
     }
 
     perform_op(state, op, row)
@@ -366,3 +321,126 @@ pub(crate) fn transition<F: Field>(state: &mut GenerationState<F>) -> anyhow::Re
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    use crate::constraint_consumer::ConstraintConsumer;
+    use crate::cpu;
+    use crate::cpu::columns::CpuColumnsView;
+    use crate::cpu::cpu_stark::{run_user_code, CpuInputs};
+    use crate::fork::Fork;
+    use crate::witness::errors::ProgramError;
+    use crate::witness::operation::Operation;
+    use crate::witness::state::RegistersState;
+    use crate::witness::transition::{decode, fill_op_flag};
+
+    fn user_registers() -> RegistersState {
+        RegistersState {
+            program_counter: 0,
+            is_kernel: false,
+            stack_len: 0,
+            context: 1,
+            gas_used: 0,
+        }
+    }
+
+    #[test]
+    fn test_decode_push0_by_fork() {
+        assert!(matches!(
+            decode(Fork::Shanghai, user_registers(), 0x5f),
+            Ok(Operation::Push(0))
+        ));
+        assert!(matches!(
+            decode(Fork::Berlin, user_registers(), 0x5f),
+            Err(ProgramError::InvalidOpcode)
+        ));
+        // The kernel isn't bound by the fork's instruction set.
+        assert!(matches!(
+            decode(Fork::Berlin, RegistersState::default(), 0x5f),
+            Ok(Operation::Push(0))
+        ));
+    }
+
+    /// Checks every opcode byte, in every fork and mode. A successful decoding sets exactly one
+    /// operation flag, which is the one that the decoding constraints derive from the opcode bits
+    /// (syscalls aside, which `syscalls` constrains), while an invalid opcode can always take the
+    /// exception path.
+    #[test]
+    fn test_decode_all_opcodes() {
+        type F = GoldilocksField;
+
+        let forks = [
+            Fork::Berlin,
+            Fork::London,
+            Fork::Paris,
+            Fork::Shanghai,
+            Fork::Cancun,
+        ];
+        for fork in forks {
+            for is_kernel in [false, true] {
+                for opcode in 0..=u8::MAX {
+                    let registers = RegistersState {
+                        is_kernel,
+                        ..user_registers()
+                    };
+                    let mut row = CpuColumnsView::<F>::default();
+                    row.is_cpu_cycle = F::ONE;
+                    row.is_kernel_mode = F::from_bool(is_kernel);
+                    row.opcode_bits = array::from_fn(|i| F::from_bool(opcode & (1 << i) != 0));
+                    let mut decoded = row;
+                    cpu::decode::generate(&mut decoded);
+
+                    let context = format!("opcode {opcode:#04x}, {fork:?}, kernel: {is_kernel}");
+                    match decode(fork, registers, opcode) {
+                        Ok(op @ Operation::Syscall(_)) => {
+                            assert!(decoded.op.iter().all(|flag| flag.is_zero()), "{context}");
+                            fill_op_flag(op, &mut row);
+                        }
+                        Ok(op) => {
+                            fill_op_flag(op, &mut row);
+                            assert_eq!(row.op, decoded.op, "{context}");
+                        }
+                        Err(ProgramError::InvalidOpcode) => row.op.exception = F::ONE,
+                        Err(ProgramError::KernelPanic) => {
+                            assert_eq!((opcode, is_kernel), (0xa5, true), "{context}");
+                            continue;
+                        }
+                        Err(e) => panic!("Unexpected error {e:?} for {context}"),
+                    }
+                    assert_eq!(row.op.iter().copied().sum::<F>(), F::ONE, "{context}");
+
+                    let mut consumer =
+                        ConstraintConsumer::new(vec![F::rand()], F::ONE, F::ZERO, F::ZERO);
+                    cpu::decode::eval_packed_generic(&row, &mut consumer);
+                    assert_eq!(consumer.accumulators(), [F::ZERO], "{context}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_push_pc_advance() -> anyhow::Result<()> {
+        type F = GoldilocksField;
+
+        // PUSHn skips its n bytes of immediate data, and running off the end of the code stops.
+        for n in 0..=32 {
+            let mut code = vec![0x5f + n];
+            code.extend(vec![0xaa; n as usize]);
+            let state = run_user_code::<F>(&code, CpuInputs::default())?;
+            let pcs: Vec<_> = state
+                .traces
+                .cpu
+                .iter()
+                .map(|row| row.program_counter)
+                .collect();
+            assert_eq!(pcs, [F::ZERO, F::from_canonical_u8(n + 1)]);
+        }
+
+        Ok(())
+    }
+}
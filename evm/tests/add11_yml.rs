@@ -16,7 +16,7 @@ use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::generation::mpt::AccountRlp;
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
-use plonky2_evm::proof::BlockMetadata;
+use plonky2_evm::proof::{BlockHashes, BlockMetadata};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
@@ -98,7 +98,9 @@ fn add11_yml() -> anyhow::Result<()> {
         tries: tries_before,
         contract_code,
         block_metadata,
+        block_hashes: BlockHashes::default(),
         addresses: vec![],
+        fork: Default::default(),
     };
 
     let mut timing = TimingTree::new("prove", log::Level::Debug);
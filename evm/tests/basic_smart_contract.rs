@@ -17,7 +17,7 @@ use plonky2_evm::config::StarkConfig;
 use plonky2_evm::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
 use plonky2_evm::generation::mpt::AccountRlp;
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
-use plonky2_evm::proof::BlockMetadata;
+use plonky2_evm::proof::{BlockHashes, BlockMetadata};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
@@ -109,7 +109,9 @@ fn test_basic_smart_contract() -> anyhow::Result<()> {
         tries: tries_before,
         contract_code,
         block_metadata,
+        block_hashes: BlockHashes::default(),
         addresses: vec![],
+        fork: Default::default(),
     };
 
     let mut timing = TimingTree::new("prove", log::Level::Debug);
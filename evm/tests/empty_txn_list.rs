@@ -6,6 +6,7 @@ use std::time::Duration;
 
 use env_logger::{try_init_from_env, Env, DEFAULT_FILTER_ENV};
 use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::H256;
 use keccak_hash::keccak;
 use log::info;
 use plonky2::field::goldilocks_field::GoldilocksField;
@@ -16,7 +17,7 @@ use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::fixed_recursive_verifier::AllRecursiveCircuits;
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
-use plonky2_evm::proof::BlockMetadata;
+use plonky2_evm::proof::{BlockHashes, BlockMetadata};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
@@ -58,7 +59,9 @@ fn test_empty_txn_list() -> anyhow::Result<()> {
         },
         contract_code,
         block_metadata,
+        block_hashes: BlockHashes::default(),
         addresses: vec![],
+        fork: Default::default(),
     };
 
     let mut timing = TimingTree::new("prove", log::Level::Debug);
@@ -138,6 +141,36 @@ fn test_empty_txn_list() -> anyhow::Result<()> {
     all_circuits.verify_aggregation(&agg_proof)
 }
 
+/// Only 256 previous block hashes are available to `BLOCKHASH`, so more are rejected.
+#[test]
+fn test_too_many_block_hashes() {
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+
+    let inputs = GenerationInputs {
+        signed_txns: vec![],
+        tries: TrieInputs {
+            state_trie: HashedPartialTrie::from(Node::Empty),
+            transactions_trie: HashedPartialTrie::from(Node::Empty),
+            receipts_trie: HashedPartialTrie::from(Node::Empty),
+            storage_tries: vec![],
+        },
+        contract_code,
+        block_metadata: BlockMetadata::default(),
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::zero(); 257],
+        },
+        addresses: vec![],
+        fork: Default::default(),
+    };
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    assert!(prove::<F, C, D>(&all_stark, &config, inputs, &mut timing).is_err());
+}
+
 fn init_logger() {
     let _ = try_init_from_env(Env::default().filter_or(DEFAULT_FILTER_ENV, "info"));
 }
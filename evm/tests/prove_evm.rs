@@ -0,0 +1,180 @@
+#![allow(clippy::upper_case_acronyms)]
+
+use std::collections::HashMap;
+
+use env_logger::{try_init_from_env, Env, DEFAULT_FILTER_ENV};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, U256};
+use hex_literal::hex;
+use keccak_hash::keccak;
+use plonky2_evm::cpu::cpu_stark::CpuPublicValues;
+use plonky2_evm::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
+use plonky2_evm::fork::Fork;
+use plonky2_evm::generation::mpt::AccountRlp;
+use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::proof::{BlockHashes, BlockMetadata};
+use plonky2_evm::prover::prove_evm;
+use plonky2_evm::verifier::verify_evm;
+use plonky2_evm::Node;
+
+/// Prove and verify a call to a contract which immediately returns nothing.
+#[test]
+#[ignore] // Too slow to run on CI.
+fn test_prove_evm_trivial_return() -> anyhow::Result<()> {
+    init_logger();
+
+    let beneficiary = hex!("deadbeefdeadbeefdeadbeefdeadbeefdeadbeef");
+    let sender = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+    let to = hex!("a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0");
+
+    let beneficiary_nibbles = Nibbles::from_bytes_be(keccak(beneficiary).as_bytes()).unwrap();
+    let sender_nibbles = Nibbles::from_bytes_be(keccak(sender).as_bytes()).unwrap();
+    let to_nibbles = Nibbles::from_bytes_be(keccak(to).as_bytes()).unwrap();
+
+    let push1 = get_push_opcode(1);
+    let return_ = get_opcode("RETURN");
+    let code = [push1, 0, push1, 0, return_];
+    // Returning an empty range of memory doesn't expand it.
+    let code_gas = 3 + 3;
+    let code_hash = keccak(code);
+
+    let beneficiary_account_before = AccountRlp::default();
+    let sender_account_before = AccountRlp {
+        nonce: 5.into(),
+        balance: eth_to_wei(100_000.into()),
+        ..AccountRlp::default()
+    };
+    let to_account_before = AccountRlp {
+        code_hash,
+        ..AccountRlp::default()
+    };
+
+    let state_trie_before = {
+        let mut children = core::array::from_fn(|_| Node::Empty.into());
+        children[sender_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: sender_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&sender_account_before).to_vec(),
+        }
+        .into();
+        children[to_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: to_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&to_account_before).to_vec(),
+        }
+        .into();
+        Node::Branch {
+            children,
+            value: vec![],
+        }
+    }
+    .into();
+
+    let tries_before = TrieInputs {
+        state_trie: state_trie_before,
+        transactions_trie: Node::Empty.into(),
+        receipts_trie: Node::Empty.into(),
+        storage_tries: vec![],
+    };
+
+    // The same transaction as in `basic_smart_contract`: 100 wei and the data 0x4242, from
+    // `sender` to `to`, with a gas price of 10.
+    let txn = hex!("f861050a8255f094a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0648242421ba02c89eb757d9deeb1f5b3859a9d4d679951ef610ac47ad4608dc142beb1b7e313a05af7e9fbab825455d36c36c7f4cfcafbeafa9a77bdff936b52afb36d4fe4bcdd");
+    let value = U256::from(100u32);
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: Address::from(beneficiary),
+        ..BlockMetadata::default()
+    };
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+    contract_code.insert(code_hash, code.to_vec());
+
+    let inputs = GenerationInputs {
+        signed_txns: vec![txn.to_vec()],
+        tries: tries_before,
+        contract_code,
+        block_metadata,
+        block_hashes: BlockHashes::default(),
+        addresses: vec![],
+        fork: Default::default(),
+    };
+
+    let proof = prove_evm(inputs)?;
+
+    let txdata_gas = 2 * 16;
+    let gas_used = 21_000 + code_gas + txdata_gas;
+    let expected_state_trie_after: HashedPartialTrie = {
+        let beneficiary_account_after = AccountRlp {
+            balance: beneficiary_account_before.balance + gas_used * 10,
+            ..beneficiary_account_before
+        };
+        let sender_account_after = AccountRlp {
+            balance: sender_account_before.balance - value - gas_used * 10,
+            nonce: sender_account_before.nonce + 1,
+            ..sender_account_before
+        };
+        let to_account_after = AccountRlp {
+            balance: to_account_before.balance + value,
+            ..to_account_before
+        };
+
+        let mut children = core::array::from_fn(|_| Node::Empty.into());
+        children[beneficiary_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: beneficiary_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&beneficiary_account_after).to_vec(),
+        }
+        .into();
+        children[sender_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: sender_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&sender_account_after).to_vec(),
+        }
+        .into();
+        children[to_nibbles.get_nibble(0) as usize] = Node::Leaf {
+            nibbles: to_nibbles.truncate_n_nibbles_front(1),
+            value: rlp::encode(&to_account_after).to_vec(),
+        }
+        .into();
+        Node::Branch {
+            children,
+            value: vec![],
+        }
+    }
+    .into();
+
+    let mut public_values = proof.public_values.clone();
+    assert_eq!(
+        public_values.trie_roots_after.state_root,
+        expected_state_trie_after.hash()
+    );
+    assert_eq!(
+        public_values.cpu,
+        CpuPublicValues {
+            txn_hash: keccak(txn),
+            sender: Address::from(sender),
+            gas_used: gas_used.into(),
+            status: true,
+        }
+    );
+    verify_evm(proof.clone(), &public_values)?;
+
+    // `verify_evm` rejects values other than those the proof claims, including another fork. This
+    // only compares them with the claim: the circuit doesn't constrain the fork yet.
+    assert_eq!(public_values.fork, Fork::default());
+    public_values.fork = Fork::Shanghai;
+    assert!(verify_evm(proof.clone(), &public_values).is_err());
+    public_values.fork = Fork::default();
+    public_values.cpu.status = false;
+    assert!(verify_evm(proof, &public_values).is_err());
+
+    Ok(())
+}
+
+fn eth_to_wei(eth: U256) -> U256 {
+    // 1 ether = 10^18 wei.
+    eth * U256::from(10).pow(18.into())
+}
+
+fn init_logger() {
+    let _ = try_init_from_env(Env::default().filter_or(DEFAULT_FILTER_ENV, "info"));
+}
@@ -14,9 +14,10 @@ use plonky2::plonk::config::PoseidonGoldilocksConfig;
 use plonky2::util::timing::TimingTree;
 use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
+use plonky2_evm::cpu::cpu_stark::{public_values, CpuPublicValues};
 use plonky2_evm::generation::mpt::AccountRlp;
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
-use plonky2_evm::proof::BlockMetadata;
+use plonky2_evm::proof::{BlockHashes, BlockMetadata};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
@@ -85,17 +86,19 @@ fn test_simple_transfer() -> anyhow::Result<()> {
         tries: tries_before,
         contract_code,
         block_metadata,
+        block_hashes: BlockHashes::default(),
         addresses: vec![],
+        fork: Default::default(),
     };
 
     let mut timing = TimingTree::new("prove", log::Level::Debug);
     let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
     timing.filter(Duration::from_millis(100)).print();
 
-    let expected_state_trie_after: HashedPartialTrie = {
-        let txdata_gas = 2 * 16;
-        let gas_used = 21_000 + txdata_gas;
+    let txdata_gas = 2 * 16;
+    let gas_used = 21_000 + txdata_gas;
 
+    let expected_state_trie_after: HashedPartialTrie = {
         let sender_account_after = AccountRlp {
             balance: sender_account_before.balance - value - gas_used * 10,
             nonce: sender_account_before.nonce + 1,
@@ -128,6 +131,15 @@ fn test_simple_transfer() -> anyhow::Result<()> {
         proof.public_values.trie_roots_after.state_root,
         expected_state_trie_after.hash()
     );
+    assert_eq!(
+        public_values(&proof),
+        CpuPublicValues {
+            txn_hash: keccak(txn),
+            sender: Address::from(sender),
+            gas_used: gas_used.into(),
+            status: true,
+        }
+    );
 
     verify_proof(&all_stark, proof, &config)
 }
@@ -411,8 +411,23 @@ pub(crate) unsafe fn reduce160(x_lo: u128, x_hi: u32) -> GoldilocksField {
 
 #[cfg(test)]
 mod tests {
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::types::{Field, Field64};
     use crate::{test_field_arithmetic, test_prime_field_arithmetic};
 
     test_prime_field_arithmetic!(crate::goldilocks_field::GoldilocksField);
     test_field_arithmetic!(crate::goldilocks_field::GoldilocksField);
+
+    #[test]
+    fn from_canonical_u64_checked() {
+        type F = GoldilocksField;
+        assert_eq!(F::from_canonical_u64_checked(F::ORDER - 1), F::NEG_ONE);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is not below the field characteristic")]
+    fn from_canonical_u64_checked_above_order() {
+        GoldilocksField::from_canonical_u64_checked(u64::MAX);
+    }
 }
@@ -1,4 +1,6 @@
+use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 use core::fmt::{self, Debug, Display, Formatter};
 use core::hash::{Hash, Hasher};
 use core::iter::{Product, Sum};
@@ -6,7 +8,6 @@ use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAss
 
 use itertools::Itertools;
 use num::bigint::BigUint;
-use num::{Integer, One};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{Field, PrimeField, Sample};
@@ -20,6 +21,18 @@ use crate::types::{Field, PrimeField, Sample};
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Secp256K1Base(pub [u64; 4]);
 
+/// The field order `P`, as little-endian 64-bit limbs.
+const ORDER: [u64; 4] = [
+    0xFFFFFFFEFFFFFC2F,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+];
+
+/// `2^256 - P = 2**32 + 2**9 + 2**8 + 2**7 + 2**6 + 2**4 + 1`. Since `2^256 = EPSILON (mod P)`, the
+/// high limbs of a wide value can be folded back down by multiplying them by this small constant.
+const EPSILON: u64 = 0x1000003D1;
+
 fn biguint_from_array(arr: [u64; 4]) -> BigUint {
     BigUint::from_slice(&[
         arr[0] as u32,
@@ -33,23 +46,167 @@ fn biguint_from_array(arr: [u64; 4]) -> BigUint {
     ])
 }
 
+impl Secp256K1Base {
+    /// Returns the limbs of the canonical representative of this element, i.e. the one in `[0, P)`.
+    #[inline]
+    fn to_canonical_limbs(&self) -> [u64; 4] {
+        // The raw value is always below `2^256 < 2P`, so a single subtraction suffices.
+        let (reduced, borrow) = sub_limbs(self.0, ORDER);
+        if borrow {
+            self.0
+        } else {
+            reduced
+        }
+    }
+
+    /// Like `to_canonical_limbs`, but without branching on the value of `self`.
+    #[inline]
+    fn to_canonical_limbs_ct(&self) -> [u64; 4] {
+        let (reduced, borrow) = sub_limbs(self.0, ORDER);
+        // All ones if the raw value was already canonical, all zeros otherwise.
+        let mask = (borrow as u64).wrapping_neg();
+        let mut res = [0; 4];
+        for i in 0..4 {
+            res[i] = (self.0[i] & mask) | (reduced[i] & !mask);
+        }
+        res
+    }
+
+    /// Tests two elements for equality in constant time, i.e. without branching on or exiting early
+    /// based on their values.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        let x = self.to_canonical_limbs_ct();
+        let y = other.to_canonical_limbs_ct();
+        let diff = (0..4).fold(0, |acc, i| acc | (x[i] ^ y[i]));
+        diff == 0
+    }
+
+    /// Reduces a 512-bit value, given as little-endian limbs, modulo `P`.
+    fn reduce(wide: [u64; 8]) -> Self {
+        // Write the input as `lo + 2^256 hi`. Since `2^256 = EPSILON (mod P)`, this is congruent to
+        // `lo + EPSILON hi`, which is less than `2^290`.
+        let mut res = [0u64; 4];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let t = wide[i] as u128 + wide[i + 4] as u128 * EPSILON as u128 + carry;
+            res[i] = t as u64;
+            carry = t >> 64;
+        }
+
+        // Fold the (at most 34-bit) overflow limb back down in the same way.
+        let mut carry = carry * EPSILON as u128;
+        for limb in res.iter_mut() {
+            let t = *limb as u128 + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+        }
+
+        // If that overflowed again, `res` is now tiny, so adding `EPSILON` cannot overflow.
+        if carry != 0 {
+            res = add_limbs(res, [EPSILON, 0, 0, 0]).0;
+        }
+
+        Self(Self(res).to_canonical_limbs())
+    }
+
+    /// Returns `self / 2`.
+    #[inline]
+    fn halve(self) -> Self {
+        let x = self.to_canonical_limbs();
+        if x[0] & 1 == 0 {
+            Self(shr1(x, false))
+        } else {
+            // `x + P` is even, and at most 257 bits long.
+            let (sum, carry) = add_limbs(x, ORDER);
+            Self(shr1(sum, carry))
+        }
+    }
+
+    /// Returns the canonical little-endian 32-byte encoding of this element.
+    pub fn to_bytes_le(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.to_canonical_limbs()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the canonical big-endian 32-byte encoding of this element, as used by Ethereum and
+    /// most secp256k1 tooling.
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Decodes a little-endian 32-byte encoding, returning `None` if the value is not below `P`.
+    pub fn from_bytes_le(bytes: &[u8; 32]) -> Option<Self> {
+        let mut limbs = [0; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        // The value is canonical iff subtracting `P` borrows.
+        sub_limbs(limbs, ORDER).1.then_some(Self(limbs))
+    }
+
+    /// Decodes a big-endian 32-byte encoding, returning `None` if the value is not below `P`.
+    pub fn from_bytes_be(bytes: &[u8; 32]) -> Option<Self> {
+        let mut bytes = *bytes;
+        bytes.reverse();
+        Self::from_bytes_le(&bytes)
+    }
+
+    /// Returns the prime factorization of `P - 1`, the order of the multiplicative group, as
+    /// `(prime, exponent)` pairs in increasing order of primes.
+    pub fn multiplicative_group_factors() -> Vec<(BigUint, u32)> {
+        [
+            "2",
+            "3",
+            "7",
+            "13441",
+            "205115282021455665897114700593932402728804164701536103180137503955397371",
+        ]
+        .into_iter()
+        .map(|q| (BigUint::parse_bytes(q.as_bytes(), 10).unwrap(), 1))
+        .collect()
+    }
+}
+
 impl Default for Secp256K1Base {
     fn default() -> Self {
         Self::ZERO
     }
 }
 
+/// Note that this comparison is not constant-time; use [`Secp256K1Base::ct_eq`] when comparing
+/// secret-dependent values.
 impl PartialEq for Secp256K1Base {
     fn eq(&self, other: &Self) -> bool {
-        self.to_canonical_biguint() == other.to_canonical_biguint()
+        self.to_canonical_limbs() == other.to_canonical_limbs()
     }
 }
 
 impl Eq for Secp256K1Base {}
 
+/// Orders elements by their canonical values, consistently with `Eq`. Like `eq`, this is not
+/// constant-time.
+impl Ord for Secp256K1Base {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Compare limbs from the most significant one down.
+        let (x, y) = (self.to_canonical_limbs(), other.to_canonical_limbs());
+        x.iter().rev().cmp(y.iter().rev())
+    }
+}
+
+impl PartialOrd for Secp256K1Base {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Hash for Secp256K1Base {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.to_canonical_biguint().hash(state)
+        self.to_canonical_limbs().hash(state)
     }
 }
 
@@ -93,7 +250,8 @@ impl Field for Secp256K1Base {
 
     const NONRESIDUE: Self = todo!();
 
-    // Sage: `g = GF(p).multiplicative_generator()`
+    // Sage: `g = GF(p).multiplicative_generator()`. It is checked against the factorization of
+    // `P - 1` given by `multiplicative_group_factors`.
     const MULTIPLICATIVE_GROUP_GENERATOR: Self = Self([5, 0, 0, 0]);
 
     // Sage: `g_2 = g^((p - 1) / 2)`
@@ -115,24 +273,106 @@ impl Field for Secp256K1Base {
         todo!()
     }
 
+    fn exp_u64(&self, power: u64) -> Self {
+        match power {
+            0 => return Self::ONE,
+            1 => return *self,
+            _ => (),
+        }
+
+        // A fixed window of 4 bits, over the table `self^0, ..., self^15`.
+        let mut table = [Self::ONE; 16];
+        let mut current = Self::ONE;
+        for entry in table.iter_mut() {
+            *entry = current;
+            current *= *self;
+        }
+
+        // Go through the 4-bit digits of `power`, most significant first, skipping leading zeros.
+        let num_windows = (64 - power.leading_zeros() as usize + 3) / 4;
+        let mut result = Self::ONE;
+        for i in (0..num_windows).rev() {
+            result = result.exp_power_of_2(4);
+            result *= table[(power >> (4 * i)) as usize & 0xf];
+        }
+        result
+    }
+
     fn try_inverse(&self) -> Option<Self> {
         if self.is_zero() {
             return None;
         }
 
-        // Fermat's Little Theorem
-        Some(self.exp_biguint(&(Self::order() - BigUint::one() - BigUint::one())))
+        // This is the binary extended Euclidean algorithm. Throughout, we maintain the invariants
+        // `x1 * self = u` and `x2 * self = v` (mod P), while `gcd(u, v) = 1`.
+        const ONE_LIMBS: [u64; 4] = [1, 0, 0, 0];
+        let mut u = self.to_canonical_limbs();
+        let mut v = ORDER;
+        let mut x1 = Self::ONE;
+        let mut x2 = Self::ZERO;
+        while u != ONE_LIMBS && v != ONE_LIMBS {
+            while u[0] & 1 == 0 {
+                u = shr1(u, false);
+                x1 = x1.halve();
+            }
+            while v[0] & 1 == 0 {
+                v = shr1(v, false);
+                x2 = x2.halve();
+            }
+            let (diff, borrow) = sub_limbs(u, v);
+            if borrow {
+                v = sub_limbs(v, u).0;
+                x2 -= x1;
+            } else {
+                u = diff;
+                x1 -= x2;
+            }
+        }
+
+        Some(if u == ONE_LIMBS { x1 } else { x2 })
+    }
+
+    fn batch_multiplicative_inverse(x: &[Self]) -> Vec<Self> {
+        // This is Montgomery's trick, except that zeros are skipped, and map to zero rather than
+        // causing a panic.
+        let mut prefix_products = Vec::with_capacity(x.len());
+        let mut product = Self::ONE;
+        for &xi in x {
+            prefix_products.push(product);
+            if xi.is_nonzero() {
+                product *= xi;
+            }
+        }
+
+        // At the start of iteration `i`, `product_inv` is the inverse of the product of all nonzero
+        // `x[j]` with `j <= i`.
+        let mut product_inv = product.inverse();
+        let mut invs = vec![Self::ZERO; x.len()];
+        for i in (0..x.len()).rev() {
+            if x[i].is_nonzero() {
+                invs[i] = product_inv * prefix_products[i];
+                product_inv *= x[i];
+            }
+        }
+        invs
     }
 
     fn from_noncanonical_biguint(val: BigUint) -> Self {
-        Self(
-            val.to_u64_digits()
-                .into_iter()
-                .pad_using(4, |_| 0)
-                .collect::<Vec<_>>()[..]
-                .try_into()
-                .expect("error converting to u64 array"),
-        )
+        // Anything below `2^256 < 2P` is reduced by `to_canonical_limbs`; only wider values need a
+        // division.
+        let val = if val.bits() > 256 {
+            val % Self::order()
+        } else {
+            val
+        };
+        let limbs = val
+            .to_u64_digits()
+            .into_iter()
+            .pad_using(4, |_| 0)
+            .collect::<Vec<_>>()[..]
+            .try_into()
+            .expect("error converting to u64 array");
+        Self(Self(limbs).to_canonical_limbs())
     }
 
     #[inline]
@@ -153,11 +393,17 @@ impl Field for Secp256K1Base {
 
 impl PrimeField for Secp256K1Base {
     fn to_canonical_biguint(&self) -> BigUint {
-        let mut result = biguint_from_array(self.0);
-        if result >= Self::order() {
-            result -= Self::order();
+        biguint_from_array(self.to_canonical_limbs())
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::ZERO);
         }
-        result
+
+        // Since `P = 3 (mod 4)`, a square root of any quadratic residue `x` is `x^((P + 1) / 4)`.
+        let root = self.exp_biguint(&((Self::order() + 1u32) >> 2));
+        (root * root == *self).then_some(root)
     }
 }
 
@@ -166,10 +412,11 @@ impl Neg for Secp256K1Base {
 
     #[inline]
     fn neg(self) -> Self {
-        if self.is_zero() {
+        let x = self.to_canonical_limbs();
+        if x == [0; 4] {
             Self::ZERO
         } else {
-            Self::from_noncanonical_biguint(Self::order() - self.to_canonical_biguint())
+            Self(sub_limbs(ORDER, x).0)
         }
     }
 }
@@ -179,11 +426,14 @@ impl Add for Secp256K1Base {
 
     #[inline]
     fn add(self, rhs: Self) -> Self {
-        let mut result = self.to_canonical_biguint() + rhs.to_canonical_biguint();
-        if result >= Self::order() {
-            result -= Self::order();
+        let (sum, carry) = add_limbs(self.to_canonical_limbs(), rhs.to_canonical_limbs());
+        // Both summands are canonical, so the sum is below `2P` and one subtraction suffices.
+        let (reduced, borrow) = sub_limbs(sum, ORDER);
+        if carry || !borrow {
+            Self(reduced)
+        } else {
+            Self(sum)
         }
-        Self::from_noncanonical_biguint(result)
     }
 }
 
@@ -196,7 +446,16 @@ impl AddAssign for Secp256K1Base {
 
 impl Sum for Secp256K1Base {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        iter.fold(Self::ZERO, |acc, x| acc + x)
+        // Accumulate the raw limbs without reducing, counting the carries out of the low 256 bits
+        // in a fifth limb, and reduce once at the end.
+        let mut lo = [0u64; 4];
+        let mut hi = 0u64;
+        for x in iter {
+            let (sum, carry) = add_limbs(lo, x.0);
+            lo = sum;
+            hi += carry as u64;
+        }
+        Self::reduce([lo[0], lo[1], lo[2], lo[3], hi, 0, 0, 0])
     }
 }
 
@@ -204,9 +463,13 @@ impl Sub for Secp256K1Base {
     type Output = Self;
 
     #[inline]
-    #[allow(clippy::suspicious_arithmetic_impl)]
     fn sub(self, rhs: Self) -> Self {
-        self + -rhs
+        let (diff, borrow) = sub_limbs(self.to_canonical_limbs(), rhs.to_canonical_limbs());
+        if borrow {
+            Self(add_limbs(diff, ORDER).0)
+        } else {
+            Self(diff)
+        }
     }
 }
 
@@ -222,9 +485,18 @@ impl Mul for Secp256K1Base {
 
     #[inline]
     fn mul(self, rhs: Self) -> Self {
-        Self::from_noncanonical_biguint(
-            (self.to_canonical_biguint() * rhs.to_canonical_biguint()).mod_floor(&Self::order()),
-        )
+        // Schoolbook multiplication into a 512-bit buffer.
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = wide[i + j] as u128 + self.0[i] as u128 * rhs.0[j] as u128 + carry;
+                wide[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + 4] = carry as u64;
+        }
+        Self::reduce(wide)
     }
 }
 
@@ -257,9 +529,623 @@ impl DivAssign for Secp256K1Base {
     }
 }
 
+/// `-P^-1 mod 2^64`, the factor which makes the lowest limb vanish in a Montgomery reduction step.
+const MONTGOMERY_N_PRIME: u64 = 0xD838091DD2253531;
+
+/// `R^2 mod P`, where `R = 2^256`, which converts elements into Montgomery form.
+const MONTGOMERY_R2: [u64; 4] = [0x7A2000E90A1, 0x1, 0x0, 0x0];
+
+/// An element of the secp256k1 base field in Montgomery form, i.e. `x` stored as `x R mod P` with
+/// `R = 2^256`.
+///
+/// Converting costs a multiplication each way, but products then reduce with word-by-word
+/// Montgomery reduction, which pays off in long chains of multiplications such as MSMs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Secp256K1BaseMontgomery([u64; 4]);
+
+impl Secp256K1BaseMontgomery {
+    /// One in Montgomery form, i.e. `R mod P = EPSILON`.
+    pub const ONE: Self = Self([EPSILON, 0, 0, 0]);
+
+    /// Converts back to the usual representation.
+    pub fn to_canonical(&self) -> Secp256K1Base {
+        Secp256K1Base(Self::mul_limbs(self.0, [1, 0, 0, 0]))
+    }
+
+    /// Returns `self^2`, computing each cross term once.
+    pub fn square(&self) -> Self {
+        let x = self.0;
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in i + 1..4 {
+                let t = wide[i + j] as u128 + x[i] as u128 * x[j] as u128 + carry;
+                wide[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + 4] = carry as u64;
+        }
+
+        // Double the cross terms, which sum to less than `2^511`, then add the squares.
+        let mut high_bit = 0;
+        for limb in wide.iter_mut() {
+            let new_high_bit = *limb >> 63;
+            *limb = (*limb << 1) | high_bit;
+            high_bit = new_high_bit;
+        }
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let square = x[i] as u128 * x[i] as u128;
+            let t = wide[2 * i] as u128 + (square as u64) as u128 + carry;
+            wide[2 * i] = t as u64;
+            let t = wide[2 * i + 1] as u128 + (square >> 64) + (t >> 64);
+            wide[2 * i + 1] = t as u64;
+            carry = t >> 64;
+        }
+
+        Self(Self::reduce(wide))
+    }
+
+    /// Returns `x y / R mod P` for `x, y < P`, interleaving the multiplication and the reduction
+    /// (the CIOS method).
+    fn mul_limbs(x: [u64; 4], y: [u64; 4]) -> [u64; 4] {
+        let mut t = [0u64; 6];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let s = t[j] as u128 + x[j] as u128 * y[i] as u128 + carry;
+                t[j] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[4] as u128 + carry;
+            t[4] = s as u64;
+            t[5] = (s >> 64) as u64;
+
+            // Add `m P`, which makes the lowest limb vanish, and shift it out.
+            let m = t[0].wrapping_mul(MONTGOMERY_N_PRIME);
+            let mut carry = (t[0] as u128 + m as u128 * ORDER[0] as u128) >> 64;
+            for j in 1..4 {
+                let s = t[j] as u128 + m as u128 * ORDER[j] as u128 + carry;
+                t[j - 1] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[4] as u128 + carry;
+            t[3] = s as u64;
+            t[4] = t[5] + (s >> 64) as u64;
+        }
+        Self::final_subtraction([t[0], t[1], t[2], t[3]], t[4] != 0)
+    }
+
+    /// Returns `wide / R mod P` for `wide < P R`.
+    fn reduce(mut wide: [u64; 8]) -> [u64; 4] {
+        let mut overflow = false;
+        for i in 0..4 {
+            let m = wide[i].wrapping_mul(MONTGOMERY_N_PRIME);
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let s = wide[i + j] as u128 + m as u128 * ORDER[j] as u128 + carry;
+                wide[i + j] = s as u64;
+                carry = s >> 64;
+            }
+            for limb in wide[i + 4..].iter_mut() {
+                let s = *limb as u128 + carry;
+                *limb = s as u64;
+                carry = s >> 64;
+            }
+            overflow |= carry != 0;
+        }
+        Self::final_subtraction([wide[4], wide[5], wide[6], wide[7]], overflow)
+    }
+
+    /// Reduces `x + 2^256 carry`, which must be below `2P`, to `[0, P)`.
+    fn final_subtraction(x: [u64; 4], carry: bool) -> [u64; 4] {
+        let (reduced, borrow) = sub_limbs(x, ORDER);
+        if carry || !borrow {
+            reduced
+        } else {
+            x
+        }
+    }
+}
+
+impl From<Secp256K1Base> for Secp256K1BaseMontgomery {
+    fn from(x: Secp256K1Base) -> Self {
+        Self(Self::mul_limbs(x.to_canonical_limbs(), MONTGOMERY_R2))
+    }
+}
+
+impl Mul for Secp256K1BaseMontgomery {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(Self::mul_limbs(self.0, rhs.0))
+    }
+}
+
+impl MulAssign for Secp256K1BaseMontgomery {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Product for Secp256K1BaseMontgomery {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+/// Computes `x + y`, returning the low 256 bits of the sum and the carry.
+#[inline]
+fn add_limbs(x: [u64; 4], y: [u64; 4]) -> ([u64; 4], bool) {
+    let mut res = [0; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (sum, c0) = x[i].overflowing_add(y[i]);
+        let (sum, c1) = sum.overflowing_add(carry as u64);
+        res[i] = sum;
+        carry = c0 | c1;
+    }
+    (res, carry)
+}
+
+/// Computes `x - y`, returning the difference modulo `2^256` and the borrow.
+#[inline]
+fn sub_limbs(x: [u64; 4], y: [u64; 4]) -> ([u64; 4], bool) {
+    let mut res = [0; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (diff, b0) = x[i].overflowing_sub(y[i]);
+        let (diff, b1) = diff.overflowing_sub(borrow as u64);
+        res[i] = diff;
+        borrow = b0 | b1;
+    }
+    (res, borrow)
+}
+
+/// Shifts `x` right by one bit, shifting `high_bit` in at the top.
+#[inline]
+fn shr1(x: [u64; 4], high_bit: bool) -> [u64; 4] {
+    [
+        (x[0] >> 1) | (x[1] << 63),
+        (x[1] >> 1) | (x[2] << 63),
+        (x[2] >> 1) | (x[3] << 63),
+        (x[3] >> 1) | ((high_bit as u64) << 63),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
+    use num::BigUint;
+
+    use crate::ops::Square;
+    use crate::secp256k1_base::{Secp256K1Base, Secp256K1BaseMontgomery, ORDER};
     use crate::test_field_arithmetic;
+    use crate::types::{Field, PrimeField, Sample};
 
     test_field_arithmetic!(crate::secp256k1_base::Secp256K1Base);
+
+    #[test]
+    fn native_arithmetic_matches_biguint() {
+        type F = Secp256K1Base;
+        let p = F::order();
+
+        for _ in 0..10_000 {
+            let (x, y) = (F::rand(), F::rand());
+            let (a, b) = (x.to_canonical_biguint(), y.to_canonical_biguint());
+            assert_eq!((x * y).to_canonical_biguint(), (&a * &b) % &p);
+            assert_eq!((x + y).to_canonical_biguint(), (&a + &b) % &p);
+            assert_eq!((x - y).to_canonical_biguint(), (&a + &p - &b) % &p);
+            assert_eq!((-x).to_canonical_biguint(), (&p - &a) % &p);
+        }
+    }
+
+    #[test]
+    fn montgomery_arithmetic() {
+        type F = Secp256K1Base;
+        type M = Secp256K1BaseMontgomery;
+
+        for x in [F::ZERO, F::ONE, F::NEG_ONE] {
+            assert_eq!(M::from(x).to_canonical(), x);
+        }
+        assert_eq!(M::ONE, M::from(F::ONE));
+
+        for _ in 0..1000 {
+            let (x, y) = (F::rand(), F::rand());
+            let (mx, my) = (M::from(x), M::from(y));
+            assert_eq!(mx.to_canonical(), x);
+            assert_eq!((mx * my).to_canonical(), x * y);
+            assert_eq!(mx.square(), mx * mx);
+            assert_eq!(mx.square().to_canonical(), x.square());
+        }
+        assert_eq!(
+            (M::from(F::NEG_ONE) * M::from(F::NEG_ONE)).to_canonical(),
+            F::ONE
+        );
+        assert_eq!(M::from(F::NEG_ONE).square().to_canonical(), F::ONE);
+
+        let xs = F::rand_vec(100);
+        let product = xs.iter().map(|&x| M::from(x)).product::<M>();
+        assert_eq!(product.to_canonical(), xs.into_iter().product::<F>());
+    }
+
+    #[test]
+    fn ordering() {
+        type F = Secp256K1Base;
+
+        // `P + 1` is a non-canonical encoding of one.
+        let non_canonical_one = Secp256K1Base([ORDER[0] + 1, ORDER[1], ORDER[2], ORDER[3]]);
+        let high = Secp256K1Base([0, 0, 0, 1]);
+        let mut xs = vec![F::NEG_ONE, high, non_canonical_one, F::TWO, F::ONE, F::ZERO];
+        xs.sort();
+        assert_eq!(xs, [F::ZERO, F::ONE, F::ONE, F::TWO, high, F::NEG_ONE]);
+        assert_eq!(non_canonical_one.cmp(&F::ONE), core::cmp::Ordering::Equal);
+
+        for (x, y) in [(F::rand(), F::rand()), (F::NEG_ONE, F::rand())] {
+            let expected = x.to_canonical_biguint().cmp(&y.to_canonical_biguint());
+            assert_eq!(x.cmp(&y), expected);
+        }
+    }
+
+    #[test]
+    fn sum_matches_fold() {
+        type F = Secp256K1Base;
+
+        assert_eq!(core::iter::empty::<F>().sum::<F>(), F::ZERO);
+
+        // Many large summands, so that the accumulator carries often, and a non-canonical one.
+        let mut xs = F::rand_vec(10_000);
+        xs.extend([F::NEG_ONE; 1000]);
+        xs.push(Secp256K1Base([ORDER[0] + 1, ORDER[1], ORDER[2], ORDER[3]]));
+        let expected = xs.iter().fold(F::ZERO, |acc, &x| acc + x);
+        assert_eq!(xs.iter().copied().sum::<F>(), expected);
+        assert_eq!(xs.iter().copied().sum::<F>().0, expected.0);
+    }
+
+    #[test]
+    fn sum_equal_to_order_is_reduced() {
+        type F = Secp256K1Base;
+
+        assert_eq!((F::ONE + F::NEG_ONE).0, [0; 4]);
+        for x in [
+            F::ONE,
+            F::TWO,
+            F::NEG_ONE,
+            F::from_canonical_u64(u64::MAX),
+            F::rand(),
+        ] {
+            // The raw limbs of `x + (P - x)` must encode zero, not `P`.
+            assert_eq!((x + -x).0, [0; 4]);
+        }
+    }
+
+    #[test]
+    fn multiplicative_group_factors() {
+        type F = Secp256K1Base;
+
+        let factors = F::multiplicative_group_factors();
+        let product = factors.iter().map(|(q, e)| q.pow(*e)).product::<BigUint>();
+        assert_eq!(product, F::order() - 1u32);
+        // The primes are distinct, and small enough ones can be checked by trial division.
+        assert!(factors.windows(2).all(|w| w[0].0 < w[1].0));
+        for (q, _) in &factors[..4] {
+            let q = q.to_u64_digits()[0];
+            assert!((2..q).take_while(|d| d * d <= q).all(|d| q % d != 0));
+        }
+    }
+
+    #[test]
+    fn multiplicative_group_generator() {
+        type F = Secp256K1Base;
+        let p_minus_1 = F::order() - 1u32;
+
+        // `g` generates the whole group iff `g^((P - 1) / q) != 1` for every prime factor `q`.
+        for (q, _) in F::multiplicative_group_factors() {
+            let power = &p_minus_1 / q;
+            assert_ne!(
+                F::MULTIPLICATIVE_GROUP_GENERATOR.exp_biguint(&power),
+                F::ONE
+            );
+        }
+    }
+
+    #[test]
+    fn power_of_two_generator() {
+        type F = Secp256K1Base;
+
+        let power = F::order() >> F::TWO_ADICITY;
+        assert_eq!(
+            F::MULTIPLICATIVE_GROUP_GENERATOR.exp_biguint(&power),
+            F::POWER_OF_TWO_GENERATOR
+        );
+        assert_eq!(
+            F::generator_order(F::POWER_OF_TWO_GENERATOR),
+            1 << F::TWO_ADICITY
+        );
+    }
+
+    #[test]
+    fn exp_u64() {
+        type F = Secp256K1Base;
+
+        let x = F::rand();
+        let mut expected = F::ONE;
+        for power in 0..100 {
+            assert_eq!(x.exp_u64(power), expected);
+            expected *= x;
+        }
+
+        assert_eq!(x.exp_u64(1 << 32), x.exp_power_of_2(32));
+        // `x^(2^64 - 1) = x^(2^64) / x`.
+        assert_eq!(x.exp_u64(u64::MAX), x.exp_power_of_2(64) * x.inverse());
+        assert_eq!(F::ZERO.exp_u64(0), F::ONE);
+        assert_eq!(F::ZERO.exp_u64(u64::MAX), F::ZERO);
+    }
+
+    #[test]
+    fn constants() {
+        type F = Secp256K1Base;
+
+        // The constants are limb literals, so check them against the canonical limbs of their
+        // values.
+        let limbs = |n: BigUint| F::from_noncanonical_biguint(n).0;
+        assert_eq!(F::ZERO.0, limbs(BigUint::from(0u32)));
+        assert_eq!(F::ONE.0, limbs(BigUint::from(1u32)));
+        assert_eq!(F::TWO.0, limbs(BigUint::from(2u32)));
+        assert_eq!(F::NEG_ONE.0, limbs(F::order() - 1u32));
+    }
+
+    #[test]
+    fn characteristic() {
+        type F = Secp256K1Base;
+        assert_eq!(F::characteristic(), F::order());
+
+        // The characteristic doesn't fit in a `u64`, so this exercises the generic code path.
+        for e in [0, 1, 2, 63, 64, 255, 256, 1000] {
+            assert_eq!(F::TWO.exp_u64(e as u64) * F::inverse_2exp(e), F::ONE);
+        }
+    }
+
+    #[test]
+    fn from_canonical_u64_checked() {
+        type F = Secp256K1Base;
+
+        // Every `u64` is below `P`, so this never asserts.
+        assert_eq!(
+            F::from_canonical_u64_checked(u64::MAX).0,
+            [u64::MAX, 0, 0, 0]
+        );
+    }
+
+    /// An RNG which replays a fixed sequence of bytes.
+    struct ReplayRng(Vec<u8>);
+
+    impl rand::RngCore for ReplayRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            assert!(dest.len() <= self.0.len(), "ran out of bytes to replay");
+            dest.copy_from_slice(&self.0[..dest.len()]);
+            self.0.drain(..dest.len());
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sample_rejects_order() {
+        type F = Secp256K1Base;
+
+        // A sampler which accepted `P` itself would return a non-canonical zero, rather than
+        // drawing again and returning 5.
+        let mut bytes = F::order().to_bytes_le();
+        bytes.extend(F::from_canonical_u64(5).to_bytes_le());
+        let mut rng = ReplayRng(bytes);
+        let x = F::sample(&mut rng);
+        assert_eq!(x.0, [5, 0, 0, 0]);
+        assert!(rng.0.is_empty());
+    }
+
+    #[test]
+    fn canonical_biguint_round_trip() {
+        type F = Secp256K1Base;
+
+        for x in [F::ZERO, F::ONE, F::NEG_ONE, F::rand(), F::rand()] {
+            let lifted = x.to_canonical_biguint();
+            assert!(lifted < F::order());
+            assert_eq!(F::from_noncanonical_biguint(lifted), x);
+        }
+    }
+
+    #[test]
+    fn from_noncanonical_biguint() {
+        type F = Secp256K1Base;
+        let p = F::order();
+
+        // Values in `[P, 2^256)` fit in the limbs, but must still be reduced.
+        let x = F::from_noncanonical_biguint(&p + 5u32);
+        assert_eq!(x.0, [5, 0, 0, 0]);
+        assert_eq!(F::from_noncanonical_biguint(p.clone()).0, [0; 4]);
+
+        // Wider values don't fit at all.
+        let wide = (BigUint::from(1u32) << 300) + 7u32;
+        let x = F::from_noncanonical_biguint(wide.clone());
+        assert_eq!(x.to_canonical_biguint(), &wide % &p);
+        assert_eq!(x.to_canonical_limbs(), x.0);
+    }
+
+    #[test]
+    fn sqrt() {
+        type F = Secp256K1Base;
+
+        assert_eq!(F::ZERO.sqrt(), Some(F::ZERO));
+
+        let four = F::from_canonical_u64(4);
+        let root = four.sqrt().unwrap();
+        assert!(root == F::TWO || root == -F::TWO);
+
+        let x = F::rand();
+        let root = x.square().sqrt().unwrap();
+        assert!(root == x || root == -x);
+
+        // The multiplicative group generator and -1 (since `P = 3 (mod 4)`) are non-residues.
+        assert_eq!(F::MULTIPLICATIVE_GROUP_GENERATOR.sqrt(), None);
+        assert_eq!(F::NEG_ONE.sqrt(), None);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        type F = Secp256K1Base;
+
+        for x in [F::ZERO, F::ONE, F::NEG_ONE, F::rand(), F::rand()] {
+            assert_eq!(F::from_bytes_be(&x.to_bytes_be()), Some(x));
+            assert_eq!(F::from_bytes_le(&x.to_bytes_le()), Some(x));
+        }
+
+        let mut one_be = [0; 32];
+        one_be[31] = 1;
+        assert_eq!(F::ONE.to_bytes_be(), one_be);
+
+        // Non-canonical limbs are reduced before encoding.
+        let p_plus_one = Secp256K1Base([ORDER[0] + 1, ORDER[1], ORDER[2], ORDER[3]]);
+        assert_eq!(p_plus_one.to_bytes_be(), one_be);
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_canonical() {
+        type F = Secp256K1Base;
+
+        let p_be = F::order().to_bytes_be();
+        let p_be: [u8; 32] = p_be.try_into().unwrap();
+        assert_eq!(F::from_bytes_be(&p_be), None);
+
+        let mut p_plus_one_be = p_be;
+        p_plus_one_be[31] += 1;
+        assert_eq!(F::from_bytes_be(&p_plus_one_be), None);
+
+        let mut p_minus_one_be = p_be;
+        p_minus_one_be[31] -= 1;
+        assert_eq!(F::from_bytes_be(&p_minus_one_be), Some(F::NEG_ONE));
+    }
+
+    #[test]
+    fn batch_inversion_with_zeros() {
+        type F = Secp256K1Base;
+
+        let xs = [
+            F::rand(),
+            F::ZERO,
+            F::ONE,
+            F::NEG_ONE,
+            F::rand(),
+            F::ZERO,
+            F::rand(),
+        ];
+        let invs = F::batch_multiplicative_inverse(&xs);
+        assert_eq!(invs.len(), xs.len());
+        for (x, inv) in xs.into_iter().zip(invs) {
+            let expected = x.try_inverse().unwrap_or(F::ZERO);
+            assert_eq!(inv, expected);
+        }
+
+        assert_eq!(F::batch_multiplicative_inverse(&[F::ZERO; 3]), [F::ZERO; 3]);
+        assert!(F::batch_multiplicative_inverse(&[]).is_empty());
+    }
+
+    #[test]
+    fn inverse_matches_fermat() {
+        type F = Secp256K1Base;
+        let p_minus_2 = F::order() - 2u32;
+
+        assert_eq!(F::ZERO.try_inverse(), None);
+
+        let edge_cases = [F::ONE, F::TWO, F::NEG_ONE, F::NEG_ONE - F::ONE];
+        let small = (3..100).map(F::from_canonical_u64);
+        let random = (0..100).map(|_| F::rand());
+        for x in edge_cases.into_iter().chain(small).chain(random) {
+            let inv = x.inverse();
+            assert_eq!(x * inv, F::ONE);
+            assert_eq!(inv, x.exp_biguint(&p_minus_2));
+        }
+    }
+
+    #[test]
+    fn sub_is_canonical() {
+        type F = Secp256K1Base;
+        let p = F::order();
+
+        let neg_one = F::ZERO - F::ONE;
+        assert_eq!(neg_one.to_canonical_biguint(), &p - 1u32);
+        assert_eq!(neg_one.0, F::NEG_ONE.0);
+
+        // Non-canonical operands also give canonical differences.
+        let non_canonical_zero = Secp256K1Base(ORDER);
+        let non_canonical_one = Secp256K1Base([ORDER[0] + 1, ORDER[1], ORDER[2], ORDER[3]]);
+        let inputs = [
+            F::ZERO,
+            F::ONE,
+            F::NEG_ONE,
+            non_canonical_zero,
+            non_canonical_one,
+            F::rand(),
+        ];
+        for x in inputs {
+            for y in inputs {
+                let diff = x - y;
+                assert_eq!(diff.to_canonical_limbs(), diff.0);
+                assert_eq!(diff + y, x);
+            }
+        }
+    }
+
+    #[test]
+    fn hash_is_canonical() {
+        type F = Secp256K1Base;
+
+        let five = F::from_canonical_u64(5);
+        let five_plus_p = Secp256K1Base([ORDER[0] + 5, ORDER[1], ORDER[2], ORDER[3]]);
+        assert_eq!(five, five_plus_p);
+
+        let set: HashSet<F> = [five, five_plus_p, F::ONE + F::from_canonical_u64(4)].into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn ct_eq_matches_eq() {
+        type F = Secp256K1Base;
+
+        let x = F::rand();
+        let non_canonical_one = Secp256K1Base([ORDER[0] + 1, ORDER[1], ORDER[2], ORDER[3]]);
+        let inputs = [
+            F::ZERO,
+            F::ONE,
+            F::NEG_ONE,
+            Secp256K1Base(ORDER),
+            non_canonical_one,
+            x,
+            -x,
+            F::rand(),
+        ];
+        for a in inputs {
+            for b in inputs {
+                assert_eq!(a.ct_eq(&b), a == b, "{:?} vs {:?}", a.0, b.0);
+            }
+        }
+    }
 }
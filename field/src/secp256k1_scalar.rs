@@ -11,7 +11,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{Field, PrimeField, Sample};
 
-/// The base field of the secp256k1 elliptic curve.
+/// The scalar field of the secp256k1 elliptic curve, i.e. the field whose order is the order of the
+/// curve's group.
 ///
 /// Its order is
 /// ```ignore
@@ -35,6 +36,49 @@ fn biguint_from_array(arr: [u64; 4]) -> BigUint {
     ])
 }
 
+/// The order of the field, as little-endian 64-bit limbs.
+const ORDER: [u64; 4] = [
+    0xBFD25E8CD0364141,
+    0xBAAEDCE6AF48A03B,
+    0xFFFFFFFFFFFFFFFE,
+    0xFFFFFFFFFFFFFFFF,
+];
+
+impl Secp256K1Scalar {
+    /// Interprets `bytes` as a big-endian integer and reduces it modulo the order, as when
+    /// deriving an ECDSA nonce from HMAC output in RFC 6979. The running time only depends on the
+    /// length of `bytes`, not on their value.
+    pub fn from_bytes_reduce(bytes: &[u8]) -> Self {
+        let mut acc = [0u64; 4];
+        for &byte in bytes {
+            for i in (0..8).rev() {
+                // `acc < n`, so `2 * acc + bit < 2n < 2^257`; `carry` is its 257th bit.
+                let bit = ((byte >> i) & 1) as u64;
+                let carry = acc[3] >> 63;
+                acc = [
+                    (acc[0] << 1) | bit,
+                    (acc[1] << 1) | (acc[0] >> 63),
+                    (acc[2] << 1) | (acc[1] >> 63),
+                    (acc[3] << 1) | (acc[2] >> 63),
+                ];
+
+                let mut borrow = false;
+                let diff: [u64; 4] = core::array::from_fn(|j| {
+                    let (d, b1) = acc[j].overflowing_sub(ORDER[j]);
+                    let (d, b2) = d.overflowing_sub(borrow as u64);
+                    borrow = b1 | b2;
+                    d
+                });
+                // Subtract `n` iff the doubled value is at least `n`, i.e. iff it overflowed or
+                // the subtraction didn't borrow, selecting the result without branching.
+                let mask = 0u64.wrapping_sub(carry | (!borrow as u64));
+                acc = core::array::from_fn(|j| (diff[j] & mask) | (acc[j] & !mask));
+            }
+        }
+        Self(acc)
+    }
+}
+
 impl Default for Secp256K1Scalar {
     fn default() -> Self {
         Self::ZERO
@@ -133,6 +177,11 @@ impl Field for Secp256K1Scalar {
     }
 
     fn from_noncanonical_biguint(val: BigUint) -> Self {
+        let val = if val >= Self::order() {
+            val.mod_floor(&Self::order())
+        } else {
+            val
+        };
         Self(
             val.to_u64_digits()
                 .into_iter()
@@ -267,7 +316,109 @@ impl DivAssign for Secp256K1Scalar {
 
 #[cfg(test)]
 mod tests {
+    use num::BigUint;
+
+    use crate::secp256k1_scalar::{biguint_from_array, Secp256K1Scalar};
     use crate::test_field_arithmetic;
+    use crate::types::Field;
 
     test_field_arithmetic!(crate::secp256k1_scalar::Secp256K1Scalar);
+
+    #[test]
+    fn constants() {
+        type F = Secp256K1Scalar;
+
+        // The constants are limb literals, so check them against the canonical limbs of their
+        // values.
+        let limbs = |n: BigUint| F::from_noncanonical_biguint(n).0;
+        assert_eq!(F::ZERO.0, limbs(BigUint::from(0u32)));
+        assert_eq!(F::ONE.0, limbs(BigUint::from(1u32)));
+        assert_eq!(F::TWO.0, limbs(BigUint::from(2u32)));
+        assert_eq!(F::NEG_ONE.0, limbs(F::order() - 1u32));
+    }
+
+    #[test]
+    fn from_noncanonical_biguint() {
+        type F = Secp256K1Scalar;
+        let n = F::order();
+
+        let x = F::from_noncanonical_biguint(&n + 5u32);
+        assert_eq!(x.0, [5, 0, 0, 0]);
+
+        let wide = (BigUint::from(1u32) << 300) + 7u32;
+        let x = F::from_noncanonical_biguint(wide.clone());
+        assert_eq!(biguint_from_array(x.0), wide % n);
+    }
+
+    #[test]
+    fn from_bytes_reduce() {
+        type F = Secp256K1Scalar;
+        let reduce = |bytes: &[u8]| {
+            let reduced = F::from_bytes_reduce(bytes);
+            assert_eq!(
+                biguint_from_array(reduced.0),
+                BigUint::from_bytes_be(bytes) % F::order()
+            );
+            reduced
+        };
+
+        // A 48-byte input well beyond `n`, as when reducing HMAC-SHA384 output.
+        let bytes: [u8; 48] = core::array::from_fn(|i| 0xff - (i as u8) * 5);
+        reduce(&bytes);
+
+        // `n` itself, and values on either side of it.
+        let order = F::order().to_bytes_be();
+        assert_eq!(reduce(&order), F::ZERO);
+        let order_plus_one = (F::order() + 1u32).to_bytes_be();
+        assert_eq!(reduce(&order_plus_one), F::ONE);
+        let order_minus_one = (F::order() - 1u32).to_bytes_be();
+        assert_eq!(reduce(&order_minus_one), F::NEG_ONE);
+        reduce(&[0xff; 32]);
+
+        assert_eq!(reduce(&[]), F::ZERO);
+    }
+
+    #[test]
+    fn multiplicative_group_generator() {
+        type F = Secp256K1Scalar;
+        let p_minus_1 = F::order() - 1u32;
+
+        // The distinct prime factors of `P - 1`; 2 has multiplicity 6 and the others have
+        // multiplicity one.
+        let factors = [
+            "2",
+            "3",
+            "149",
+            "631",
+            "107361793816595537",
+            "174723607534414371449",
+            "341948486974166000522343609283189",
+        ]
+        .map(|q| BigUint::parse_bytes(q.as_bytes(), 10).unwrap());
+        assert_eq!(factors.iter().product::<BigUint>() << 5, p_minus_1);
+
+        // `g` generates the whole group iff `g^((P - 1) / q) != 1` for every prime factor `q`.
+        for q in factors {
+            let power = &p_minus_1 / q;
+            assert_ne!(
+                F::MULTIPLICATIVE_GROUP_GENERATOR.exp_biguint(&power),
+                F::ONE
+            );
+        }
+    }
+
+    #[test]
+    fn power_of_two_generator() {
+        type F = Secp256K1Scalar;
+
+        let power = F::order() >> F::TWO_ADICITY;
+        assert_eq!(
+            F::MULTIPLICATIVE_GROUP_GENERATOR.exp_biguint(&power),
+            F::POWER_OF_TWO_GENERATOR
+        );
+        assert_eq!(
+            F::generator_order(F::POWER_OF_TWO_GENERATOR),
+            1 << F::TWO_ADICITY
+        );
+    }
 }
@@ -80,8 +80,7 @@ pub trait Field:
     /// The 2-adicity of this field's multiplicative group.
     const TWO_ADICITY: usize;
 
-    /// The field's characteristic and it's 2-adicity.
-    /// Set to `None` when the characteristic doesn't fit in a u64.
+    /// The 2-adicity of `characteristic() - 1`. For prime fields this equals `TWO_ADICITY`.
     const CHARACTERISTIC_TWO_ADICITY: usize;
 
     const NONRESIDUE: Self;
@@ -95,6 +94,9 @@ pub trait Field:
     const BITS: usize;
 
     fn order() -> BigUint;
+
+    /// The characteristic of this field. This is a `BigUint` since it need not fit in a `u64`, as
+    /// is the case for e.g. the secp256k1 fields.
     fn characteristic() -> BigUint;
 
     #[inline]
@@ -315,6 +317,17 @@ pub trait Field:
     // TODO: Should probably be unsafe.
     fn from_canonical_u64(n: u64) -> Self;
 
+    /// Like `from_canonical_u64`, but asserts in debug builds that `n` is below the characteristic,
+    /// so that generic code passing unreduced values is caught in tests. Release builds trust the
+    /// caller, just like `from_canonical_u64`.
+    fn from_canonical_u64_checked(n: u64) -> Self {
+        debug_assert!(
+            BigUint::from(n) < Self::characteristic(),
+            "{n} is not below the field characteristic"
+        );
+        Self::from_canonical_u64(n)
+    }
+
     /// Returns `n`. Assumes that `n` is already in canonical form, i.e. `n < Self::order()`.
     // TODO: Should probably be unsafe.
     fn from_canonical_u32(n: u32) -> Self {
@@ -5,6 +5,7 @@ use plonky2::field::extension::quadratic::QuadraticExtension;
 use plonky2::field::extension::quartic::QuarticExtension;
 use plonky2::field::extension::quintic::QuinticExtension;
 use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::secp256k1_base::Secp256K1Base;
 use plonky2::field::types::Field;
 use tynm::type_name;
 
@@ -171,11 +172,31 @@ pub(crate) fn bench_field<F: Field>(c: &mut Criterion) {
     );
 }
 
+/// Compares `Sum`, which batches reductions, against a naive fold.
+pub(crate) fn bench_sum<F: Field>(c: &mut Criterion) {
+    c.bench_function(&format!("sum-10k<{}>", type_name::<F>()), |b| {
+        b.iter_batched(
+            || F::rand_vec(10_000),
+            |x| x.into_iter().sum::<F>(),
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function(&format!("sum-10k-fold<{}>", type_name::<F>()), |b| {
+        b.iter_batched(
+            || F::rand_vec(10_000),
+            |x| x.into_iter().fold(F::ZERO, |acc, x| acc + x),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     bench_field::<GoldilocksField>(c);
     bench_field::<QuadraticExtension<GoldilocksField>>(c);
     bench_field::<QuarticExtension<GoldilocksField>>(c);
     bench_field::<QuinticExtension<GoldilocksField>>(c);
+    bench_sum::<Secp256K1Base>(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
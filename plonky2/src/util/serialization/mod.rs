@@ -140,7 +140,8 @@ pub trait Read {
         Ok(res)
     }
 
-    /// Reads a element from the field `F` with size less than `2^64` from `self.`
+    /// Reads a element from the field `F` with size less than `2^64` from `self.` Fails if the
+    /// element isn't in canonical form.
     #[inline]
     fn read_field<F>(&mut self) -> IoResult<F>
     where
@@ -148,7 +149,11 @@ pub trait Read {
     {
         let mut buf = [0; size_of::<u64>()];
         self.read_exact(&mut buf)?;
-        Ok(F::from_canonical_u64(u64::from_le_bytes(buf)))
+        let n = u64::from_le_bytes(buf);
+        if n >= F::ORDER {
+            return Err(IoError);
+        }
+        Ok(F::from_canonical_u64(n))
     }
 
     /// Reads a vector of elements from the field `F` from `self`.